@@ -0,0 +1,534 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+
+use crate::gate::{CustomGate, Gate};
+use crate::lookup::{LookupTable, TableId};
+use crate::witness::{Assignment, Source, Witness, WitnessError};
+
+/// Default number of wires per row: a (left), b (right), c (output).
+pub const DEFAULT_NUM_WIRES: usize = 3;
+
+/// Handle to a value in the constraint system.
+///
+/// Every wire that holds the same variable is tied together by the
+/// permutation argument, so reusing a variable is a copy constraint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Variable(usize);
+
+impl Variable {
+    /// Handle to the variable at `index`, e.g. one read from a witness
+    /// file.
+    pub fn new(index: usize) -> Self {
+        Variable(index)
+    }
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Handle to a custom gate registered with a constraint system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CustomGateId(usize);
+
+impl CustomGateId {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Row<F: PrimeField = Fr> {
+    /// Standard arithmetic gate for this row
+    pub gate: Gate<F>,
+    /// Variables on the a, b, c, … wires
+    pub wires: Vec<Variable>,
+    /// Custom gates enabled on this row, with their selector values
+    pub custom: Vec<(CustomGateId, Vec<F>)>,
+    /// Table this row's wires are looked up in
+    pub lookup: Option<TableId>,
+}
+
+/// Circuit builder holding both the gates and the witness values.
+///
+/// Every variable is either an input or records how it is computed from
+/// earlier variables, so a witness for new inputs can be regenerated
+/// without building the circuit again.
+#[derive(Clone, Debug)]
+pub struct ConstraintSystem<F: PrimeField = Fr> {
+    num_wires: usize,
+    values: Vec<F>,
+    sources: Vec<Source<F>>,
+    rows: Vec<Row<F>>,
+    public_inputs: Vec<usize>,
+    custom_gates: Vec<Arc<dyn CustomGate<F>>>,
+    tables: Vec<LookupTable<F>>,
+    zero: Variable,
+    names: BTreeMap<Variable, String>,
+    annotations: BTreeMap<usize, String>,
+}
+
+impl<F: PrimeField> ConstraintSystem<F> {
+    /// Creates an empty three-wire system whose first row pins the zero
+    /// variable.
+    pub fn new() -> Self {
+        Self::with_wires(DEFAULT_NUM_WIRES)
+    }
+
+    /// Creates an empty system with `num_wires` wires per row.
+    ///
+    /// Wires after c enter the standard gate through linear selectors,
+    /// so wider rows fit longer sums and richer custom gates.
+    pub fn with_wires(num_wires: usize) -> Self {
+        assert!(
+            num_wires >= DEFAULT_NUM_WIRES,
+            "A row needs at least {DEFAULT_NUM_WIRES} wires"
+        );
+
+        let zero = Variable(0);
+        ConstraintSystem {
+            num_wires,
+            values: vec![F::zero()],
+            sources: vec![Source::Derived(Arc::new(|_| Ok(F::zero())))],
+            rows: vec![Row {
+                gate: Gate::constant(F::zero()),
+                wires: vec![zero; num_wires],
+                custom: Vec::new(),
+                lookup: None,
+            }],
+            public_inputs: Vec::new(),
+            custom_gates: Vec::new(),
+            tables: Vec::new(),
+            zero,
+            names: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    /// Variable constrained to 0. Fills unused wires.
+    pub fn zero(&self) -> Variable {
+        self.zero
+    }
+
+    pub fn num_wires(&self) -> usize {
+        self.num_wires
+    }
+
+    /// Allocates a private input without constraining it.
+    pub fn alloc(&mut self, value: F) -> Variable {
+        self.values.push(value);
+        self.sources.push(Source::Input);
+        Variable(self.values.len() - 1)
+    }
+
+    /// Allocates a variable computed from earlier ones, without
+    /// constraining it.
+    ///
+    /// The assignment runs now against the current values and again on
+    /// every [`generate_witness`](Self::generate_witness). If it fails now,
+    /// the value is left at zero.
+    pub fn assign(
+        &mut self,
+        assignment: impl Fn(&Assignment<F>) -> Result<F, WitnessError> + Send + Sync + 'static,
+    ) -> Variable {
+        let var = Variable(self.values.len());
+        let value = assignment(&Assignment::new(&self.values, var)).unwrap_or(F::zero());
+        self.values.push(value);
+        self.sources.push(Source::Derived(Arc::new(assignment)));
+        var
+    }
+
+    /// Gives a variable a name for debugging output.
+    pub fn set_name(&mut self, var: Variable, name: impl Into<String>) {
+        self.names.insert(var, name.into());
+    }
+
+    pub fn name(&self, var: Variable) -> Option<&str> {
+        self.names.get(&var).map(String::as_str)
+    }
+
+    /// Name of a variable, or `v` and its index if it has none.
+    pub fn display_name(&self, var: Variable) -> String {
+        self.name(var)
+            .map_or_else(|| format!("v{}", var.index()), str::to_string)
+    }
+
+    /// Attaches a note to a row for debugging output.
+    pub fn annotate(&mut self, row: usize, note: impl Into<String>) {
+        self.annotations.insert(row, note.into());
+    }
+
+    pub fn annotation(&self, row: usize) -> Option<&str> {
+        self.annotations.get(&row).map(String::as_str)
+    }
+
+    /// Runs `build` and annotates every row it adds with `name`. Nested
+    /// regions join their names with `/`, e.g. `hash/sbox`.
+    pub fn region<T>(&mut self, name: &str, build: impl FnOnce(&mut Self) -> T) -> T {
+        let start = self.rows.len();
+        let result = build(self);
+        for row in start..self.rows.len() {
+            let note = match self.annotations.get(&row) {
+                Some(inner) => format!("{name}/{inner}"),
+                None => name.to_string(),
+            };
+            self.annotations.insert(row, note);
+        }
+        result
+    }
+
+    /// Allocates a public input on its own row.
+    pub fn public_input(&mut self, value: F) -> Variable {
+        let var = self.alloc(value);
+        self.expose(var);
+        var
+    }
+
+    /// Appends a row making an existing variable a public input and
+    /// returns its index.
+    pub fn expose(&mut self, var: Variable) -> usize {
+        let row = self.add_gate(Gate::public_input(), &[var]);
+        self.public_inputs.push(row);
+        row
+    }
+
+    /// Allocates a variable constrained to a fixed value.
+    pub fn constant(&mut self, value: F) -> Variable {
+        let var = self.assign(move |_| Ok(value));
+        self.add_gate(Gate::constant(value), &[var]);
+        var
+    }
+
+    pub fn value(&self, var: Variable) -> F {
+        self.values[var.0]
+    }
+
+    /// Overwrites a variable's witness value without touching the rows,
+    /// e.g. to check that a circuit rejects a dishonest witness.
+    pub fn set_value(&mut self, var: Variable, value: F) {
+        self.values[var.0] = value;
+    }
+
+    /// Input variables, public and private, in allocation order.
+    pub fn inputs(&self) -> Vec<Variable> {
+        self.sources
+            .iter()
+            .enumerate()
+            .filter(|(_, source)| matches!(source, Source::Input))
+            .map(|(i, _)| Variable(i))
+            .collect()
+    }
+
+    /// Computes every variable's value from the input values alone, in
+    /// allocation order.
+    pub fn generate_witness(&self, inputs: &Witness<F>) -> Result<Vec<F>, WitnessError> {
+        let mut values = Vec::with_capacity(self.sources.len());
+        for (i, source) in self.sources.iter().enumerate() {
+            let var = Variable(i);
+            let value = match source {
+                Source::Input => inputs.get(var).ok_or(WitnessError::MissingInput(var))?,
+                Source::Derived(assignment) => assignment(&Assignment::new(&values, var))?,
+            };
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Replaces the witness with the one generated from `inputs`.
+    pub fn set_witness(&mut self, inputs: &Witness<F>) -> Result<(), WitnessError> {
+        self.values = self.generate_witness(inputs)?;
+        Ok(())
+    }
+
+    /// Appends a row with a standard gate and returns its index.
+    ///
+    /// Wires left out at the end of `wires` are filled with zero.
+    pub fn add_gate(&mut self, gate: Gate<F>, wires: &[Variable]) -> usize {
+        assert!(
+            gate.q_extra.len() <= self.num_wires - DEFAULT_NUM_WIRES,
+            "Gate has selectors for {} wires, rows have {}",
+            DEFAULT_NUM_WIRES + gate.q_extra.len(),
+            self.num_wires
+        );
+
+        let wires = self.pad_wires(wires);
+        self.rows.push(Row {
+            gate,
+            wires,
+            custom: Vec::new(),
+            lookup: None,
+        });
+        self.rows.len() - 1
+    }
+
+    /// Constrains c = a + b and returns c.
+    pub fn add(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.assign(move |w| Ok(w.get(a)? + w.get(b)?));
+        self.add_gate(Gate::addition(), &[a, b, c]);
+        c
+    }
+
+    /// Constrains c = a · b and returns c.
+    pub fn mul(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.assign(move |w| Ok(w.get(a)? * w.get(b)?));
+        self.add_gate(Gate::multiplication(), &[a, b, c]);
+        c
+    }
+
+    /// Registers a custom gate so rows can enable it.
+    pub fn register_gate(&mut self, gate: impl CustomGate<F> + 'static) -> CustomGateId {
+        self.register_shared_gate(Arc::new(gate))
+    }
+
+    /// Registers a gate that is already shared, e.g. one rebuilt with
+    /// [`builtin`](crate::custom_gates::builtin).
+    pub fn register_shared_gate(&mut self, gate: Arc<dyn CustomGate<F>>) -> CustomGateId {
+        assert!(
+            gate.num_wires() <= self.num_wires,
+            "Custom gate {} reads {} wires, rows have {}",
+            gate.name(),
+            gate.num_wires(),
+            self.num_wires
+        );
+        assert!(
+            self.custom_gates.iter().all(|g| g.name() != gate.name()),
+            "Custom gate {} is already registered",
+            gate.name()
+        );
+        self.custom_gates.push(gate);
+        CustomGateId(self.custom_gates.len() - 1)
+    }
+
+    /// Looks up a registered custom gate by name.
+    pub fn find_gate(&self, name: &str) -> Option<CustomGateId> {
+        self.custom_gates
+            .iter()
+            .position(|gate| gate.name() == name)
+            .map(CustomGateId)
+    }
+
+    /// Appends a row that enables a custom gate and returns its index.
+    ///
+    /// Wires left out at the end of `wires` are filled with zero.
+    pub fn custom_gate(
+        &mut self,
+        id: CustomGateId,
+        selectors: Vec<F>,
+        wires: &[Variable],
+    ) -> usize {
+        let gate = &self.custom_gates[id.0];
+        assert_eq!(
+            selectors.len(),
+            gate.selectors().len(),
+            "Custom gate {} takes {} selectors",
+            gate.name(),
+            gate.selectors().len()
+        );
+
+        let wires = self.pad_wires(wires);
+        self.rows.push(Row {
+            gate: Gate::disabled(),
+            wires,
+            custom: vec![(id, selectors)],
+            lookup: None,
+        });
+        self.rows.len() - 1
+    }
+
+    /// Registers a lookup table so rows can query it.
+    pub fn register_table(&mut self, table: LookupTable<F>) -> TableId {
+        assert!(
+            table.width() <= self.num_wires,
+            "Lookup table {} has {} columns, rows have {} wires",
+            table.name,
+            table.width(),
+            self.num_wires
+        );
+        self.tables.push(table);
+        TableId(self.tables.len() - 1)
+    }
+
+    /// Appends a row asserting that `wires` match a row of `table`.
+    pub fn lookup(&mut self, table: TableId, wires: &[Variable]) -> usize {
+        let width = self.tables[table.0].width();
+        assert_eq!(
+            wires.len(),
+            width,
+            "Lookup table {} has {} columns",
+            self.tables[table.0].name,
+            width
+        );
+
+        let wires = self.pad_wires(wires);
+        self.rows.push(Row {
+            gate: Gate::disabled(),
+            wires,
+            custom: Vec::new(),
+            lookup: Some(table),
+        });
+        self.rows.len() - 1
+    }
+
+    /// Appends a row as given, e.g. one read back from a circuit file.
+    pub(crate) fn push_row(&mut self, row: Row<F>) -> usize {
+        self.rows.push(row);
+        self.rows.len() - 1
+    }
+
+    pub(crate) fn rows_mut(&mut self) -> &mut [Row<F>] {
+        &mut self.rows
+    }
+
+    /// Drops the rows whose `keep` entry is false, keeping public input
+    /// rows and annotations pointing at the same rows.
+    pub(crate) fn retain_rows(&mut self, keep: &[bool]) {
+        let mut new_index = Vec::with_capacity(self.rows.len());
+        let mut next = 0;
+        for &kept in keep {
+            new_index.push(next);
+            next += usize::from(kept);
+        }
+        for row in &mut self.public_inputs {
+            assert!(keep[*row], "Public input rows cannot be removed");
+            *row = new_index[*row];
+        }
+        self.annotations = std::mem::take(&mut self.annotations)
+            .into_iter()
+            .filter(|(row, _)| keep[*row])
+            .map(|(row, note)| (new_index[row], note))
+            .collect();
+
+        let mut i = 0;
+        self.rows.retain(|_| {
+            i += 1;
+            keep[i - 1]
+        });
+    }
+
+    fn pad_wires(&self, wires: &[Variable]) -> Vec<Variable> {
+        assert!(
+            wires.len() <= self.num_wires,
+            "Row has {} wires, got {}",
+            self.num_wires,
+            wires.len()
+        );
+
+        let mut padded = wires.to_vec();
+        padded.resize(self.num_wires, self.zero);
+        padded
+    }
+
+    pub fn rows(&self) -> &[Row<F>] {
+        &self.rows
+    }
+
+    pub fn custom_gates(&self) -> &[Arc<dyn CustomGate<F>>] {
+        &self.custom_gates
+    }
+
+    pub fn tables(&self) -> &[LookupTable<F>] {
+        &self.tables
+    }
+
+    /// Number of wires every lookup query compresses: the widest table.
+    pub fn lookup_width(&self) -> usize {
+        self.tables
+            .iter()
+            .map(LookupTable::width)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn num_variables(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Indices of the rows holding public inputs, in allocation order.
+    pub fn public_input_rows(&self) -> &[usize] {
+        &self.public_inputs
+    }
+
+    /// Public input values, in allocation order.
+    pub fn public_inputs(&self) -> Vec<F> {
+        self.public_inputs
+            .iter()
+            .map(|&row| self.value(self.rows[row].wires[0]))
+            .collect()
+    }
+
+    /// Number of rows after padding to a power of two. Lookup tables are
+    /// laid out in one column, so they also need to fit.
+    pub fn domain_size(&self) -> usize {
+        let table_rows: usize = self.tables.iter().map(LookupTable::len).sum();
+        self.rows.len().max(table_rows).max(2).next_power_of_two()
+    }
+
+    pub fn wire_values(&self, row: usize) -> Vec<F> {
+        self.rows[row]
+            .wires
+            .iter()
+            .map(|&var| self.value(var))
+            .collect()
+    }
+
+    /// Wires of the row after `row`, wrapping around the padded domain.
+    /// Padding rows carry the zero variable on every wire.
+    pub fn next_wire_values(&self, row: usize) -> Vec<F> {
+        let next = (row + 1) % self.domain_size();
+        if next < self.rows.len() {
+            self.wire_values(next)
+        } else {
+            vec![F::zero(); self.num_wires]
+        }
+    }
+
+    /// Check every standard and custom gate against the witness values.
+    ///
+    /// Copy constraints hold by construction since wires reference
+    /// variables rather than values.
+    pub fn is_satisfied(&self) -> bool {
+        let tables: Vec<HashSet<&[F]>> = self
+            .tables
+            .iter()
+            .map(|table| table.rows.iter().map(Vec::as_slice).collect())
+            .collect();
+
+        (0..self.rows.len()).all(|i| {
+            self.is_row_satisfied(i)
+                && self.rows[i].lookup.is_none_or(|table| {
+                    let values = self.wire_values(i);
+                    tables[table.0].contains(&values[..self.tables[table.0].width()])
+                })
+        })
+    }
+
+    fn is_row_satisfied(&self, i: usize) -> bool {
+        let row = &self.rows[i];
+        let wires = self.wire_values(i);
+
+        let mut gate_eval = row.gate.evaluate_row(&wires);
+        if self.public_inputs.contains(&i) {
+            gate_eval -= wires[0];
+        }
+        if !gate_eval.is_zero() {
+            return false;
+        }
+
+        let next = self.next_wire_values(i);
+        row.custom.iter().all(|(id, selectors)| {
+            self.custom_gates[id.0]
+                .evaluate(selectors, &wires, &next)
+                .iter()
+                .all(|v| v.is_zero())
+        })
+    }
+}
+
+impl<F: PrimeField> Default for ConstraintSystem<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use ark_bls12_381::Fr;
+use ark_ec::twisted_edwards::TECurveConfig;
+use ark_ed_on_bls12_381::JubjubConfig;
+use ark_ff::PrimeField;
+
+use crate::gate::CustomGate;
+
+/// Boolean check on the left wire.
+///
+/// Constraint: q_bool·(a² - a) = 0, i.e., a ∈ {0, 1}
+#[derive(Clone, Debug, Default)]
+pub struct BooleanGate;
+
+impl<F: PrimeField> CustomGate<F> for BooleanGate {
+    fn name(&self) -> &'static str {
+        "boolean"
+    }
+
+    fn selectors(&self) -> &'static [&'static str] {
+        &["q_bool"]
+    }
+
+    fn degree(&self) -> usize {
+        3
+    }
+
+    fn evaluate(&self, selectors: &[F], wires: &[F], _next: &[F]) -> Vec<F> {
+        let a = wires[0];
+        vec![selectors[0] * (a.square() - a)]
+    }
+}
+
+/// Fifth-power S-box, as used by Poseidon-style hashes.
+///
+/// Constraint: q_pow5·(a⁵ - c) = 0
+#[derive(Clone, Debug, Default)]
+pub struct Pow5Gate;
+
+impl<F: PrimeField> CustomGate<F> for Pow5Gate {
+    fn name(&self) -> &'static str {
+        "pow5"
+    }
+
+    fn selectors(&self) -> &'static [&'static str] {
+        &["q_pow5"]
+    }
+
+    fn degree(&self) -> usize {
+        6
+    }
+
+    fn evaluate(&self, selectors: &[F], wires: &[F], _next: &[F]) -> Vec<F> {
+        let a = wires[0];
+        let c = wires[2];
+        vec![selectors[0] * (a.pow([5]) - c)]
+    }
+}
+
+/// Twisted Edwards point addition for the curve a·x² + y² = 1 + d·x²·y².
+///
+/// Spans two rows:
+///
+/// ```text
+/// row i:     a = x1, b = y1, c = x2
+/// row i + 1: a = y2, b = x3, c = y3
+/// ```
+///
+/// Constraints, with t = d·x1·x2·y1·y2:
+///
+/// ```text
+/// q_ecc_add·(x3·(1 + t) - (x1·y2 + y1·x2)) = 0
+/// q_ecc_add·(y3·(1 - t) - (y1·y2 - a·x1·x2)) = 0
+/// ```
+#[derive(Clone, Debug)]
+pub struct EdwardsAddGate<F: PrimeField = Fr> {
+    pub a: F,
+    pub d: F,
+}
+
+impl EdwardsAddGate {
+    /// Jubjub, the curve embedded in BLS12-381's scalar field:
+    /// -x² + y² = 1 - (10240/10241)·x²·y²
+    pub fn jubjub() -> Self {
+        Self::new(JubjubConfig::COEFF_A, JubjubConfig::COEFF_D)
+    }
+}
+
+impl<F: PrimeField> EdwardsAddGate<F> {
+    pub fn new(a: F, d: F) -> Self {
+        EdwardsAddGate { a, d }
+    }
+
+    /// Native point addition with the same formula the gate enforces.
+    pub fn add(&self, p1: (F, F), p2: (F, F)) -> (F, F) {
+        let (x1, y1) = p1;
+        let (x2, y2) = p2;
+        let t = self.d * x1 * x2 * y1 * y2;
+        let x3 = (x1 * y2 + y1 * x2) * (F::one() + t).inverse().unwrap();
+        let y3 = (y1 * y2 - self.a * x1 * x2) * (F::one() - t).inverse().unwrap();
+        (x3, y3)
+    }
+}
+
+impl<F: PrimeField> CustomGate<F> for EdwardsAddGate<F> {
+    fn name(&self) -> &'static str {
+        "edwards_add"
+    }
+
+    fn selectors(&self) -> &'static [&'static str] {
+        &["q_ecc_add"]
+    }
+
+    fn params(&self) -> Vec<(&'static str, F)> {
+        vec![("a", self.a), ("d", self.d)]
+    }
+
+    fn degree(&self) -> usize {
+        6
+    }
+
+    fn num_constraints(&self) -> usize {
+        2
+    }
+
+    fn evaluate(&self, selectors: &[F], wires: &[F], next: &[F]) -> Vec<F> {
+        let q = selectors[0];
+        let (x1, y1, x2) = (wires[0], wires[1], wires[2]);
+        let (y2, x3, y3) = (next[0], next[1], next[2]);
+
+        let x1x2 = x1 * x2;
+        let t = self.d * x1x2 * y1 * y2;
+
+        vec![
+            q * (x3 * (F::one() + t) - (x1 * y2 + y1 * x2)),
+            q * (y3 * (F::one() - t) - (y1 * y2 - self.a * x1x2)),
+        ]
+    }
+}
+
+/// Rebuilds one of the gates above from its name and the parameters it
+/// reports through [`CustomGate::params`], e.g. when reading a circuit or
+/// key file.
+pub fn builtin<F: PrimeField>(
+    name: &str,
+    params: &[(String, F)],
+) -> Result<Arc<dyn CustomGate<F>>, String> {
+    let expected: &[&str] = match name {
+        "boolean" | "pow5" => &[],
+        "edwards_add" => &["a", "d"],
+        other => return Err(format!("Unknown custom gate {other}")),
+    };
+    if let Some((extra, _)) = params
+        .iter()
+        .find(|(key, _)| !expected.contains(&key.as_str()))
+    {
+        return Err(format!("Custom gate {name} has no parameter {extra}"));
+    }
+    let param = |key: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| format!("Custom gate {name} needs parameter {key}"))
+    };
+
+    Ok(match name {
+        "boolean" => Arc::new(BooleanGate),
+        "pow5" => Arc::new(Pow5Gate),
+        _ => Arc::new(EdwardsAddGate::new(param("a")?, param("d")?)),
+    })
+}
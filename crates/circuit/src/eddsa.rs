@@ -0,0 +1,77 @@
+//! EdDSA over Jubjub with a Poseidon challenge, so signatures are cheap
+//! to verify in circuits over BLS12-381's scalar field.
+//!
+//! A signature (R, s) on a message m under the key A = sk·B is valid when
+//!
+//! ```text
+//! 8·s·B = 8·R + 8·c·A,  c = Poseidon(R.x, R.y, A.x, A.y, m)
+//! ```
+//!
+//! The challenge c is a base field element used as an integer, and the
+//! cofactor 8 clears any small-order components of R and A.
+
+use ark_bls12_381::Fr;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ed_on_bls12_381::{EdwardsAffine, EdwardsProjective, Fr as JubjubScalar};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use rand::Rng;
+
+use crate::poseidon::PoseidonParams;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SecretKey(pub JubjubScalar);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey(pub EdwardsAffine);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub r: EdwardsAffine,
+    pub s: JubjubScalar,
+}
+
+impl SecretKey {
+    pub fn generate<R: Rng>(rng: &mut R) -> Self {
+        SecretKey(JubjubScalar::rand(rng))
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey((EdwardsAffine::generator() * self.0).into_affine())
+    }
+
+    pub fn sign<R: Rng>(&self, message: Fr, rng: &mut R) -> Signature {
+        let nonce = JubjubScalar::rand(rng);
+        let r = (EdwardsAffine::generator() * nonce).into_affine();
+        let c = challenge(&r, &self.public_key().0, message);
+
+        Signature {
+            r,
+            s: nonce + challenge_scalar(c) * self.0,
+        }
+    }
+}
+
+impl PublicKey {
+    pub fn verify(&self, message: Fr, signature: &Signature) -> bool {
+        let c = challenge(&signature.r, &self.0, message);
+        let lhs = EdwardsAffine::generator() * signature.s;
+        let rhs = EdwardsProjective::from(signature.r) + self.0 * challenge_scalar(c);
+        (lhs - rhs).into_affine().mul_by_cofactor().is_zero()
+    }
+}
+
+/// c = Poseidon(R.x, R.y, A.x, A.y, m)
+pub fn challenge(r: &EdwardsAffine, public_key: &EdwardsAffine, message: Fr) -> Fr {
+    PoseidonParams::standard().hash(&[r.x, r.y, public_key.x, public_key.y, message])
+}
+
+/// The challenge as a Jubjub scalar, i.e. its integer value mod ℓ.
+pub fn challenge_scalar(c: Fr) -> JubjubScalar {
+    JubjubScalar::from_le_bytes_mod_order(&c.into_bigint().to_bytes_le())
+}
+
+/// Integer value of a Jubjub scalar as a base field element. Scalars are
+/// smaller than the base field modulus, so this is exact.
+pub fn scalar_to_base(s: JubjubScalar) -> Fr {
+    Fr::from_le_bytes_mod_order(&s.into_bigint().to_bytes_le())
+}
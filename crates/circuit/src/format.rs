@@ -0,0 +1,527 @@
+//! Textual circuit format.
+//!
+//! A circuit file lists declarations and rows, one per line; `#` starts a
+//! comment:
+//!
+//! ```text
+//! wires 3
+//! variables 4
+//! gate pow5
+//! table range_2
+//! entry 0
+//! entry 1
+//! entry 2
+//! entry 3
+//! row public : 1
+//! row q_m=1 q_o=-1 : 2 2 1
+//! row pow5=1 : 2 0 3
+//! row lookup=range_2 : 3
+//! copy 2 3
+//! ```
+//!
+//! - `wires n` sets the row width (default 3) and `variables n` the number
+//!   of variables (default: one past the highest index used).
+//! - `gate name [param=value …]` registers a built-in custom gate:
+//!   `boolean`, `pow5` or `edwards_add a=… d=…`.
+//! - `table name` starts a lookup table whose rows follow as `entry` lines.
+//! - `row [attrs] : w₀ w₁ …` appends a row. Wires are variable indices and
+//!   missing trailing wires are 0. Attributes are standard selectors
+//!   (`q_l`, `q_r`, `q_o`, `q_m`, `q_c`, then `q_4`, `q_5`, … for wider
+//!   rows), `name=s₀,s₁,…` to enable a custom gate, `lookup=table`, or
+//!   `public` on its own to make w₀ a public input.
+//! - `copy i j` ties two variables together. Using one variable on several
+//!   wires does the same.
+//!
+//! Values are decimal or `0x` hexadecimal integers, optionally negative.
+//! Variable 0 is the zero variable and row 0, which pins it, is implicit.
+//! Every other variable is an input of the parsed circuit, so its witness
+//! comes from a [`Witness`](crate::witness::Witness) file.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use ark_bls12_381::Fr;
+use ark_ff::{PrimeField, Zero};
+
+use crate::constraint_system::{ConstraintSystem, CustomGateId, DEFAULT_NUM_WIRES, Row, Variable};
+use crate::custom_gates::builtin;
+use crate::gate::Gate;
+use crate::lookup::{LookupTable, TableId};
+use crate::witness::parse_integer;
+
+const STANDARD_SELECTORS: [&str; 5] = ["q_l", "q_r", "q_o", "q_m", "q_c"];
+
+/// Why a circuit file could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// One-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn error<T>(line: usize, message: impl Into<String>) -> Result<T, ParseError> {
+    Err(ParseError {
+        line,
+        message: message.into(),
+    })
+}
+
+struct GateDecl {
+    line: usize,
+    name: String,
+    params: HashMap<String, Fr>,
+}
+
+struct TableDecl {
+    line: usize,
+    name: String,
+    rows: Vec<Vec<Fr>>,
+}
+
+struct RowDecl {
+    line: usize,
+    gate: Gate,
+    public: bool,
+    custom: Vec<(String, Vec<Fr>)>,
+    lookup: Option<String>,
+    wires: Vec<usize>,
+}
+
+/// Parses a circuit file into a constraint system with a zero witness.
+pub fn parse(text: &str) -> Result<ConstraintSystem, ParseError> {
+    let mut wires = (0, DEFAULT_NUM_WIRES);
+    let mut variables = None;
+    let mut gates = Vec::new();
+    let mut tables: Vec<TableDecl> = Vec::new();
+    let mut rows = Vec::new();
+    let mut copies = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let content = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = content.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "wires" => wires = (line_no, parse_count(line_no, &args)?),
+            "variables" => variables = Some(parse_count(line_no, &args)?),
+            "gate" => {
+                let Some((name, params)) = args.split_first() else {
+                    return error(line_no, "Expected a gate name");
+                };
+                let params = params
+                    .iter()
+                    .map(|param| {
+                        let (key, value) = split_attr(line_no, param)?;
+                        Ok((key.to_string(), parse_value(line_no, value)?))
+                    })
+                    .collect::<Result<_, _>>()?;
+                gates.push(GateDecl {
+                    line: line_no,
+                    name: name.to_string(),
+                    params,
+                });
+            }
+            "table" => {
+                let [name] = args.as_slice() else {
+                    return error(line_no, "Expected a table name");
+                };
+                tables.push(TableDecl {
+                    line: line_no,
+                    name: name.to_string(),
+                    rows: Vec::new(),
+                });
+            }
+            "entry" => {
+                let Some(table) = tables.last_mut() else {
+                    return error(line_no, "Entry outside of a table");
+                };
+                let entry = args
+                    .iter()
+                    .map(|value| parse_value(line_no, value))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if table
+                    .rows
+                    .first()
+                    .is_some_and(|first| first.len() != entry.len())
+                {
+                    return error(
+                        line_no,
+                        format!("Table {} has rows of different widths", table.name),
+                    );
+                }
+                table.rows.push(entry);
+            }
+            "copy" => {
+                let [a, b] = args.as_slice() else {
+                    return error(line_no, "Expected two variables");
+                };
+                copies.push((line_no, parse_index(line_no, a)?, parse_index(line_no, b)?));
+            }
+            "row" => rows.push(parse_row(line_no, &args)?),
+            other => return error(line_no, format!("Unknown declaration {other}")),
+        }
+    }
+
+    let (wires_line, num_wires) = wires;
+    if num_wires < DEFAULT_NUM_WIRES {
+        return error(
+            wires_line,
+            format!("A row needs at least {DEFAULT_NUM_WIRES} wires"),
+        );
+    }
+    let mut cs = ConstraintSystem::with_wires(num_wires);
+
+    let highest = rows
+        .iter()
+        .flat_map(|row| row.wires.iter().copied())
+        .chain(copies.iter().flat_map(|&(_, a, b)| [a, b]))
+        .max()
+        .unwrap_or(0);
+    let num_variables = variables.unwrap_or(highest + 1).max(1);
+    let out_of_range = rows
+        .iter()
+        .flat_map(|row| row.wires.iter().map(|&v| (row.line, v)))
+        .chain(
+            copies
+                .iter()
+                .flat_map(|&(line, a, b)| [(line, a), (line, b)]),
+        )
+        .find(|&(_, v)| v >= num_variables);
+    if let Some((line, v)) = out_of_range {
+        return error(
+            line,
+            format!("Variable {v} is out of range, there are {num_variables}"),
+        );
+    }
+    for _ in 1..num_variables {
+        cs.alloc(Fr::zero());
+    }
+
+    let mut gate_ids: HashMap<String, (CustomGateId, usize)> = HashMap::new();
+    for decl in &gates {
+        if gate_ids.contains_key(&decl.name) {
+            return error(
+                decl.line,
+                format!("Custom gate {} is declared twice", decl.name),
+            );
+        }
+        let id = register_gate(&mut cs, decl)?;
+        let selectors = cs.custom_gates()[id.index()].selectors().len();
+        gate_ids.insert(decl.name.clone(), (id, selectors));
+    }
+
+    let mut table_ids: HashMap<String, TableId> = HashMap::new();
+    for decl in tables {
+        if table_ids.contains_key(&decl.name) {
+            return error(
+                decl.line,
+                format!("Lookup table {} is declared twice", decl.name),
+            );
+        }
+        let Some(first) = decl.rows.first() else {
+            return error(decl.line, format!("Lookup table {} is empty", decl.name));
+        };
+        if first.len() > num_wires {
+            return error(
+                decl.line,
+                format!("Lookup table {} is wider than a row", decl.name),
+            );
+        }
+        let id = cs.register_table(LookupTable::new(decl.name.clone(), decl.rows));
+        table_ids.insert(decl.name, id);
+    }
+
+    // Union-find over variables, keeping the smallest index as the root so
+    // the zero variable stays put
+    let mut parent: Vec<usize> = (0..num_variables).collect();
+    fn root(parent: &mut [usize], mut v: usize) -> usize {
+        while parent[v] != v {
+            parent[v] = parent[parent[v]];
+            v = parent[v];
+        }
+        v
+    }
+    for &(_, a, b) in &copies {
+        let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+        parent[ra.max(rb)] = ra.min(rb);
+    }
+
+    for decl in rows {
+        if decl.wires.len() > num_wires {
+            return error(
+                decl.line,
+                format!("Row has {} wires, got {}", num_wires, decl.wires.len()),
+            );
+        }
+        if decl.gate.q_extra.len() > num_wires - DEFAULT_NUM_WIRES {
+            return error(
+                decl.line,
+                format!(
+                    "Rows have no wire for q_{}",
+                    DEFAULT_NUM_WIRES + decl.gate.q_extra.len()
+                ),
+            );
+        }
+        let mut wires: Vec<Variable> = decl
+            .wires
+            .iter()
+            .map(|&v| Variable::new(root(&mut parent, v)))
+            .collect();
+        wires.resize(num_wires, cs.zero());
+
+        if decl.public {
+            cs.expose(wires[0]);
+            continue;
+        }
+
+        let custom = decl
+            .custom
+            .into_iter()
+            .map(|(name, selectors)| {
+                let Some(&(id, expected)) = gate_ids.get(&name) else {
+                    return error(decl.line, format!("Unknown selector or gate {name}"));
+                };
+                if selectors.len() != expected {
+                    return error(
+                        decl.line,
+                        format!("Custom gate {name} takes {expected} selectors"),
+                    );
+                }
+                Ok((id, selectors))
+            })
+            .collect::<Result<_, _>>()?;
+        let lookup = match decl.lookup {
+            Some(name) => match table_ids.get(&name) {
+                Some(&id) => Some(id),
+                None => return error(decl.line, format!("Unknown lookup table {name}")),
+            },
+            None => None,
+        };
+
+        cs.push_row(Row {
+            gate: decl.gate,
+            wires,
+            custom,
+            lookup,
+        });
+    }
+
+    Ok(cs)
+}
+
+fn register_gate(cs: &mut ConstraintSystem, decl: &GateDecl) -> Result<CustomGateId, ParseError> {
+    let mut params: Vec<(String, Fr)> = decl
+        .params
+        .iter()
+        .map(|(key, value)| (key.clone(), *value))
+        .collect();
+    params.sort_by(|x, y| x.0.cmp(&y.0));
+    let gate = builtin(&decl.name, &params).map_err(|message| ParseError {
+        line: decl.line,
+        message,
+    })?;
+
+    if gate.num_wires() > cs.num_wires() {
+        return error(
+            decl.line,
+            format!("Custom gate {} is wider than a row", decl.name),
+        );
+    }
+    Ok(cs.register_shared_gate(gate))
+}
+
+fn parse_row(line: usize, args: &[&str]) -> Result<RowDecl, ParseError> {
+    let Some(colon) = args.iter().position(|&token| token == ":") else {
+        return error(line, "Expected ':' before the wires");
+    };
+    let (attrs, wires) = (&args[..colon], &args[colon + 1..]);
+
+    let mut row = RowDecl {
+        line,
+        gate: Gate::disabled(),
+        public: false,
+        custom: Vec::new(),
+        lookup: None,
+        wires: wires
+            .iter()
+            .map(|w| parse_index(line, w))
+            .collect::<Result<_, _>>()?,
+    };
+
+    for attr in attrs {
+        if *attr == "public" {
+            row.public = true;
+            continue;
+        }
+        let (key, value) = split_attr(line, attr)?;
+        if key == "lookup" {
+            row.lookup = Some(value.to_string());
+        } else if let Some(i) = STANDARD_SELECTORS.iter().position(|&name| name == key) {
+            let value = parse_value(line, value)?;
+            match i {
+                0 => row.gate.q_l = value,
+                1 => row.gate.q_r = value,
+                2 => row.gate.q_o = value,
+                3 => row.gate.q_m = value,
+                _ => row.gate.q_c = value,
+            }
+        } else if let Some(k) = extra_selector(key) {
+            if row.gate.q_extra.len() <= k {
+                row.gate.q_extra.resize(k + 1, Fr::zero());
+            }
+            row.gate.q_extra[k] = parse_value(line, value)?;
+        } else {
+            let selectors = value
+                .split(',')
+                .map(|s| parse_value(line, s))
+                .collect::<Result<_, _>>()?;
+            row.custom.push((key.to_string(), selectors));
+        }
+    }
+
+    if row.public
+        && (row.gate != Gate::disabled()
+            || !row.custom.is_empty()
+            || row.lookup.is_some()
+            || row.wires.len() != 1)
+    {
+        return error(line, "A public row holds one wire and nothing else");
+    }
+    Ok(row)
+}
+
+/// Position in `q_extra` of a selector named q_4, q_5, …
+fn extra_selector(key: &str) -> Option<usize> {
+    let n: usize = key.strip_prefix("q_")?.parse().ok()?;
+    n.checked_sub(DEFAULT_NUM_WIRES + 1)
+}
+
+fn split_attr(line: usize, attr: &str) -> Result<(&str, &str), ParseError> {
+    match attr.split_once('=') {
+        Some(pair) => Ok(pair),
+        None => error(line, format!("Expected key=value, got {attr}")),
+    }
+}
+
+fn parse_value(line: usize, text: &str) -> Result<Fr, ParseError> {
+    match parse_integer(text) {
+        Some(value) => Ok(value),
+        None => error(line, format!("{text} is not an integer")),
+    }
+}
+
+fn parse_index(line: usize, text: &str) -> Result<usize, ParseError> {
+    match text.parse() {
+        Ok(index) => Ok(index),
+        Err(_) => error(line, format!("{text} is not a variable index")),
+    }
+}
+
+fn parse_count(line: usize, args: &[&str]) -> Result<usize, ParseError> {
+    match args {
+        [count] => parse_index(line, count),
+        _ => error(line, "Expected one number"),
+    }
+}
+
+/// Writes a constraint system in the circuit file format. The witness is
+/// not included.
+pub fn write(cs: &ConstraintSystem) -> String {
+    let mut out = String::new();
+    let mut line = |text: String| {
+        out.push_str(&text);
+        out.push('\n');
+    };
+
+    line(format!("wires {}", cs.num_wires()));
+    line(format!("variables {}", cs.num_variables()));
+
+    for gate in cs.custom_gates() {
+        let params: String = gate
+            .params()
+            .iter()
+            .map(|(key, value)| format!(" {key}={}", format_value(*value)))
+            .collect();
+        line(format!("gate {}{params}", gate.name()));
+    }
+
+    for table in cs.tables() {
+        line(format!("table {}", table.name));
+        for entry in &table.rows {
+            line(format!(
+                "entry {}",
+                join(entry.iter().map(|v| format_value(*v)))
+            ));
+        }
+    }
+
+    for (i, row) in cs.rows().iter().enumerate().skip(1) {
+        if cs.public_input_rows().contains(&i) {
+            line(format!("row public : {}", row.wires[0].index()));
+            continue;
+        }
+
+        // Trailing zero wires are padding
+        let used = row
+            .wires
+            .iter()
+            .rposition(|w| *w != cs.zero())
+            .map_or(0, |p| p + 1);
+        let wires = join(row.wires[..used].iter().map(|w| w.index().to_string()));
+
+        let mut attrs = Vec::new();
+        let selectors = row.gate.selectors(cs.num_wires());
+        for (k, value) in selectors.iter().enumerate() {
+            if !value.is_zero() {
+                let name = match STANDARD_SELECTORS.get(k) {
+                    Some(name) => name.to_string(),
+                    None => format!("q_{}", k - STANDARD_SELECTORS.len() + DEFAULT_NUM_WIRES + 1),
+                };
+                attrs.push(format!("{name}={}", format_value(*value)));
+            }
+        }
+        for (id, selectors) in &row.custom {
+            let values: Vec<String> = selectors.iter().map(|v| format_value(*v)).collect();
+            attrs.push(format!(
+                "{}={}",
+                cs.custom_gates()[id.index()].name(),
+                values.join(",")
+            ));
+        }
+        if let Some(table) = row.lookup {
+            attrs.push(format!("lookup={}", cs.tables()[table.index()].name));
+        }
+
+        let attrs = join(attrs.into_iter());
+        let sep = if attrs.is_empty() { "" } else { " " };
+        line(format!("row {attrs}{sep}: {wires}"));
+    }
+
+    out
+}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(" ")
+}
+
+/// Decimal, written as -x when that is shorter, e.g. -1 for p - 1.
+pub(crate) fn format_value(value: Fr) -> String {
+    let negated = -value;
+    if negated.into_bigint() < value.into_bigint() {
+        format!("-{negated}")
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,135 @@
+use ark_bls12_381::Fr;
+use ark_ff::{Field, One, PrimeField, Zero};
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::gate::Gate;
+
+/// Constrains a = b.
+pub fn assert_equal(cs: &mut ConstraintSystem, a: Variable, b: Variable) {
+    cs.add_gate(Gate::linear(&[Fr::one(), -Fr::one()], Fr::zero()), &[a, b]);
+}
+
+/// Returns a - b.
+pub fn sub(cs: &mut ConstraintSystem, a: Variable, b: Variable) -> Variable {
+    let c = cs.assign(move |w| Ok(w.get(a)? - w.get(b)?));
+    cs.add_gate(
+        Gate::linear(&[Fr::one(), -Fr::one(), -Fr::one()], Fr::zero()),
+        &[a, b, c],
+    );
+    c
+}
+
+/// Returns Σ coeffᵢ·termᵢ + constant.
+///
+/// Packs as many terms into each row as its width allows, so a sum of k
+/// terms takes one row on a (k + 1)-wire system.
+pub fn linear_combination<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    terms: &[(F, Variable)],
+    constant: F,
+) -> Variable {
+    let width = cs.num_wires();
+    let (first, mut rest) = terms.split_at(terms.len().min(width - 1));
+
+    let mut sum = add_row(cs, first, constant);
+    while !rest.is_empty() {
+        let (chunk, tail) = rest.split_at(rest.len().min(width - 2));
+        let mut row = vec![(F::one(), sum)];
+        row.extend_from_slice(chunk);
+        sum = add_row(cs, &row, F::zero());
+        rest = tail;
+    }
+    sum
+}
+
+/// One row constraining out = Σ coeffᵢ·termᵢ + constant, with out on the
+/// wire after the terms.
+fn add_row<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    terms: &[(F, Variable)],
+    constant: F,
+) -> Variable {
+    let recorded = terms.to_vec();
+    let out = cs.assign(move |w| {
+        recorded.iter().try_fold(
+            constant,
+            |acc, (coeff, var)| Ok(acc + *coeff * w.get(*var)?),
+        )
+    });
+
+    let mut coeffs: Vec<F> = terms.iter().map(|(coeff, _)| *coeff).collect();
+    let mut wires: Vec<Variable> = terms.iter().map(|(_, var)| *var).collect();
+    coeffs.push(-F::one());
+    wires.push(out);
+    cs.add_gate(Gate::linear(&coeffs, constant), &wires);
+    out
+}
+
+/// Returns x⁻¹ and constrains x·x⁻¹ = 1, so x must be non-zero.
+///
+/// For x = 0 the witness is set to 0 and the circuit is unsatisfiable;
+/// generating the witness fails with a division by zero.
+pub fn inverse(cs: &mut ConstraintSystem, x: Variable) -> Variable {
+    let inv = cs.assign(move |w| w.inverse(w.get(x)?));
+    let gate = Gate {
+        q_m: Fr::one(),
+        q_c: -Fr::one(),
+        ..Gate::disabled()
+    };
+    cs.add_gate(gate, &[x, inv]);
+    inv
+}
+
+/// Constrains x ≠ 0 by proving it has an inverse.
+pub fn assert_nonzero(cs: &mut ConstraintSystem, x: Variable) {
+    inverse(cs, x);
+}
+
+/// Returns 1 if x = 0 and 0 otherwise.
+///
+/// With inv = x⁻¹ (or 0 when x = 0) and out = 1 - x·inv, the rows
+/// x·inv + out - 1 = 0 and x·out = 0 leave the prover no other choice.
+pub fn is_zero(cs: &mut ConstraintSystem, x: Variable) -> Variable {
+    let inv = cs.assign(move |w| Ok(w.get(x)?.inverse().unwrap_or(Fr::zero())));
+    let out = cs.assign(move |w| {
+        Ok(if w.get(x)?.is_zero() {
+            Fr::one()
+        } else {
+            Fr::zero()
+        })
+    });
+
+    let product = Gate {
+        q_o: Fr::one(),
+        q_m: Fr::one(),
+        q_c: -Fr::one(),
+        ..Gate::disabled()
+    };
+    cs.add_gate(product, &[x, inv, out]);
+
+    let annihilate = Gate {
+        q_m: Fr::one(),
+        ..Gate::disabled()
+    };
+    cs.add_gate(annihilate, &[x, out]);
+    out
+}
+
+/// Returns 1 if a = b and 0 otherwise.
+pub fn is_equal(cs: &mut ConstraintSystem, a: Variable, b: Variable) -> Variable {
+    let difference = sub(cs, a, b);
+    is_zero(cs, difference)
+}
+
+/// Returns `a` if `condition` is 1 and `b` if it is 0, as
+/// b + condition·(a - b). The condition must be boolean.
+pub fn select(
+    cs: &mut ConstraintSystem,
+    condition: Variable,
+    a: Variable,
+    b: Variable,
+) -> Variable {
+    let difference = sub(cs, a, b);
+    let offset = cs.mul(condition, difference);
+    cs.add(b, offset)
+}
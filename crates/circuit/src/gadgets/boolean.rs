@@ -0,0 +1,75 @@
+use ark_bls12_381::Fr;
+use ark_ff::{AdditiveGroup, One, Zero};
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::gate::Gate;
+
+/// Constrains x ∈ {0, 1} with x·x - x = 0.
+pub fn assert_boolean(cs: &mut ConstraintSystem, x: Variable) {
+    let gate = Gate {
+        q_l: -Fr::one(),
+        q_m: Fr::one(),
+        ..Gate::disabled()
+    };
+    cs.add_gate(gate, &[x, x]);
+}
+
+/// Allocates a bit and constrains it to be boolean.
+pub fn alloc_bit(cs: &mut ConstraintSystem, value: bool) -> Variable {
+    let bit = cs.alloc(if value { Fr::one() } else { Fr::zero() });
+    assert_boolean(cs, bit);
+    bit
+}
+
+/// Returns 1 - a. The input must be boolean.
+pub fn not(cs: &mut ConstraintSystem, a: Variable) -> Variable {
+    let c = cs.assign(move |w| Ok(Fr::one() - w.get(a)?));
+    // -a - c + 1 = 0
+    let gate = Gate {
+        q_l: -Fr::one(),
+        q_o: -Fr::one(),
+        q_c: Fr::one(),
+        ..Gate::disabled()
+    };
+    cs.add_gate(gate, &[a, cs.zero(), c]);
+    c
+}
+
+/// Returns a·b. The inputs must be boolean.
+pub fn and(cs: &mut ConstraintSystem, a: Variable, b: Variable) -> Variable {
+    cs.mul(a, b)
+}
+
+/// Returns a + b - a·b. The inputs must be boolean.
+pub fn or(cs: &mut ConstraintSystem, a: Variable, b: Variable) -> Variable {
+    let c = cs.assign(move |w| {
+        let (va, vb) = (w.get(a)?, w.get(b)?);
+        Ok(va + vb - va * vb)
+    });
+    let gate = Gate {
+        q_l: Fr::one(),
+        q_r: Fr::one(),
+        q_o: -Fr::one(),
+        q_m: -Fr::one(),
+        ..Gate::disabled()
+    };
+    cs.add_gate(gate, &[a, b, c]);
+    c
+}
+
+/// Returns a + b - 2·a·b. The inputs must be boolean.
+pub fn xor(cs: &mut ConstraintSystem, a: Variable, b: Variable) -> Variable {
+    let c = cs.assign(move |w| {
+        let (va, vb) = (w.get(a)?, w.get(b)?);
+        Ok(va + vb - va * vb.double())
+    });
+    let gate = Gate {
+        q_l: Fr::one(),
+        q_r: Fr::one(),
+        q_o: -Fr::one(),
+        q_m: -Fr::from(2u64),
+        ..Gate::disabled()
+    };
+    cs.add_gate(gate, &[a, b, c]);
+    c
+}
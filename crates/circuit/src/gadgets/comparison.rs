@@ -0,0 +1,41 @@
+use ark_bls12_381::Fr;
+use ark_ff::{AdditiveGroup, One};
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::gadgets::boolean::not;
+use crate::gadgets::range::to_bits;
+use crate::gate::Gate;
+
+/// Returns 1 if a < b and 0 otherwise, for a, b < 2^num_bits.
+///
+/// d = a - b + 2^num_bits lies in [0, 2^(num_bits+1)) and its top bit is
+/// set exactly when a ≥ b. The inputs must be range checked by the
+/// caller, otherwise the result is meaningless.
+pub fn less_than(cs: &mut ConstraintSystem, a: Variable, b: Variable, num_bits: usize) -> Variable {
+    let shift = (0..num_bits).fold(Fr::one(), |acc, _| acc.double());
+    let d = cs.assign(move |w| Ok(w.get(a)? - w.get(b)? + shift));
+    cs.add_gate(
+        Gate::linear(&[Fr::one(), -Fr::one(), -Fr::one()], shift),
+        &[a, b, d],
+    );
+
+    let bits = to_bits(cs, d, num_bits + 1);
+    not(cs, bits[num_bits])
+}
+
+/// Constrains a < b, for a, b < 2^num_bits.
+pub fn assert_less_than(cs: &mut ConstraintSystem, a: Variable, b: Variable, num_bits: usize) {
+    let lt = less_than(cs, a, b, num_bits);
+    cs.add_gate(Gate::constant(Fr::one()), &[lt]);
+}
+
+/// Returns 1 if a ≤ b and 0 otherwise, for a, b < 2^num_bits.
+pub fn less_or_equal(
+    cs: &mut ConstraintSystem,
+    a: Variable,
+    b: Variable,
+    num_bits: usize,
+) -> Variable {
+    let gt = less_than(cs, b, a, num_bits);
+    not(cs, gt)
+}
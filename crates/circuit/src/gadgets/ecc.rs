@@ -0,0 +1,156 @@
+use ark_bls12_381::Fr;
+use ark_ff::{One, Zero};
+
+use crate::constraint_system::{ConstraintSystem, CustomGateId, Variable};
+use crate::custom_gates::EdwardsAddGate;
+use crate::gadgets::arithmetic::{assert_equal, select};
+use crate::gate::{CustomGate, Gate};
+use crate::witness::Assignment;
+
+/// Affine point on a twisted Edwards curve.
+#[derive(Clone, Copy, Debug)]
+pub struct PointVar {
+    pub x: Variable,
+    pub y: Variable,
+}
+
+/// Point arithmetic on one twisted Edwards curve, backed by
+/// [`EdwardsAddGate`].
+///
+/// The curve's formulas are complete when a is a square and d is not, as
+/// for Jubjub, so additions and doublings need no special cases.
+#[derive(Clone, Debug)]
+pub struct EdwardsCurve {
+    pub params: EdwardsAddGate,
+    gate: CustomGateId,
+}
+
+impl EdwardsCurve {
+    /// Registers the addition gate, or reuses the one already registered.
+    pub fn new(cs: &mut ConstraintSystem, params: EdwardsAddGate) -> Self {
+        let gate = cs
+            .find_gate(params.name())
+            .unwrap_or_else(|| cs.register_gate(params.clone()));
+        EdwardsCurve { params, gate }
+    }
+
+    /// Allocates a private point and constrains it to the curve.
+    pub fn alloc_point(&self, cs: &mut ConstraintSystem, point: (Fr, Fr)) -> PointVar {
+        let p = PointVar {
+            x: cs.alloc(point.0),
+            y: cs.alloc(point.1),
+        };
+        self.assert_on_curve(cs, p);
+        p
+    }
+
+    /// Allocates a public point.
+    pub fn public_point(&self, cs: &mut ConstraintSystem, point: (Fr, Fr)) -> PointVar {
+        let p = PointVar {
+            x: cs.public_input(point.0),
+            y: cs.public_input(point.1),
+        };
+        self.assert_on_curve(cs, p);
+        p
+    }
+
+    /// Allocates a point fixed by the circuit.
+    pub fn constant_point(&self, cs: &mut ConstraintSystem, point: (Fr, Fr)) -> PointVar {
+        PointVar {
+            x: cs.constant(point.0),
+            y: cs.constant(point.1),
+        }
+    }
+
+    /// The neutral element (0, 1).
+    pub fn identity(&self, cs: &mut ConstraintSystem) -> PointVar {
+        self.constant_point(cs, (Fr::zero(), Fr::one()))
+    }
+
+    pub fn value(&self, cs: &ConstraintSystem, p: PointVar) -> (Fr, Fr) {
+        (cs.value(p.x), cs.value(p.y))
+    }
+
+    /// Constrains a·x² + y² = 1 + d·x²·y².
+    pub fn assert_on_curve(&self, cs: &mut ConstraintSystem, p: PointVar) {
+        let xx = cs.mul(p.x, p.x);
+        let yy = cs.mul(p.y, p.y);
+        let xxyy = cs.mul(xx, yy);
+        let gate = Gate {
+            q_l: self.params.a,
+            q_r: Fr::one(),
+            q_o: -self.params.d,
+            q_c: -Fr::one(),
+            ..Gate::disabled()
+        };
+        cs.add_gate(gate, &[xx, yy, xxyy]);
+    }
+
+    /// Returns p + q in two rows of the addition gate.
+    pub fn add(&self, cs: &mut ConstraintSystem, p: PointVar, q: PointVar) -> PointVar {
+        let EdwardsAddGate { a, d } = self.params;
+        // t = d·x1·x2·y1·y2
+        let t =
+            move |w: &Assignment| Ok(d * w.get(p.x)? * w.get(q.x)? * w.get(p.y)? * w.get(q.y)?);
+        let r = PointVar {
+            // x3 = (x1·y2 + y1·x2) / (1 + t)
+            x: cs.assign(move |w| {
+                let numerator = w.get(p.x)? * w.get(q.y)? + w.get(p.y)? * w.get(q.x)?;
+                Ok(numerator * w.inverse(Fr::one() + t(w)?)?)
+            }),
+            // y3 = (y1·y2 - a·x1·x2) / (1 - t)
+            y: cs.assign(move |w| {
+                let numerator = w.get(p.y)? * w.get(q.y)? - a * w.get(p.x)? * w.get(q.x)?;
+                Ok(numerator * w.inverse(Fr::one() - t(w)?)?)
+            }),
+        };
+        cs.custom_gate(self.gate, vec![Fr::one()], &[p.x, p.y, q.x]);
+        cs.add_gate(Gate::disabled(), &[q.y, r.x, r.y]);
+        r
+    }
+
+    pub fn double(&self, cs: &mut ConstraintSystem, p: PointVar) -> PointVar {
+        self.add(cs, p, p)
+    }
+
+    /// Returns `p` if `condition` is 1 and `q` if it is 0.
+    pub fn select(
+        &self,
+        cs: &mut ConstraintSystem,
+        condition: Variable,
+        p: PointVar,
+        q: PointVar,
+    ) -> PointVar {
+        PointVar {
+            x: select(cs, condition, p.x, q.x),
+            y: select(cs, condition, p.y, q.y),
+        }
+    }
+
+    /// Returns Σ 2ⁱ·bitᵢ·p by double-and-add from the top bit. The bits
+    /// must be boolean, least significant first.
+    pub fn scalar_mul(
+        &self,
+        cs: &mut ConstraintSystem,
+        bits: &[Variable],
+        p: PointVar,
+    ) -> PointVar {
+        let mut acc = self.identity(cs);
+        for &bit in bits.iter().rev() {
+            acc = self.double(cs, acc);
+            let sum = self.add(cs, acc, p);
+            acc = self.select(cs, bit, sum, acc);
+        }
+        acc
+    }
+
+    /// Returns 2ᵏ·p.
+    pub fn double_times(&self, cs: &mut ConstraintSystem, p: PointVar, k: usize) -> PointVar {
+        (0..k).fold(p, |acc, _| self.double(cs, acc))
+    }
+
+    pub fn assert_equal(&self, cs: &mut ConstraintSystem, p: PointVar, q: PointVar) {
+        assert_equal(cs, p.x, q.x);
+        assert_equal(cs, p.y, q.y);
+    }
+}
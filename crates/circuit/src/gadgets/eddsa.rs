@@ -0,0 +1,81 @@
+use ark_bls12_381::Fr;
+use ark_ec::AffineRepr;
+use ark_ed_on_bls12_381::{EdwardsAffine, Fr as JubjubScalar};
+use ark_ff::PrimeField;
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::custom_gates::EdwardsAddGate;
+use crate::eddsa::{Signature, scalar_to_base};
+use crate::gadgets::comparison::assert_less_than;
+use crate::gadgets::ecc::{EdwardsCurve, PointVar};
+use crate::gadgets::poseidon::hash;
+use crate::gadgets::range::{to_bits, to_bits_canonical};
+use crate::poseidon::PoseidonParams;
+
+/// Signature allocated as private variables.
+#[derive(Clone, Copy, Debug)]
+pub struct SignatureVar {
+    pub r: PointVar,
+    /// s as an integer below the Jubjub group order
+    pub s: Variable,
+}
+
+impl SignatureVar {
+    pub fn alloc(cs: &mut ConstraintSystem, curve: &EdwardsCurve, signature: &Signature) -> Self {
+        SignatureVar {
+            r: curve.alloc_point(cs, point(&signature.r)),
+            s: cs.alloc(scalar_to_base(signature.s)),
+        }
+    }
+}
+
+/// Coordinates of a Jubjub point.
+pub fn point(p: &EdwardsAffine) -> (Fr, Fr) {
+    (p.x, p.y)
+}
+
+/// Jubjub with its addition gate registered in `cs`.
+pub fn jubjub(cs: &mut ConstraintSystem) -> EdwardsCurve {
+    EdwardsCurve::new(cs, EdwardsAddGate::jubjub())
+}
+
+/// Constrains `signature` to be a valid signature on `message` under
+/// `public_key`, i.e. 8·s·B = 8·R + 8·c·A with c = Poseidon(R, A, m)
+/// and s below the Jubjub group order ℓ.
+///
+/// The public key must be on the curve, e.g. allocated with
+/// [`EdwardsCurve::alloc_point`] or [`EdwardsCurve::public_point`].
+pub fn verify(
+    cs: &mut ConstraintSystem,
+    curve: &EdwardsCurve,
+    public_key: PointVar,
+    message: Variable,
+    signature: &SignatureVar,
+) {
+    let r = signature.r;
+    let c = hash(
+        cs,
+        PoseidonParams::standard(),
+        &[r.x, r.y, public_key.x, public_key.y, message],
+    );
+    let c_bits = to_bits_canonical(cs, c);
+    let s_bits = to_bits(cs, signature.s, JubjubScalar::MODULUS_BIT_SIZE as usize);
+    // s + ℓ passes the equation too, so only accept s below the group order
+    let order = cs.constant(Fr::from(JubjubScalar::MODULUS));
+    assert_less_than(
+        cs,
+        signature.s,
+        order,
+        JubjubScalar::MODULUS_BIT_SIZE as usize,
+    );
+
+    let generator = curve.constant_point(cs, point(&EdwardsAffine::generator()));
+    let lhs = curve.scalar_mul(cs, &s_bits, generator);
+    let c_a = curve.scalar_mul(cs, &c_bits, public_key);
+    let rhs = curve.add(cs, r, c_a);
+
+    // Clear the cofactor 8 on both sides
+    let lhs = curve.double_times(cs, lhs, 3);
+    let rhs = curve.double_times(cs, rhs, 3);
+    curve.assert_equal(cs, lhs, rhs);
+}
@@ -0,0 +1,55 @@
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::gadgets::arithmetic::{assert_equal, select};
+use crate::gadgets::boolean::alloc_bit;
+use crate::gadgets::poseidon::hash_two;
+use crate::merkle::MerklePath;
+
+/// Authentication path allocated as private variables.
+#[derive(Clone, Debug)]
+pub struct MerklePathVars {
+    pub siblings: Vec<Variable>,
+    /// Boolean index bits, least significant first
+    pub index_bits: Vec<Variable>,
+}
+
+impl MerklePathVars {
+    /// Allocates a path, constraining every index bit to be boolean.
+    pub fn alloc(cs: &mut ConstraintSystem, path: &MerklePath) -> Self {
+        MerklePathVars {
+            siblings: path.siblings.iter().map(|&s| cs.alloc(s)).collect(),
+            index_bits: path.index_bits.iter().map(|&b| alloc_bit(cs, b)).collect(),
+        }
+    }
+}
+
+/// Hashes `leaf` up the path and returns the root.
+///
+/// At each level the index bit picks whether the current node is the
+/// left or the right input of the hash.
+pub fn compute_root(cs: &mut ConstraintSystem, leaf: Variable, path: &MerklePathVars) -> Variable {
+    assert_eq!(
+        path.siblings.len(),
+        path.index_bits.len(),
+        "Merkle path needs one index bit per sibling"
+    );
+
+    path.siblings
+        .iter()
+        .zip(&path.index_bits)
+        .fold(leaf, |node, (&sibling, &is_right)| {
+            let left = select(cs, is_right, sibling, node);
+            let right = select(cs, is_right, node, sibling);
+            hash_two(cs, left, right)
+        })
+}
+
+/// Constrains `leaf` to sit in the tree with the given `root`.
+pub fn assert_membership(
+    cs: &mut ConstraintSystem,
+    root: Variable,
+    leaf: Variable,
+    path: &MerklePathVars,
+) {
+    let computed = compute_root(cs, leaf, path);
+    assert_equal(cs, computed, root);
+}
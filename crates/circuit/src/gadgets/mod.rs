@@ -0,0 +1,16 @@
+//! Reusable sub-circuits built from standard gates.
+//!
+//! Every gadget takes the constraint system it adds rows to, computes the
+//! witness values of the variables it allocates and returns them. Inputs
+//! are assumed to be constrained elsewhere unless a gadget says otherwise.
+
+pub mod arithmetic;
+pub mod boolean;
+pub mod comparison;
+pub mod ecc;
+pub mod eddsa;
+pub mod merkle;
+pub mod poseidon;
+pub mod range;
+pub mod sha256;
+pub mod transcript;
@@ -0,0 +1,73 @@
+use ark_bls12_381::Fr;
+use ark_ff::{Field, One, Zero};
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::custom_gates::Pow5Gate;
+use crate::gadgets::arithmetic::linear_combination;
+use crate::gate::CustomGate;
+use crate::poseidon::{ALPHA, PoseidonParams, RATE, WIDTH};
+
+/// Applies the Poseidon permutation in-circuit.
+///
+/// Each S-box is one [`Pow5Gate`] row. Round constants are folded into the
+/// linear rows of the previous round, so a round costs its S-boxes plus one
+/// linear combination per state element.
+pub fn permute(
+    cs: &mut ConstraintSystem,
+    params: &PoseidonParams,
+    state: [Variable; WIDTH],
+) -> [Variable; WIDTH] {
+    let pow5 = cs
+        .find_gate(CustomGate::<Fr>::name(&Pow5Gate))
+        .unwrap_or_else(|| cs.register_gate(Pow5Gate));
+
+    // Invariant: at the start of round r, `state` already includes the
+    // round's constants
+    let mut state: [Variable; WIDTH] = std::array::from_fn(|i| {
+        linear_combination(cs, &[(Fr::one(), state[i])], params.round_constants[0][i])
+    });
+
+    for r in 0..params.num_rounds() {
+        let sboxed: [Variable; WIDTH] = std::array::from_fn(|i| {
+            if i == 0 || params.is_full_round(r) {
+                let input = state[i];
+                let out = cs.assign(move |w| Ok(w.get(input)?.pow([ALPHA])));
+                cs.custom_gate(pow5, vec![Fr::one()], &[state[i], cs.zero(), out]);
+                out
+            } else {
+                state[i]
+            }
+        });
+
+        let next_constants = params
+            .round_constants
+            .get(r + 1)
+            .copied()
+            .unwrap_or([Fr::zero(); WIDTH]);
+        state = std::array::from_fn(|i| {
+            let terms: Vec<(Fr, Variable)> = params.mds[i].iter().copied().zip(sboxed).collect();
+            linear_combination(cs, &terms, next_constants[i])
+        });
+    }
+
+    state
+}
+
+/// In-circuit counterpart of [`PoseidonParams::hash`].
+pub fn hash(cs: &mut ConstraintSystem, params: &PoseidonParams, inputs: &[Variable]) -> Variable {
+    let mut state = [cs.zero(); WIDTH];
+    for (k, chunk) in inputs.chunks(RATE).enumerate() {
+        if k > 0 {
+            state = permute(cs, params, state);
+        }
+        for (x, &input) in state[WIDTH - RATE..].iter_mut().zip(chunk) {
+            *x = cs.add(*x, input);
+        }
+    }
+    permute(cs, params, state)[WIDTH - RATE]
+}
+
+/// Two-to-one hash with the standard parameters.
+pub fn hash_two(cs: &mut ConstraintSystem, left: Variable, right: Variable) -> Variable {
+    hash(cs, PoseidonParams::standard(), &[left, right])
+}
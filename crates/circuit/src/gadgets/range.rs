@@ -0,0 +1,137 @@
+use ark_bls12_381::Fr;
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::gadgets::arithmetic::assert_equal;
+use crate::gadgets::boolean::assert_boolean;
+use crate::gate::Gate;
+use crate::lookup::TableId;
+
+/// Decomposes x into `num_bits` boolean variables, least significant
+/// first, and constrains Σ 2ⁱ·bitᵢ = x.
+///
+/// This doubles as a range check: the circuit is unsatisfiable when
+/// x ≥ 2^num_bits. `num_bits` must stay below the field size in bits.
+pub fn to_bits(cs: &mut ConstraintSystem, x: Variable, num_bits: usize) -> Vec<Variable> {
+    assert!(
+        num_bits < Fr::MODULUS_BIT_SIZE as usize,
+        "Decomposing into {num_bits} bits does not fit the field"
+    );
+    let bits = decompose(cs, x, num_bits);
+    let sum = weighted_sum(cs, &bits, Fr::from(2u64));
+    assert_equal(cs, sum, x);
+    bits
+}
+
+/// Decomposes x into all `Fr::MODULUS_BIT_SIZE` bits, least significant
+/// first, and constrains the bits to read a number below the modulus.
+///
+/// Without the last check x + p could pass for x whenever it still fits
+/// in the bits, so the decomposition would not be unique.
+pub fn to_bits_canonical(cs: &mut ConstraintSystem, x: Variable) -> Vec<Variable> {
+    let bits = decompose(cs, x, Fr::MODULUS_BIT_SIZE as usize);
+    let sum = weighted_sum(cs, &bits, Fr::from(2u64));
+    assert_equal(cs, sum, x);
+    assert_canonical(cs, &bits);
+    bits
+}
+
+/// Constrains little-endian bits to read at most p - 1, where p is the
+/// field modulus.
+///
+/// Scanning from the top, `equal` tracks whether the bits so far match
+/// p - 1. Wherever p - 1 has a 0, a matching prefix forces the bit to 0.
+pub fn assert_canonical(cs: &mut ConstraintSystem, bits: &[Variable]) {
+    let bound = (-Fr::one()).into_bigint().to_bits_le();
+    let nand = Gate {
+        q_m: Fr::one(),
+        ..Gate::disabled()
+    };
+
+    let mut equal: Option<Variable> = None;
+    for (i, &bit) in bits.iter().enumerate().rev() {
+        if bound[i] {
+            equal = Some(match equal {
+                Some(prefix) => cs.mul(prefix, bit),
+                None => bit,
+            });
+        } else if let Some(prefix) = equal {
+            cs.add_gate(nand.clone(), &[prefix, bit]);
+        }
+    }
+}
+
+/// Constrains x < 2^num_bits bit by bit.
+pub fn range_check(cs: &mut ConstraintSystem, x: Variable, num_bits: usize) {
+    to_bits(cs, x, num_bits);
+}
+
+/// Constrains x < 2^(limb_bits·num_limbs) by splitting it into limbs and
+/// looking each one up in `table`, which must be
+/// [`LookupTable::range(limb_bits)`](crate::lookup::LookupTable::range).
+///
+/// Costs one row per limb plus the recombination, instead of two rows per
+/// bit for [`range_check`].
+pub fn range_check_lookup(
+    cs: &mut ConstraintSystem,
+    table: TableId,
+    limb_bits: usize,
+    x: Variable,
+    num_limbs: usize,
+) -> Vec<Variable> {
+    assert!(
+        limb_bits * num_limbs < Fr::MODULUS_BIT_SIZE as usize,
+        "Decomposing into {} bits does not fit the field",
+        limb_bits * num_limbs
+    );
+    let limbs: Vec<Variable> = (0..num_limbs)
+        .map(|k| {
+            let limb = cs.assign(move |w| {
+                let bits = w.get(x)?.into_bigint().to_bits_le();
+                let value = bits[k * limb_bits..(k + 1) * limb_bits]
+                    .iter()
+                    .rev()
+                    .fold(0u64, |acc, &bit| (acc << 1) | bit as u64);
+                Ok(Fr::from(value))
+            });
+            cs.lookup(table, &[limb]);
+            limb
+        })
+        .collect();
+
+    let sum = weighted_sum(cs, &limbs, Fr::from(1u64 << limb_bits));
+    assert_equal(cs, sum, x);
+    limbs
+}
+
+/// Allocates the low `num_bits` bits of x, least significant first, and
+/// constrains each one to be boolean.
+fn decompose(cs: &mut ConstraintSystem, x: Variable, num_bits: usize) -> Vec<Variable> {
+    (0..num_bits)
+        .map(|i| {
+            let bit = cs.assign(move |w| Ok(Fr::from(w.get(x)?.into_bigint().get_bit(i))));
+            assert_boolean(cs, bit);
+            bit
+        })
+        .collect()
+}
+
+/// Returns Σ baseⁱ·termsᵢ, one addition row per term after the first.
+fn weighted_sum(cs: &mut ConstraintSystem, terms: &[Variable], base: Fr) -> Variable {
+    let Some((&first, rest)) = terms.split_first() else {
+        return cs.zero();
+    };
+
+    let mut sum = first;
+    let mut weight = Fr::one();
+    for &term in rest {
+        weight *= base;
+        let next = cs.assign(move |w| Ok(w.get(sum)? + weight * w.get(term)?));
+        cs.add_gate(
+            Gate::linear(&[Fr::one(), weight, -Fr::one()], Fr::zero()),
+            &[sum, term, next],
+        );
+        sum = next;
+    }
+    sum
+}
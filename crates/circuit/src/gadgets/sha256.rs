@@ -0,0 +1,211 @@
+//! SHA-256 over bit-decomposed words.
+//!
+//! Words are kept as 32 boolean variables so rotations and shifts are
+//! free rewirings. Bitwise functions cost a few rows per bit; modular
+//! additions pack their operands into one field element and decompose the
+//! sum back into bits, dropping the carries.
+
+use ark_bls12_381::Fr;
+use ark_ff::{One, Zero};
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::gadgets::arithmetic::{linear_combination, sub};
+use crate::gadgets::boolean::{alloc_bit, xor};
+use crate::gadgets::range::to_bits;
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Initial hash value H⁽⁰⁾.
+pub const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// 32-bit word as boolean variables, least significant bit first.
+#[derive(Clone, Copy, Debug)]
+pub struct Word(pub [Variable; 32]);
+
+impl Word {
+    /// Allocates a private word, constraining every bit.
+    pub fn alloc(cs: &mut ConstraintSystem, value: u32) -> Self {
+        Word(std::array::from_fn(|i| {
+            alloc_bit(cs, (value >> i) & 1 == 1)
+        }))
+    }
+
+    /// Builds a fixed word from the zero variable and a variable pinned to 1.
+    pub fn constant(zero: Variable, one: Variable, value: u32) -> Self {
+        Word(std::array::from_fn(|i| {
+            if (value >> i) & 1 == 1 { one } else { zero }
+        }))
+    }
+
+    pub fn value(&self, cs: &ConstraintSystem) -> u32 {
+        self.0.iter().enumerate().fold(0, |acc, (i, &bit)| {
+            acc | (u32::from(!cs.value(bit).is_zero()) << i)
+        })
+    }
+
+    /// Rotates right by `n` bits.
+    pub fn rotr(&self, n: usize) -> Self {
+        Word(std::array::from_fn(|i| self.0[(i + n) % 32]))
+    }
+
+    /// Shifts right by `n` bits, filling with zero.
+    pub fn shr(&self, zero: Variable, n: usize) -> Self {
+        Word(std::array::from_fn(|i| {
+            self.0.get(i + n).copied().unwrap_or(zero)
+        }))
+    }
+}
+
+fn xor_words(cs: &mut ConstraintSystem, a: &Word, b: &Word) -> Word {
+    Word(std::array::from_fn(|i| xor(cs, a.0[i], b.0[i])))
+}
+
+fn xor3(cs: &mut ConstraintSystem, a: &Word, b: &Word, c: &Word) -> Word {
+    let ab = xor_words(cs, a, b);
+    xor_words(cs, &ab, c)
+}
+
+/// Ch(e, f, g) = g + e·(f - g), bit by bit.
+fn ch(cs: &mut ConstraintSystem, e: &Word, f: &Word, g: &Word) -> Word {
+    Word(std::array::from_fn(|i| {
+        let difference = sub(cs, f.0[i], g.0[i]);
+        let chosen = cs.mul(e.0[i], difference);
+        cs.add(g.0[i], chosen)
+    }))
+}
+
+/// Maj(a, b, c) = a·b + c·(a ⊕ b), bit by bit.
+fn maj(cs: &mut ConstraintSystem, a: &Word, b: &Word, c: &Word) -> Word {
+    Word(std::array::from_fn(|i| {
+        let both = cs.mul(a.0[i], b.0[i]);
+        let either = xor(cs, a.0[i], b.0[i]);
+        let third = cs.mul(c.0[i], either);
+        cs.add(both, third)
+    }))
+}
+
+/// Returns (Σ words + constant) mod 2³².
+fn add_mod(cs: &mut ConstraintSystem, words: &[&Word], constant: u32) -> Word {
+    let terms: Vec<(Fr, Variable)> = words
+        .iter()
+        .flat_map(|word| {
+            word.0
+                .iter()
+                .enumerate()
+                .map(|(i, &bit)| (Fr::from(1u64 << i), bit))
+        })
+        .collect();
+    let sum = linear_combination(cs, &terms, Fr::from(constant));
+
+    // The sum of k + 1 words needs ⌈log₂(k + 1)⌉ carry bits
+    let carry_bits = (words.len() + 1).next_power_of_two().trailing_zeros() as usize;
+    let bits = to_bits(cs, sum, 32 + carry_bits);
+    Word(std::array::from_fn(|i| bits[i]))
+}
+
+/// Applies the compression function to one 16-word block.
+pub fn compress(cs: &mut ConstraintSystem, state: &[Word; 8], block: &[Word; 16]) -> [Word; 8] {
+    let zero = cs.zero();
+
+    let mut schedule: Vec<Word> = block.to_vec();
+    for t in 16..64 {
+        let w15 = schedule[t - 15];
+        let w2 = schedule[t - 2];
+        let s0 = xor3(cs, &w15.rotr(7), &w15.rotr(18), &w15.shr(zero, 3));
+        let s1 = xor3(cs, &w2.rotr(17), &w2.rotr(19), &w2.shr(zero, 10));
+        let next = add_mod(cs, &[&s1, &schedule[t - 7], &s0, &schedule[t - 16]], 0);
+        schedule.push(next);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (t, w) in schedule.iter().enumerate() {
+        let big_s1 = xor3(cs, &e.rotr(6), &e.rotr(11), &e.rotr(25));
+        let choice = ch(cs, &e, &f, &g);
+        let big_s0 = xor3(cs, &a.rotr(2), &a.rotr(13), &a.rotr(22));
+        let majority = maj(cs, &a, &b, &c);
+
+        // T1 = h + Σ1 + Ch + K + W, T2 = Σ0 + Maj
+        let t1_terms = [&h, &big_s1, &choice, w];
+        let new_e = add_mod(
+            cs,
+            &[t1_terms.as_slice(), &[&d]].concat(),
+            ROUND_CONSTANTS[t],
+        );
+        let new_a = add_mod(
+            cs,
+            &[t1_terms.as_slice(), &[&big_s0, &majority]].concat(),
+            ROUND_CONSTANTS[t],
+        );
+
+        h = g;
+        g = f;
+        f = e;
+        e = new_e;
+        d = c;
+        c = b;
+        b = a;
+        a = new_a;
+    }
+
+    let working = [a, b, c, d, e, f, g, h];
+    std::array::from_fn(|i| add_mod(cs, &[&state[i], &working[i]], 0))
+}
+
+/// Hashes a message given as bits in stream order, i.e. the most
+/// significant bit of the first byte first, and returns the digest words.
+///
+/// The message length is fixed by the circuit, so padding is made of
+/// constant bits.
+pub fn sha256(cs: &mut ConstraintSystem, message: &[Variable]) -> [Word; 8] {
+    let zero = cs.zero();
+    let one = cs.constant(Fr::one());
+
+    // message ‖ 1 ‖ 0…0 ‖ 64-bit length, to a multiple of 512 bits
+    let mut bits = message.to_vec();
+    bits.push(one);
+    while bits.len() % 512 != 448 {
+        bits.push(zero);
+    }
+    let length = message.len() as u64;
+    bits.extend(
+        (0..64)
+            .rev()
+            .map(|i| if (length >> i) & 1 == 1 { one } else { zero }),
+    );
+
+    let mut state = IV.map(|value| Word::constant(zero, one, value));
+    for block in bits.chunks(512) {
+        let words: [Word; 16] =
+            std::array::from_fn(|k| Word(std::array::from_fn(|i| block[32 * k + 31 - i])));
+        state = compress(cs, &state, &words);
+    }
+    state
+}
+
+/// Allocates the bits of `bytes` in stream order, constraining each one.
+pub fn alloc_message(cs: &mut ConstraintSystem, bytes: &[u8]) -> Vec<Variable> {
+    bytes
+        .iter()
+        .flat_map(|&byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+        .map(|bit| alloc_bit(cs, bit))
+        .collect()
+}
+
+/// Digest bytes of a hashed state, read from the witness.
+pub fn digest_bytes(cs: &ConstraintSystem, digest: &[Word; 8]) -> Vec<u8> {
+    digest
+        .iter()
+        .flat_map(|word| word.value(cs).to_be_bytes())
+        .collect()
+}
@@ -0,0 +1,42 @@
+//! In-circuit Fiat-Shamir transcript.
+//!
+//! Mirrors `proof::transcript::PoseidonTranscript`: every message is a
+//! label element followed by its field elements, and a challenge is the
+//! Poseidon hash of everything absorbed since the previous one, which then
+//! restarts the transcript. Group elements are absorbed as the limbs the
+//! native transcript splits them into; the caller constrains the limbs.
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::gadgets::poseidon;
+use crate::poseidon::{PoseidonParams, label_element};
+
+#[derive(Clone, Debug)]
+pub struct TranscriptVar {
+    elements: Vec<Variable>,
+}
+
+impl TranscriptVar {
+    pub fn new(cs: &mut ConstraintSystem, label: &[u8]) -> Self {
+        TranscriptVar {
+            elements: vec![cs.constant(label_element(label))],
+        }
+    }
+
+    /// Absorbs a commitment given as its limbs.
+    pub fn append_point(&mut self, cs: &mut ConstraintSystem, label: &[u8], limbs: &[Variable]) {
+        self.elements.push(cs.constant(label_element(label)));
+        self.elements.extend(limbs);
+    }
+
+    pub fn append_scalar(&mut self, cs: &mut ConstraintSystem, label: &[u8], scalar: Variable) {
+        self.elements.push(cs.constant(label_element(label)));
+        self.elements.push(scalar);
+    }
+
+    pub fn challenge(&mut self, cs: &mut ConstraintSystem, label: &[u8]) -> Variable {
+        self.elements.push(cs.constant(label_element(label)));
+        let challenge = poseidon::hash(cs, PoseidonParams::standard(), &self.elements);
+        self.elements = vec![challenge];
+        challenge
+    }
+}
@@ -1,32 +1,38 @@
+use std::fmt::Debug;
+
 use ark_bls12_381::Fr;
-use ark_ff::{Field, One, Zero};
+use ark_ff::PrimeField;
 
-#[derive(Clone, Debug)]
-pub struct Gate {
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gate<F: PrimeField = Fr> {
     /// Left wire selector
-    pub q_l: Fr,
+    pub q_l: F,
     /// Right wire selector
-    pub q_r: Fr,
+    pub q_r: F,
     /// Output wire selector
-    pub q_o: Fr,
+    pub q_o: F,
     /// Multiplication selector
-    pub q_m: Fr,
+    pub q_m: F,
     /// Constant selector
-    pub q_c: Fr,
+    pub q_c: F,
+    /// Linear selectors for the wires after c (q_4, q_5, …); missing
+    /// entries are zero
+    pub q_extra: Vec<F>,
 }
 
-impl Gate {
+impl<F: PrimeField> Gate<F> {
     /// Creates a multiplication gate: a * b = c
     ///
     /// Gate equation: 0·a + 0·b + (-1)·c + 1·(a·b) + 0 = 0
     /// Simplifies to: a·b - c = 0, i.e., a·b = c
     pub fn multiplication() -> Self {
         Gate {
-            q_l: Fr::zero(),
-            q_r: Fr::zero(),
-            q_o: -Fr::one(),
-            q_m: Fr::one(),
-            q_c: Fr::zero(),
+            q_l: F::zero(),
+            q_r: F::zero(),
+            q_o: -F::one(),
+            q_m: F::one(),
+            q_c: F::zero(),
+            q_extra: Vec::new(),
         }
     }
 
@@ -34,23 +40,156 @@ impl Gate {
     ///
     /// Gate equation: 1·a + 1·b + (-1)·c + 0·(a·b) + 0 = 0
     /// Simplifies to: a + b - c = 0, i.e., a + b = c
-    #[allow(dead_code)]
     pub fn addition() -> Self {
         Gate {
-            q_l: Fr::one(),
-            q_r: Fr::one(),
-            q_o: -Fr::one(),
-            q_m: Fr::zero(),
-            q_c: Fr::zero(),
+            q_l: F::one(),
+            q_r: F::one(),
+            q_o: -F::one(),
+            q_m: F::zero(),
+            q_c: F::zero(),
+            q_extra: Vec::new(),
+        }
+    }
+
+    /// Creates a constant gate: a = value
+    ///
+    /// Gate equation: 1·a + 0·b + 0·c + 0·(a·b) + (-value) = 0
+    pub fn constant(value: F) -> Self {
+        Gate {
+            q_l: F::one(),
+            q_r: F::zero(),
+            q_o: F::zero(),
+            q_m: F::zero(),
+            q_c: -value,
+            q_extra: Vec::new(),
         }
     }
 
+    /// Creates a public input gate: a = PI(X)
+    ///
+    /// Gate equation: 1·a + PI = 0, where the prover and verifier both
+    /// fill PI with the negated public value for this row.
+    pub fn public_input() -> Self {
+        Self::constant(F::zero())
+    }
+
+    /// Creates a gate with every selector off. Used for rows that only
+    /// carry wires for a custom gate, and for padding.
+    pub fn disabled() -> Self {
+        Gate {
+            q_l: F::zero(),
+            q_r: F::zero(),
+            q_o: F::zero(),
+            q_m: F::zero(),
+            q_c: F::zero(),
+            q_extra: Vec::new(),
+        }
+    }
+
+    /// Creates a linear combination gate over every wire:
+    /// Σ coeffᵢ·wᵢ + constant = 0
+    ///
+    /// With four or more wires this folds a whole sum into one row.
+    pub fn linear(coeffs: &[F], constant: F) -> Self {
+        let coeff = |i: usize| coeffs.get(i).copied().unwrap_or(F::zero());
+        Gate {
+            q_l: coeff(0),
+            q_r: coeff(1),
+            q_o: coeff(2),
+            q_m: F::zero(),
+            q_c: constant,
+            q_extra: coeffs.iter().skip(3).copied().collect(),
+        }
+    }
+
+    /// Selector values in column order: q_l, q_r, q_o, q_m, q_c, then one
+    /// linear selector per wire after c, for a row of `num_wires` wires.
+    pub fn selectors(&self, num_wires: usize) -> Vec<F> {
+        let mut selectors = vec![self.q_l, self.q_r, self.q_o, self.q_m, self.q_c];
+        selectors
+            .extend((0..num_wires - 3).map(|i| self.q_extra.get(i).copied().unwrap_or(F::zero())));
+        selectors
+    }
+
+    /// Builds a gate from selector values in column order.
+    pub fn from_selectors(selectors: &[F]) -> Self {
+        Gate {
+            q_l: selectors[0],
+            q_r: selectors[1],
+            q_o: selectors[2],
+            q_m: selectors[3],
+            q_c: selectors[4],
+            q_extra: selectors[5..].to_vec(),
+        }
+    }
+
+    /// Evaluates qL·a + qR·b + qO·c + qM·(a·b) + qC
+    pub fn evaluate(&self, a: F, b: F, c: F) -> F {
+        self.evaluate_row(&[a, b, c])
+    }
+
+    /// Evaluates the gate over a row of any width:
+    /// qL·a + qR·b + qO·c + qM·(a·b) + qC + Σ q_extraᵢ·w₃₊ᵢ
+    pub fn evaluate_row(&self, wires: &[F]) -> F {
+        let (a, b, c) = (wires[0], wires[1], wires[2]);
+        let extra: F = self
+            .q_extra
+            .iter()
+            .zip(&wires[3..])
+            .map(|(q, w)| *q * w)
+            .sum();
+
+        self.q_l * a + self.q_r * b + self.q_o * c + self.q_m * (a * b) + self.q_c + extra
+    }
+
     /// Check if wire values satisfy this gate's constraint.
     ///
     /// Returns true if qL·a + qR·b + qO·c + qM·(a·b) + qC = 0
-    pub fn is_satisfied(&self, a: Fr, b: Fr, c: Fr) -> bool {
-        let result = self.q_l * a + self.q_r * b + self.q_o * c + self.q_m * (a * b) + self.q_c;
+    pub fn is_satisfied(&self, a: F, b: F, c: F) -> bool {
+        self.evaluate(a, b, c).is_zero()
+    }
+}
 
-        result.is_zero()
+/// A gate that brings its own selector columns and polynomial identity.
+///
+/// Every selector column declared here becomes a preprocessed polynomial
+/// that is committed in the verifying key. The prover adds the gate's
+/// constraints to the quotient and the verifier re-evaluates them at ζ,
+/// so implementing this trait is all that is needed to use a new gate.
+///
+/// Constraints must already be multiplied by the gate's selectors so that
+/// they vanish on rows where the gate is off.
+pub trait CustomGate<F: PrimeField = Fr>: Debug + Send + Sync {
+    /// Short identifier, unique among the gates of one circuit.
+    fn name(&self) -> &'static str;
+
+    /// Names of the selector columns owned by this gate.
+    fn selectors(&self) -> &'static [&'static str];
+
+    /// Named constants the gate is built from, e.g. curve coefficients.
+    /// Circuit files record them so the gate can be rebuilt.
+    fn params(&self) -> Vec<(&'static str, F)> {
+        Vec::new()
+    }
+
+    /// Number of wires per row the gate reads.
+    fn num_wires(&self) -> usize {
+        3
+    }
+
+    /// Total degree of the identity in selectors and wires.
+    ///
+    /// Sizes the domain used to compute the quotient polynomial.
+    fn degree(&self) -> usize;
+
+    /// Number of values returned by [`CustomGate::evaluate`].
+    fn num_constraints(&self) -> usize {
+        1
     }
+
+    /// Evaluates every constraint of this gate on one row.
+    ///
+    /// `selectors` holds this gate's selector values, `wires` the row's
+    /// wires `[a, b, c, …]` and `next` the wires of the following row.
+    fn evaluate(&self, selectors: &[F], wires: &[F], next: &[F]) -> Vec<F>;
 }
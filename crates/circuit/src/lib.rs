@@ -1,3 +1,16 @@
+pub mod constraint_system;
+pub mod custom_gates;
+pub mod eddsa;
+pub mod format;
+pub mod gadgets;
 pub mod gate;
+pub mod lookup;
+pub mod merkle;
+pub mod mock_prover;
+pub mod optimizer;
+pub mod poseidon;
+pub mod r1cs;
+pub mod serialization;
 pub mod square_circuit;
+pub mod stats;
 pub mod witness;
@@ -0,0 +1,80 @@
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+
+/// Handle to a lookup table registered with a constraint system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TableId(pub(crate) usize);
+
+impl TableId {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    /// Value of the q_table selector on rows that query this table.
+    ///
+    /// Tags start at 1 so that rows without a lookup never match a table.
+    pub fn tag<F: PrimeField>(&self) -> F {
+        F::from(self.0 as u64 + 1)
+    }
+}
+
+/// Fixed table of tuples. A lookup row asserts that its first wires hold
+/// one of the table's rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupTable<F: PrimeField = Fr> {
+    pub name: String,
+    pub rows: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> LookupTable<F> {
+    pub fn new(name: impl Into<String>, rows: Vec<Vec<F>>) -> Self {
+        let name = name.into();
+        assert!(!rows.is_empty(), "Lookup table {name} is empty");
+        assert!(
+            rows.iter().all(|row| row.len() == rows[0].len()),
+            "Lookup table {name} has rows of different widths"
+        );
+
+        LookupTable { name, rows }
+    }
+
+    /// Every value in [0, 2^bits), one per row.
+    pub fn range(bits: u32) -> Self {
+        let rows = (0..1u64 << bits).map(|v| vec![F::from(v)]).collect();
+        Self::new(format!("range_{bits}"), rows)
+    }
+
+    /// Rows (x, y, x ⊕ y) for every pair of `bits`-bit values.
+    pub fn xor(bits: u32) -> Self {
+        let size = 1u64 << bits;
+        let rows = (0..size)
+            .flat_map(|x| (0..size).map(move |y| vec![F::from(x), F::from(y), F::from(x ^ y)]))
+            .collect();
+        Self::new(format!("xor_{bits}"), rows)
+    }
+
+    /// Rows (x, y, x ∧ y) for every pair of `bits`-bit values.
+    pub fn and(bits: u32) -> Self {
+        let size = 1u64 << bits;
+        let rows = (0..size)
+            .flat_map(|x| (0..size).map(move |y| vec![F::from(x), F::from(y), F::from(x & y)]))
+            .collect();
+        Self::new(format!("and_{bits}"), rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn contains(&self, values: &[F]) -> bool {
+        self.rows.iter().any(|row| row == values)
+    }
+}
@@ -0,0 +1,97 @@
+//! Fixed-depth binary Merkle tree over Poseidon, for building witnesses
+//! of the membership gadget in [`crate::gadgets::merkle`].
+
+use ark_bls12_381::Fr;
+use ark_ff::Zero;
+
+use crate::poseidon::hash_two;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// levels[0] holds the leaves, the last level holds the root
+    levels: Vec<Vec<Fr>>,
+}
+
+/// Authentication path from a leaf to the root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath {
+    /// Sibling at each level, from the leaves up
+    pub siblings: Vec<Fr>,
+    /// Bits of the leaf index, least significant first. A set bit means
+    /// the node is the right child at that level.
+    pub index_bits: Vec<bool>,
+}
+
+impl MerkleTree {
+    /// Builds a tree with 2^depth leaves, filling the missing ones with 0.
+    pub fn new(depth: usize, leaves: &[Fr]) -> Self {
+        assert!(
+            leaves.len() <= 1 << depth,
+            "A tree of depth {depth} holds at most {} leaves, got {}",
+            1usize << depth,
+            leaves.len()
+        );
+
+        let mut level = leaves.to_vec();
+        level.resize(1 << depth, Fr::zero());
+
+        let mut levels = vec![level];
+        for _ in 0..depth {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hash_two(pair[0], pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn root(&self) -> Fr {
+        self.levels[self.depth()][0]
+    }
+
+    pub fn leaf(&self, index: usize) -> Fr {
+        self.levels[0][index]
+    }
+
+    pub fn path(&self, index: usize) -> MerklePath {
+        assert!(index < self.levels[0].len(), "Leaf {index} is out of range");
+
+        let (siblings, index_bits) = (0..self.depth())
+            .map(|level| {
+                let position = index >> level;
+                (self.levels[level][position ^ 1], position & 1 == 1)
+            })
+            .unzip();
+        MerklePath {
+            siblings,
+            index_bits,
+        }
+    }
+}
+
+impl MerklePath {
+    /// Hashes `leaf` up the path.
+    pub fn compute_root(&self, leaf: Fr) -> Fr {
+        self.siblings
+            .iter()
+            .zip(&self.index_bits)
+            .fold(leaf, |node, (&sibling, &is_right)| {
+                if is_right {
+                    hash_two(sibling, node)
+                } else {
+                    hash_two(node, sibling)
+                }
+            })
+    }
+
+    pub fn verify(&self, root: Fr, leaf: Fr) -> bool {
+        self.compute_root(leaf) == root
+    }
+}
@@ -0,0 +1,289 @@
+//! Checks a circuit the way the prover would, without any cryptography,
+//! and reports every row and copy constraint that fails.
+//!
+//! The wire values are laid out over the padded domain exactly as the
+//! prover commits to them. Gates, lookups and the permutation then come
+//! from the layout, so a witness built against a different layout fails
+//! here where it would fail verification.
+
+use std::fmt;
+
+use ark_bls12_381::Fr;
+use ark_ff::Zero;
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::format::format_value;
+
+/// One identity the witness breaks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The standard gate, with the public input on public rows, is not zero
+    Gate {
+        row: usize,
+        annotation: Option<String>,
+        value: Fr,
+        wires: Vec<(String, Fr)>,
+    },
+    /// A constraint of a custom gate is not zero
+    CustomGate {
+        row: usize,
+        annotation: Option<String>,
+        gate: &'static str,
+        constraint: usize,
+        value: Fr,
+        wires: Vec<(String, Fr)>,
+    },
+    /// The row's wires are not a row of the table
+    Lookup {
+        row: usize,
+        annotation: Option<String>,
+        table: String,
+        wires: Vec<(String, Fr)>,
+    },
+    /// Wire positions holding one variable carry different values.
+    /// Positions are (row, wire) and compare against the first one.
+    Copy {
+        variable: String,
+        first: (usize, usize, Fr),
+        mismatched: Vec<(usize, usize, Fr)>,
+    },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = |f: &mut fmt::Formatter<'_>, row: &usize, annotation: &Option<String>| {
+            write!(f, "row {row}")?;
+            match annotation {
+                Some(note) => write!(f, " ({note})"),
+                None => Ok(()),
+            }
+        };
+        let wires = |wires: &[(String, Fr)]| {
+            wires
+                .iter()
+                .map(|(name, value)| format!("{name}={}", format_value(*value)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        match self {
+            Failure::Gate {
+                row,
+                annotation,
+                value,
+                wires: values,
+            } => {
+                location(f, row, annotation)?;
+                write!(
+                    f,
+                    ": gate evaluates to {} on {}",
+                    format_value(*value),
+                    wires(values)
+                )
+            }
+            Failure::CustomGate {
+                row,
+                annotation,
+                gate,
+                constraint,
+                value,
+                wires: values,
+            } => {
+                location(f, row, annotation)?;
+                write!(
+                    f,
+                    ": {gate} constraint {constraint} evaluates to {} on {}",
+                    format_value(*value),
+                    wires(values)
+                )
+            }
+            Failure::Lookup {
+                row,
+                annotation,
+                table,
+                wires: values,
+            } => {
+                location(f, row, annotation)?;
+                write!(f, ": {} is not in table {table}", wires(values))
+            }
+            Failure::Copy {
+                variable,
+                first: (row, wire, value),
+                mismatched,
+            } => {
+                write!(
+                    f,
+                    "copy of {variable}: {} at row {row} wire {wire}",
+                    format_value(*value)
+                )?;
+                for (row, wire, value) in mismatched {
+                    write!(f, ", {} at row {row} wire {wire}", format_value(*value))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Wire values of a witness, checked against a layout.
+pub struct MockProver<'a> {
+    layout: &'a ConstraintSystem,
+    /// Wire values per row over the padded domain
+    wires: Vec<Vec<Fr>>,
+    public_inputs: Vec<Fr>,
+}
+
+impl<'a> MockProver<'a> {
+    /// Checks a system against its own layout.
+    pub fn new(cs: &'a ConstraintSystem) -> Self {
+        Self::with_layout(cs, cs)
+    }
+
+    /// Checks the wire values and public inputs of `witness` against the
+    /// gates, lookups and copy constraints of `layout`, as proving
+    /// `witness` with a key preprocessed from `layout` would.
+    pub fn with_layout(layout: &'a ConstraintSystem, witness: &ConstraintSystem) -> Self {
+        assert_eq!(
+            layout.num_wires(),
+            witness.num_wires(),
+            "Layout and witness have different row widths"
+        );
+        let n = layout.domain_size();
+        assert!(
+            witness.rows().len() <= n,
+            "Witness has {} rows, the layout's domain {n}",
+            witness.rows().len()
+        );
+
+        let wires = (0..n)
+            .map(|i| {
+                if i < witness.rows().len() {
+                    witness.wire_values(i)
+                } else {
+                    vec![Fr::zero(); layout.num_wires()]
+                }
+            })
+            .collect();
+        MockProver {
+            layout,
+            wires,
+            public_inputs: witness.public_inputs(),
+        }
+    }
+
+    /// Replaces the public inputs the prover would use with the values a
+    /// verifier is given.
+    pub fn with_public_inputs(mut self, public_inputs: &[Fr]) -> Self {
+        self.public_inputs = public_inputs.to_vec();
+        self
+    }
+
+    /// Every failing gate, lookup and copy constraint, in row order with
+    /// copy constraints last.
+    pub fn verify(&self) -> Result<(), Vec<Failure>> {
+        let mut failures = Vec::new();
+        for i in 0..self.layout.rows().len() {
+            self.check_row(i, &mut failures);
+        }
+        self.check_copies(&mut failures);
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+
+    fn check_row(&self, i: usize, failures: &mut Vec<Failure>) {
+        let cs = self.layout;
+        let row = &cs.rows()[i];
+        let wires = &self.wires[i];
+        let next = &self.wires[(i + 1) % self.wires.len()];
+        let annotation = cs.annotation(i).map(str::to_string);
+        let named = |values: &[Fr]| -> Vec<(String, Fr)> {
+            row.wires
+                .iter()
+                .zip(values)
+                .map(|(&var, &value)| (cs.display_name(var), value))
+                .collect()
+        };
+
+        let mut value = row.gate.evaluate_row(wires);
+        if let Some(k) = cs.public_input_rows().iter().position(|&r| r == i) {
+            value -= self.public_inputs.get(k).copied().unwrap_or(Fr::zero());
+        }
+        if !value.is_zero() {
+            failures.push(Failure::Gate {
+                row: i,
+                annotation: annotation.clone(),
+                value,
+                wires: named(wires),
+            });
+        }
+
+        for (id, selectors) in &row.custom {
+            let gate = &cs.custom_gates()[id.index()];
+            for (constraint, value) in gate
+                .evaluate(selectors, wires, next)
+                .into_iter()
+                .enumerate()
+            {
+                if !value.is_zero() {
+                    failures.push(Failure::CustomGate {
+                        row: i,
+                        annotation: annotation.clone(),
+                        gate: gate.name(),
+                        constraint,
+                        value,
+                        wires: named(wires),
+                    });
+                }
+            }
+        }
+
+        if let Some(id) = row.lookup {
+            let table = &cs.tables()[id.index()];
+            let query = &wires[..table.width()];
+            if !table.rows.iter().any(|entry| entry == query) {
+                failures.push(Failure::Lookup {
+                    row: i,
+                    annotation,
+                    table: table.name.clone(),
+                    wires: named(query),
+                });
+            }
+        }
+    }
+
+    /// Compares the values along each variable's permutation cycle.
+    /// Padding rows belong to the zero variable's cycle.
+    fn check_copies(&self, failures: &mut Vec<Failure>) {
+        let cs = self.layout;
+        let mut positions: Vec<Vec<(usize, usize)>> = vec![Vec::new(); cs.num_variables()];
+        for i in 0..self.wires.len() {
+            for j in 0..cs.num_wires() {
+                let var = cs.rows().get(i).map_or(cs.zero(), |row| row.wires[j]);
+                positions[var.index()].push((i, j));
+            }
+        }
+
+        for (index, cycle) in positions.iter().enumerate() {
+            let Some(&(row, wire)) = cycle.first() else {
+                continue;
+            };
+            let expected = self.wires[row][wire];
+            let mismatched: Vec<(usize, usize, Fr)> = cycle
+                .iter()
+                .map(|&(i, j)| (i, j, self.wires[i][j]))
+                .filter(|&(_, _, value)| value != expected)
+                .collect();
+            if !mismatched.is_empty() {
+                failures.push(Failure::Copy {
+                    variable: cs.display_name(Variable::new(index)),
+                    first: (row, wire, expected),
+                    mismatched,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,411 @@
+//! Optimization passes over a built constraint system.
+//!
+//! Every pass keeps the set of satisfying witnesses the same on the
+//! variables that remain in use: a witness generated for the original
+//! circuit still satisfies the optimized one, and values the original
+//! rejects on kept variables stay rejected.
+//!
+//! Passes only rewrite or remove *free* rows: rows with a standard gate
+//! and nothing else, that are not public inputs, not row 0 and not read
+//! as the next row of a custom gate. Variables keep their indices, so
+//! witness generation is unaffected.
+
+use std::collections::{HashMap, HashSet};
+
+use ark_bls12_381::Fr;
+use ark_ff::{Field, Zero};
+
+use crate::constraint_system::{ConstraintSystem, DEFAULT_NUM_WIRES, Row, Variable};
+use crate::gate::Gate;
+
+/// What a run of [`optimize`] changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OptimizationStats {
+    pub rows_before: usize,
+    pub rows_after: usize,
+    /// Constant rows dropped because another variable holds the value
+    pub constants_deduplicated: usize,
+    /// Wires replaced by their constant value in the selectors
+    pub constants_folded: usize,
+    /// Linear rows folded into the row sharing their intermediate variable
+    pub rows_merged: usize,
+    /// Rows that constrained nothing
+    pub dead_rows_removed: usize,
+}
+
+impl OptimizationStats {
+    pub fn rows_saved(&self) -> usize {
+        self.rows_before - self.rows_after
+    }
+}
+
+/// Runs every pass until none of them finds anything left to do.
+pub fn optimize(cs: &mut ConstraintSystem) -> OptimizationStats {
+    let mut stats = OptimizationStats {
+        rows_before: cs.rows().len(),
+        ..Default::default()
+    };
+
+    loop {
+        let deduplicated = deduplicate_constants(cs);
+        let folded = fold_constants(cs);
+        let merged = merge_linear(cs);
+        let dead = eliminate_dead_rows(cs);
+
+        stats.constants_deduplicated += deduplicated;
+        stats.constants_folded += folded;
+        stats.rows_merged += merged;
+        stats.dead_rows_removed += dead;
+        if deduplicated + folded + merged + dead == 0 {
+            break;
+        }
+    }
+
+    stats.rows_after = cs.rows().len();
+    stats
+}
+
+/// Points every variable pinned to a constant at the first variable
+/// pinned to the same value, and drops the redundant constant rows.
+/// Constants equal to zero collapse onto the zero variable. Public
+/// inputs keep their own variable.
+///
+/// Returns the number of rows removed.
+pub fn deduplicate_constants(cs: &mut ConstraintSystem) -> usize {
+    let free = free_rows(cs);
+    let public = public_variables(cs);
+    let zero = cs.zero();
+
+    let mut canonical: HashMap<Fr, Variable> = HashMap::from([(Fr::zero(), zero)]);
+    let mut defined: HashMap<Variable, Fr> = HashMap::from([(zero, Fr::zero())]);
+    let mut remap: HashMap<Variable, Variable> = HashMap::new();
+    let mut keep = vec![true; cs.rows().len()];
+
+    for (i, row) in cs.rows().iter().enumerate() {
+        let Some((var, value)) = free[i].then(|| constant_row(row)).flatten() else {
+            continue;
+        };
+        if public.contains(&var) {
+            continue;
+        }
+        match (defined.get(&var), canonical.get(&value)) {
+            // The same variable pinned to the same value again
+            (Some(&v), _) if v == value => keep[i] = false,
+            // Pinned to two values: the circuit is unsatisfiable, keep both
+            (Some(_), _) => {}
+            (None, Some(&target)) => {
+                remap.insert(var, target);
+                defined.insert(var, value);
+                keep[i] = false;
+            }
+            (None, None) => {
+                canonical.insert(value, var);
+                defined.insert(var, value);
+            }
+        }
+    }
+
+    for row in cs.rows_mut() {
+        for wire in &mut row.wires {
+            if let Some(&target) = remap.get(wire) {
+                *wire = target;
+            }
+        }
+    }
+    remove_rows(cs, &keep)
+}
+
+/// Replaces wires that hold a constant-pinned variable by the constant,
+/// moving it into the row's selectors: into q_c for linear terms and
+/// into the other factor's selector for the product.
+///
+/// Returns the number of wires folded.
+pub fn fold_constants(cs: &mut ConstraintSystem) -> usize {
+    let free = free_rows(cs);
+    let zero = cs.zero();
+    let num_wires = cs.num_wires();
+
+    let mut constants: HashMap<Variable, (Fr, usize)> = HashMap::new();
+    for (i, row) in cs.rows().iter().enumerate() {
+        if let Some((var, value)) = free[i].then(|| constant_row(row)).flatten() {
+            constants.entry(var).or_insert((value, i));
+        }
+    }
+
+    let mut folded = 0;
+    for (i, row) in cs.rows_mut().iter_mut().enumerate() {
+        if !free[i] {
+            continue;
+        }
+        let value = |j: usize| {
+            let var = row.wires[j];
+            constants
+                .get(&var)
+                .filter(|&&(_, defining)| defining != i && var != zero)
+                .map(|&(value, _)| value)
+        };
+        let values: Vec<Option<Fr>> = (0..num_wires).map(value).collect();
+        if values.iter().all(Option::is_none) {
+            continue;
+        }
+
+        let mut selectors = row.gate.selectors(num_wires);
+        let q_m = selectors[3];
+        if !q_m.is_zero() {
+            match (values[0], values[1]) {
+                (Some(a), Some(b)) => selectors[4] += q_m * a * b,
+                (Some(a), None) => selectors[1] += q_m * a,
+                (None, Some(b)) => selectors[0] += q_m * b,
+                (None, None) => {}
+            }
+            if values[0].is_some() || values[1].is_some() {
+                selectors[3] = Fr::zero();
+            }
+        }
+        for (j, value) in values.iter().enumerate() {
+            if let Some(value) = value {
+                let k = linear_selector(j);
+                let coeff = std::mem::take(&mut selectors[k]);
+                selectors[4] += coeff * value;
+                row.wires[j] = zero;
+                folded += 1;
+            }
+        }
+        row.gate = Gate::from_selectors(&selectors);
+    }
+    folded
+}
+
+/// Merges pairs of linear rows that share a variable used nowhere else:
+/// with α·x + L₁ = 0 and β·x + L₂ = 0, the row L₂ - (β/α)·L₁ = 0 replaces
+/// both, as long as its terms fit on one row.
+///
+/// Returns the number of rows removed.
+pub fn merge_linear(cs: &mut ConstraintSystem) -> usize {
+    let free = free_rows(cs);
+    let num_wires = cs.num_wires();
+    let public = public_variables(cs);
+    let zero = cs.zero();
+
+    let mut forms: Vec<Option<LinearForm>> = cs
+        .rows()
+        .iter()
+        .enumerate()
+        .map(|(i, row)| free[i].then(|| linear_form(row, num_wires, zero)).flatten())
+        .collect();
+    let mut occurrences = occurrences(cs);
+    let mut keep = vec![true; cs.rows().len()];
+    let mut merged = 0;
+
+    let mut candidates: Vec<Variable> = occurrences.keys().copied().collect();
+    candidates.sort();
+    while let Some(x) = candidates.pop() {
+        if x == zero || public.contains(&x) {
+            continue;
+        }
+        let [first, second] = occurrences[&x][..] else {
+            continue;
+        };
+        let (Some(f1), Some(f2)) = (&forms[first], &forms[second]) else {
+            continue;
+        };
+        let (Some(alpha), Some(beta)) = (f1.coeff(x), f2.coeff(x)) else {
+            continue;
+        };
+
+        let ratio = beta * alpha.inverse().expect("coefficients are non-zero");
+        let combined = f2.sub_scaled(f1, ratio);
+        if combined.terms.len() > num_wires {
+            continue;
+        }
+
+        let old: HashSet<Variable> = cs.rows()[first]
+            .wires
+            .iter()
+            .chain(&cs.rows()[second].wires)
+            .copied()
+            .collect();
+        let row = combined.to_row(num_wires, zero);
+        for var in &old {
+            let rows = occurrences.get_mut(var).expect("variable occurs");
+            rows.retain(|&r| r != first && r != second);
+        }
+        for var in row.wires.iter().collect::<HashSet<_>>() {
+            occurrences.entry(*var).or_default().push(second);
+        }
+        candidates.extend(old);
+
+        cs.rows_mut()[second] = row;
+        forms[second] = Some(combined);
+        forms[first] = None;
+        keep[first] = false;
+        merged += 1;
+    }
+
+    remove_rows(cs, &keep);
+    merged
+}
+
+/// Removes free rows that constrain nothing: rows with every selector at
+/// zero, repeats of an earlier row, and rows that only define a variable
+/// used nowhere else, since any value of it satisfies them.
+///
+/// Returns the number of rows removed.
+pub fn eliminate_dead_rows(cs: &mut ConstraintSystem) -> usize {
+    let free = free_rows(cs);
+    let num_wires = cs.num_wires();
+    let public = public_variables(cs);
+    let zero = cs.zero();
+
+    let mut counts: HashMap<Variable, usize> = occurrences(cs)
+        .into_iter()
+        .map(|(var, rows)| (var, rows.len()))
+        .collect();
+    let mut seen: HashSet<(Vec<Fr>, Vec<Variable>)> = HashSet::new();
+    let mut keep = vec![true; cs.rows().len()];
+
+    for (i, row) in cs.rows().iter().enumerate() {
+        if !free[i] {
+            continue;
+        }
+        let selectors = row.gate.selectors(num_wires);
+        let dead = selectors.iter().all(Fr::is_zero)
+            || !seen.insert((selectors.clone(), row.wires.clone()))
+            || row.wires.iter().enumerate().any(|(j, &var)| {
+                var != zero
+                    && !public.contains(&var)
+                    && counts[&var] == 1
+                    && row.wires.iter().filter(|&&w| w == var).count() == 1
+                    && !selectors[linear_selector(j)].is_zero()
+                    && (j >= 2 || selectors[3].is_zero())
+            });
+
+        if dead {
+            keep[i] = false;
+            for var in row.wires.iter().collect::<HashSet<_>>() {
+                *counts.get_mut(var).expect("variable occurs") -= 1;
+            }
+        }
+    }
+    remove_rows(cs, &keep)
+}
+
+/// Σ coeff·var + constant over distinct non-zero variables.
+#[derive(Clone, Debug)]
+struct LinearForm {
+    terms: Vec<(Variable, Fr)>,
+    constant: Fr,
+}
+
+impl LinearForm {
+    fn coeff(&self, var: Variable) -> Option<Fr> {
+        self.terms.iter().find(|(v, _)| *v == var).map(|(_, c)| *c)
+    }
+
+    fn add_term(&mut self, var: Variable, coeff: Fr) {
+        match self.terms.iter_mut().find(|(v, _)| *v == var) {
+            Some((_, c)) => *c += coeff,
+            None => self.terms.push((var, coeff)),
+        }
+    }
+
+    /// self - scale·other, dropping cancelled terms.
+    fn sub_scaled(&self, other: &LinearForm, scale: Fr) -> LinearForm {
+        let mut result = self.clone();
+        for &(var, coeff) in &other.terms {
+            result.add_term(var, -scale * coeff);
+        }
+        result.constant -= scale * other.constant;
+        result.terms.retain(|(_, c)| !c.is_zero());
+        result
+    }
+
+    fn to_row(&self, num_wires: usize, zero: Variable) -> Row {
+        let coeffs: Vec<Fr> = self.terms.iter().map(|(_, c)| *c).collect();
+        let mut wires: Vec<Variable> = self.terms.iter().map(|(v, _)| *v).collect();
+        wires.resize(num_wires, zero);
+        Row {
+            gate: Gate::linear(&coeffs, self.constant),
+            wires,
+            custom: Vec::new(),
+            lookup: None,
+        }
+    }
+}
+
+/// The row's gate as a linear form, if it has no product term.
+fn linear_form(row: &Row, num_wires: usize, zero: Variable) -> Option<LinearForm> {
+    let selectors = row.gate.selectors(num_wires);
+    if !selectors[3].is_zero() {
+        return None;
+    }
+    let mut form = LinearForm {
+        terms: Vec::new(),
+        constant: selectors[4],
+    };
+    for (j, &var) in row.wires.iter().enumerate() {
+        let coeff = selectors[linear_selector(j)];
+        if var != zero && !coeff.is_zero() {
+            form.add_term(var, coeff);
+        }
+    }
+    form.terms.retain(|(_, c)| !c.is_zero());
+    Some(form)
+}
+
+/// Position in [`Gate::selectors`] of the linear selector of wire `j`.
+fn linear_selector(j: usize) -> usize {
+    if j < DEFAULT_NUM_WIRES { j } else { j + 2 }
+}
+
+/// Variable and value of a row of the form q·x + q_c = 0.
+fn constant_row(row: &Row) -> Option<(Variable, Fr)> {
+    let gate = &row.gate;
+    let only_q_l = gate.q_r.is_zero()
+        && gate.q_o.is_zero()
+        && gate.q_m.is_zero()
+        && gate.q_extra.iter().all(Fr::is_zero);
+    let inverse = gate.q_l.inverse()?;
+    only_q_l.then(|| (row.wires[0], -gate.q_c * inverse))
+}
+
+/// Rows a pass may rewrite or remove.
+fn free_rows(cs: &ConstraintSystem) -> Vec<bool> {
+    let public: HashSet<usize> = cs.public_input_rows().iter().copied().collect();
+    let rows = cs.rows();
+    (0..rows.len())
+        .map(|i| {
+            i > 0
+                && !public.contains(&i)
+                && rows[i].custom.is_empty()
+                && rows[i].lookup.is_none()
+                && rows[i - 1].custom.is_empty()
+        })
+        .collect()
+}
+
+fn public_variables(cs: &ConstraintSystem) -> HashSet<Variable> {
+    cs.public_input_rows()
+        .iter()
+        .map(|&row| cs.rows()[row].wires[0])
+        .collect()
+}
+
+/// Rows each variable appears in, once per row.
+fn occurrences(cs: &ConstraintSystem) -> HashMap<Variable, Vec<usize>> {
+    let mut occurrences: HashMap<Variable, Vec<usize>> = HashMap::new();
+    for (i, row) in cs.rows().iter().enumerate() {
+        for var in row.wires.iter().collect::<HashSet<_>>() {
+            occurrences.entry(*var).or_default().push(i);
+        }
+    }
+    occurrences
+}
+
+fn remove_rows(cs: &mut ConstraintSystem, keep: &[bool]) -> usize {
+    let removed = keep.iter().filter(|&&kept| !kept).count();
+    if removed > 0 {
+        cs.retain_rows(keep);
+    }
+    removed
+}
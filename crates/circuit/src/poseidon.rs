@@ -0,0 +1,203 @@
+//! Poseidon hash over the BLS12-381 scalar field.
+//!
+//! Uses the standard instance for a 255-bit field with the x⁵ S-box:
+//! width 3 (rate 2, capacity 1), 8 full rounds and 57 partial rounds.
+//! Round constants and the MDS matrix come from the Grain LFSR of the
+//! reference implementation (`generate_parameters_grain.sage`).
+
+use std::sync::OnceLock;
+
+use ark_bls12_381::Fr;
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+
+/// Number of field elements in the state.
+pub const WIDTH: usize = 3;
+/// Number of state elements inputs are absorbed into.
+pub const RATE: usize = 2;
+pub const FULL_ROUNDS: usize = 8;
+pub const PARTIAL_ROUNDS: usize = 57;
+/// S-box exponent.
+pub const ALPHA: u64 = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonParams {
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    /// Round constants, one row of `WIDTH` per round
+    pub round_constants: Vec<[Fr; WIDTH]>,
+    pub mds: [[Fr; WIDTH]; WIDTH],
+}
+
+impl PoseidonParams {
+    /// Generates parameters for the given round numbers with the Grain LFSR.
+    pub fn generate(full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut lfsr = GrainLfsr::new(
+            Fr::MODULUS_BIT_SIZE as u64,
+            WIDTH as u64,
+            full_rounds as u64,
+            partial_rounds as u64,
+        );
+
+        let round_constants = (0..full_rounds + partial_rounds)
+            .map(|_| std::array::from_fn(|_| lfsr.field_element_rejection_sampling()))
+            .collect();
+
+        // Cauchy matrix Mᵢⱼ = 1 / (xᵢ + yⱼ)
+        let xs: [Fr; WIDTH] = std::array::from_fn(|_| lfsr.field_element_mod_p());
+        let ys: [Fr; WIDTH] = std::array::from_fn(|_| lfsr.field_element_mod_p());
+        let mds = std::array::from_fn(|i| {
+            std::array::from_fn(|j| (xs[i] + ys[j]).inverse().expect("xᵢ + yⱼ is non-zero"))
+        });
+
+        PoseidonParams {
+            full_rounds,
+            partial_rounds,
+            round_constants,
+            mds,
+        }
+    }
+
+    /// The standard parameters, generated once and cached.
+    pub fn standard() -> &'static Self {
+        static PARAMS: OnceLock<PoseidonParams> = OnceLock::new();
+        PARAMS.get_or_init(|| Self::generate(FULL_ROUNDS, PARTIAL_ROUNDS))
+    }
+
+    pub fn num_rounds(&self) -> usize {
+        self.full_rounds + self.partial_rounds
+    }
+
+    /// Whether round `r` applies the S-box to the whole state. Half of the
+    /// full rounds come before the partial rounds, half after.
+    pub fn is_full_round(&self, r: usize) -> bool {
+        r < self.full_rounds / 2 || r >= self.full_rounds / 2 + self.partial_rounds
+    }
+
+    /// Applies the permutation: each round adds its constants, applies x⁵
+    /// to the whole state (full rounds) or the first element (partial
+    /// rounds), then multiplies by the MDS matrix.
+    pub fn permute(&self, state: &mut [Fr; WIDTH]) {
+        for r in 0..self.num_rounds() {
+            for (x, c) in state.iter_mut().zip(&self.round_constants[r]) {
+                *x += c;
+            }
+            if self.is_full_round(r) {
+                for x in state.iter_mut() {
+                    *x = x.pow([ALPHA]);
+                }
+            } else {
+                state[0] = state[0].pow([ALPHA]);
+            }
+            *state = self.mix(state);
+        }
+    }
+
+    /// Multiplies the state by the MDS matrix.
+    pub fn mix(&self, state: &[Fr; WIDTH]) -> [Fr; WIDTH] {
+        std::array::from_fn(|i| self.mds[i].iter().zip(state).map(|(m, x)| *m * x).sum())
+    }
+
+    /// Hashes any number of elements with a sponge that starts from the
+    /// zero state, adds inputs into the rate and squeezes state[1].
+    ///
+    /// The input length is not padded, so callers must fix it per use:
+    /// `hash(&[a])` and `hash(&[a, 0])` agree.
+    pub fn hash(&self, inputs: &[Fr]) -> Fr {
+        let mut state = [Fr::zero(); WIDTH];
+        for (k, chunk) in inputs.chunks(RATE).enumerate() {
+            if k > 0 {
+                self.permute(&mut state);
+            }
+            for (x, input) in state[WIDTH - RATE..].iter_mut().zip(chunk) {
+                *x += input;
+            }
+        }
+        self.permute(&mut state);
+        state[WIDTH - RATE]
+    }
+}
+
+/// Two-to-one Poseidon hash with the standard parameters.
+pub fn hash_two(left: Fr, right: Fr) -> Fr {
+    PoseidonParams::standard().hash(&[left, right])
+}
+
+/// Encodes a Fiat-Shamir label as the field element it is absorbed as:
+/// its bytes read big-endian.
+pub fn label_element(label: &[u8]) -> Fr {
+    Fr::from_be_bytes_mod_order(label)
+}
+
+/// Grain LFSR in self-shrinking mode, seeded with the instance parameters.
+struct GrainLfsr {
+    state: [bool; 80],
+    head: usize,
+    prime_bits: usize,
+}
+
+impl GrainLfsr {
+    fn new(prime_bits: u64, width: u64, full_rounds: u64, partial_rounds: u64) -> Self {
+        // b0..b1: prime field, b2..b5: x^α S-box, then n, t, R_F, R_P in
+        // 12, 12, 10 and 10 bits, most significant first, then all ones
+        let mut seed = vec![false, true, false, false, false, false];
+        for (value, bits) in [
+            (prime_bits, 12),
+            (width, 12),
+            (full_rounds, 10),
+            (partial_rounds, 10),
+        ] {
+            seed.extend((0..bits).rev().map(|i| (value >> i) & 1 == 1));
+        }
+        seed.resize(80, true);
+
+        let mut lfsr = GrainLfsr {
+            state: seed.try_into().expect("seed is 80 bits"),
+            head: 0,
+            prime_bits: prime_bits as usize,
+        };
+        for _ in 0..160 {
+            lfsr.clock();
+        }
+        lfsr
+    }
+
+    fn clock(&mut self) -> bool {
+        let bit = [62, 51, 38, 23, 13, 0]
+            .iter()
+            .fold(false, |acc, tap| acc ^ self.state[(self.head + tap) % 80]);
+        self.state[self.head] = bit;
+        self.head = (self.head + 1) % 80;
+        bit
+    }
+
+    /// Output bits: of each pair, the second is kept when the first is 1.
+    fn bits(&mut self, count: usize) -> Vec<bool> {
+        (0..count)
+            .map(|_| {
+                while !self.clock() {
+                    self.clock();
+                }
+                self.clock()
+            })
+            .collect()
+    }
+
+    /// `prime_bits` bits read most significant first.
+    fn bigint(&mut self) -> <Fr as PrimeField>::BigInt {
+        let mut bits = self.bits(self.prime_bits);
+        bits.reverse();
+        <Fr as PrimeField>::BigInt::from_bits_le(&bits)
+    }
+
+    fn field_element_rejection_sampling(&mut self) -> Fr {
+        loop {
+            if let Some(element) = Fr::from_bigint(self.bigint()) {
+                return element;
+            }
+        }
+    }
+
+    fn field_element_mod_p(&mut self) -> Fr {
+        Fr::from_le_bytes_mod_order(&self.bigint().to_bytes_le())
+    }
+}
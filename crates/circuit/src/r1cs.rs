@@ -0,0 +1,380 @@
+//! Import of circom's binary `.r1cs` constraint and `.wtns` witness files.
+//!
+//! Files are read over a scalar field `F`, BLS12-381's by default, and
+//! must name its modulus as their prime: compile with `circom --prime
+//! bls12381` for BLS12-381, or keep circom's default `bn128` prime for
+//! BN254. Wire 0 holds the constant 1, followed by the public outputs, the
+//! public inputs, the private inputs and the internal signals.
+//!
+//! Counts in the headers are checked against the lengths of the sections
+//! they describe before anything is allocated from them.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use ark_bls12_381::Fr;
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::constraint_system::{ConstraintSystem, Variable};
+use crate::gadgets::arithmetic::linear_combination;
+use crate::gate::Gate;
+use crate::serialization::invalid_data;
+use crate::witness::Witness;
+
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINTS_SECTION: u32 = 2;
+const WIRE_MAP_SECTION: u32 = 3;
+const WITNESS_SECTION: u32 = 2;
+
+/// Bytes per entry of the wire-to-label map.
+const WIRE_MAP_ENTRY: usize = 8;
+
+/// Bytes of a constraint with no terms: three term counts.
+const MIN_CONSTRAINT: usize = 12;
+
+/// Σ coeff·wire, with wire 0 standing for the constant 1.
+pub type LinearCombination<F = Fr> = Vec<(usize, F)>;
+
+/// A rank-1 constraint A·B = C.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint<F: PrimeField = Fr> {
+    pub a: LinearCombination<F>,
+    pub b: LinearCombination<F>,
+    pub c: LinearCombination<F>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1cs<F: PrimeField = Fr> {
+    /// Number of wires, including the constant wire 0
+    pub num_wires: usize,
+    pub num_public_outputs: usize,
+    pub num_public_inputs: usize,
+    pub num_private_inputs: usize,
+    pub constraints: Vec<Constraint<F>>,
+}
+
+impl<F: PrimeField> R1cs<F> {
+    /// Reads a `.r1cs` file.
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let sections = read_sections(&mut reader, b"r1cs")?;
+        let header = find_section(&sections, HEADER_SECTION)?;
+        let mut header = header.as_slice();
+
+        read_prime::<F>(&mut header)?;
+        let num_wires = read_u32(&mut header)? as usize;
+        let num_public_outputs = read_u32(&mut header)? as usize;
+        let num_public_inputs = read_u32(&mut header)? as usize;
+        let num_private_inputs = read_u32(&mut header)? as usize;
+        let _num_labels = read_u64(&mut header)?;
+        let num_constraints = read_u32(&mut header)? as usize;
+
+        let wire_map = find_section(&sections, WIRE_MAP_SECTION)?;
+        if num_wires.checked_mul(WIRE_MAP_ENTRY) != Some(wire_map.len()) {
+            return Err(invalid_data(format!(
+                "Header has {num_wires} wires, but the wire map has {} bytes",
+                wire_map.len()
+            )));
+        }
+        let num_signals = [num_public_outputs, num_public_inputs, num_private_inputs]
+            .into_iter()
+            .try_fold(1usize, usize::checked_add);
+        if num_signals.is_none_or(|num_signals| num_signals > num_wires) {
+            return Err(invalid_data(format!(
+                "{num_wires} wires cannot hold the header's public and private signals"
+            )));
+        }
+
+        let mut body = find_section(&sections, CONSTRAINTS_SECTION)?.as_slice();
+        if num_constraints > body.len() / MIN_CONSTRAINT {
+            return Err(invalid_data(format!(
+                "{num_constraints} constraints do not fit in {} bytes",
+                body.len()
+            )));
+        }
+        let read_lc = |body: &mut &[u8]| -> io::Result<LinearCombination<F>> {
+            let num_terms = read_u32(body)?;
+            (0..num_terms)
+                .map(|_| {
+                    let wire = read_u32(body)? as usize;
+                    if wire >= num_wires {
+                        return Err(invalid_data(format!("Wire {wire} is out of range")));
+                    }
+                    let mut coeff = vec![0u8; field_size::<F>()];
+                    body.read_exact(&mut coeff)?;
+                    Ok((wire, read_field(&coeff)?))
+                })
+                .collect()
+        };
+        let constraints = (0..num_constraints)
+            .map(|_| {
+                Ok(Constraint {
+                    a: read_lc(&mut body)?,
+                    b: read_lc(&mut body)?,
+                    c: read_lc(&mut body)?,
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        if !body.is_empty() {
+            return Err(invalid_data("Trailing bytes after the constraints"));
+        }
+
+        Ok(R1cs {
+            num_wires,
+            num_public_outputs,
+            num_public_inputs,
+            num_private_inputs,
+            constraints,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Number of public signals: outputs, then inputs.
+    pub fn num_public(&self) -> usize {
+        self.num_public_outputs + self.num_public_inputs
+    }
+
+    /// Checks every constraint against a full wire assignment.
+    pub fn is_satisfied(&self, wires: &[F]) -> bool {
+        let eval = |lc: &LinearCombination<F>| -> F {
+            lc.iter().map(|(wire, coeff)| *coeff * wires[*wire]).sum()
+        };
+        wires.len() == self.num_wires
+            && wires[0].is_one()
+            && self
+                .constraints
+                .iter()
+                .all(|c| eval(&c.a) * eval(&c.b) == eval(&c.c))
+    }
+
+    /// Compiles the constraints into PLONK rows of `num_wires` wires.
+    ///
+    /// Each side of A·B = C is reduced to one variable with
+    /// [`linear_combination`], unless it already is a single wire, and
+    /// the three are tied by a multiplication row. When A or B is
+    /// constant the constraint is linear and takes no multiplication.
+    pub fn compile(&self, num_wires: usize) -> R1csCircuit<F> {
+        let mut cs = ConstraintSystem::with_wires(num_wires);
+        let one = cs.constant(F::one());
+        let wires: Vec<Variable> = std::iter::once(one)
+            .chain((1..self.num_wires).map(|i| {
+                if i <= self.num_public() {
+                    cs.public_input(F::zero())
+                } else {
+                    cs.alloc(F::zero())
+                }
+            }))
+            .collect();
+
+        for constraint in &self.constraints {
+            match (constant(&constraint.a), constant(&constraint.b)) {
+                (Some(k), _) => assert_linear(&mut cs, &wires, &constraint.b, k, &constraint.c),
+                (_, Some(k)) => assert_linear(&mut cs, &wires, &constraint.a, k, &constraint.c),
+                _ => {
+                    let a = reduce(&mut cs, &wires, &constraint.a);
+                    let b = reduce(&mut cs, &wires, &constraint.b);
+                    let c = reduce(&mut cs, &wires, &constraint.c);
+                    cs.add_gate(Gate::multiplication(), &[a, b, c]);
+                }
+            }
+        }
+
+        R1csCircuit { cs, wires }
+    }
+}
+
+/// A compiled R1CS together with the variable holding each wire.
+#[derive(Clone, Debug)]
+pub struct R1csCircuit<F: PrimeField = Fr> {
+    pub cs: ConstraintSystem<F>,
+    /// Variable of each R1CS wire; wire 0 is pinned to 1
+    pub wires: Vec<Variable>,
+}
+
+impl<F: PrimeField> R1csCircuit<F> {
+    /// Witness assigning every wire but the constant one from a full
+    /// assignment, e.g. one read with [`read_wtns`].
+    pub fn witness(&self, values: &[F]) -> Witness<F> {
+        self.wires
+            .iter()
+            .zip(values)
+            .skip(1)
+            .map(|(&var, &value)| (var, value))
+            .collect()
+    }
+
+    /// Compiled system with the witness generated from `values`.
+    pub fn synthesize(&self, values: &[F]) -> io::Result<ConstraintSystem<F>> {
+        if values.len() != self.wires.len() {
+            return Err(invalid_data(format!(
+                "Expected {} wire values, got {}",
+                self.wires.len(),
+                values.len()
+            )));
+        }
+        let mut cs = self.cs.clone();
+        cs.set_witness(&self.witness(values))
+            .map_err(invalid_data)?;
+        Ok(cs)
+    }
+}
+
+/// Reads the wire values of a `.wtns` file.
+pub fn read_wtns<F: PrimeField>(mut reader: impl Read) -> io::Result<Vec<F>> {
+    let sections = read_sections(&mut reader, b"wtns")?;
+    let mut header = find_section(&sections, HEADER_SECTION)?.as_slice();
+    read_prime::<F>(&mut header)?;
+    let num_values = read_u32(&mut header)? as usize;
+
+    let body = find_section(&sections, WITNESS_SECTION)?;
+    if num_values.checked_mul(field_size::<F>()) != Some(body.len()) {
+        return Err(invalid_data(format!(
+            "Header has {num_values} values, but the witness has {} bytes",
+            body.len()
+        )));
+    }
+    body.chunks(field_size::<F>()).map(read_field).collect()
+}
+
+pub fn load_wtns<F: PrimeField>(path: impl AsRef<Path>) -> io::Result<Vec<F>> {
+    read_wtns(BufReader::new(File::open(path)?))
+}
+
+/// Value of a linear combination that only uses the constant wire.
+fn constant<F: PrimeField>(lc: &LinearCombination<F>) -> Option<F> {
+    lc.iter()
+        .all(|(wire, _)| *wire == 0)
+        .then(|| lc.iter().map(|(_, coeff)| *coeff).sum())
+}
+
+/// Splits off the constant term of a linear combination.
+fn terms<F: PrimeField>(wires: &[Variable], lc: &LinearCombination<F>) -> (Vec<(F, Variable)>, F) {
+    let mut constant = F::zero();
+    let mut terms = Vec::new();
+    for &(wire, coeff) in lc {
+        if wire == 0 {
+            constant += coeff;
+        } else {
+            terms.push((coeff, wires[wire]));
+        }
+    }
+    (terms, constant)
+}
+
+/// Variable equal to a linear combination.
+fn reduce<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    wires: &[Variable],
+    lc: &LinearCombination<F>,
+) -> Variable {
+    match terms(wires, lc) {
+        (terms, constant) if constant.is_zero() && terms.len() == 1 && terms[0].0.is_one() => {
+            terms[0].1
+        }
+        (terms, constant) => linear_combination(cs, &terms, constant),
+    }
+}
+
+/// Constrains k·L - C = 0.
+fn assert_linear<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    wires: &[Variable],
+    lc: &LinearCombination<F>,
+    k: F,
+    c: &LinearCombination<F>,
+) {
+    let (left, left_constant) = terms(wires, lc);
+    let (right, right_constant) = terms(wires, c);
+    let combined: Vec<(F, Variable)> = left
+        .into_iter()
+        .map(|(coeff, var)| (k * coeff, var))
+        .chain(right.into_iter().map(|(coeff, var)| (-coeff, var)))
+        .collect();
+    let sum = linear_combination(cs, &combined, k * left_constant - right_constant);
+    cs.add_gate(Gate::constant(F::zero()), &[sum]);
+}
+
+/// Reads the magic, version and sections of an iden3 binary file.
+fn read_sections(reader: &mut impl Read, magic: &[u8; 4]) -> io::Result<Vec<(u32, Vec<u8>)>> {
+    let mut found = [0u8; 4];
+    reader.read_exact(&mut found)?;
+    if &found != magic {
+        return Err(invalid_data(format!(
+            "Not a {} file",
+            String::from_utf8_lossy(magic)
+        )));
+    }
+    let _version = read_u32(reader)?;
+    let num_sections = read_u32(reader)?;
+
+    (0..num_sections)
+        .map(|_| {
+            let kind = read_u32(reader)?;
+            let size = read_u64(reader)?;
+            let mut content = Vec::new();
+            reader.by_ref().take(size).read_to_end(&mut content)?;
+            if content.len() as u64 != size {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            Ok((kind, content))
+        })
+        .collect()
+}
+
+fn find_section(sections: &[(u32, Vec<u8>)], kind: u32) -> io::Result<&Vec<u8>> {
+    sections
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, content)| content)
+        .ok_or_else(|| invalid_data(format!("Missing section {kind}")))
+}
+
+/// Reads the field size and prime, which must be `F`'s. The size is
+/// checked before the prime is read.
+fn read_prime<F: PrimeField>(reader: &mut impl Read) -> io::Result<()> {
+    let size = read_u32(reader)? as usize;
+    if size != field_size::<F>() {
+        return Err(invalid_data(format!(
+            "Field elements are {size} bytes, expected {}",
+            field_size::<F>()
+        )));
+    }
+    let mut prime = vec![0u8; size];
+    reader.read_exact(&mut prime)?;
+    if prime != F::MODULUS.to_bytes_le() {
+        return Err(invalid_data(
+            "File is over a different prime field; for BLS12-381 compile with --prime bls12381",
+        ));
+    }
+    Ok(())
+}
+
+/// Bytes of a field element in circom's files, 32 on both BN254 and
+/// BLS12-381.
+fn field_size<F: PrimeField>() -> usize {
+    F::MODULUS.to_bytes_le().len()
+}
+
+/// Little-endian field element below the modulus.
+fn read_field<F: PrimeField>(bytes: &[u8]) -> io::Result<F> {
+    let value = F::from_le_bytes_mod_order(bytes);
+    if value.into_bigint().to_bytes_le() != bytes {
+        return Err(invalid_data("Field element is not reduced"));
+    }
+    Ok(value)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
@@ -0,0 +1,29 @@
+//! Helpers for the binary encodings in this crate and in `proof`.
+
+use std::io::{self, Read};
+
+use ark_serialize::CanonicalDeserialize;
+
+/// Reads a u64 length and then that many items in arkworks' compressed
+/// encoding. Items are read one by one, so a corrupt length fails at the
+/// end of the input instead of allocating it up front.
+pub fn read_vec<T: CanonicalDeserialize>(reader: &mut impl Read) -> io::Result<Vec<T>> {
+    let len = u64::deserialize_compressed(&mut *reader).map_err(invalid_data)?;
+    let mut items = Vec::new();
+    for _ in 0..len {
+        items.push(T::deserialize_compressed(&mut *reader).map_err(invalid_data)?);
+    }
+    Ok(items)
+}
+
+/// Fails if anything is left in `reader`.
+pub fn expect_end(reader: &mut impl Read) -> io::Result<()> {
+    match reader.read(&mut [0u8])? {
+        0 => Ok(()),
+        _ => Err(invalid_data("Trailing bytes after the encoded value")),
+    }
+}
+
+pub fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
@@ -1,6 +1,9 @@
 use ark_bls12_381::Fr;
+use ark_ff::Zero;
 
+use crate::constraint_system::{ConstraintSystem, Variable};
 use crate::gate::Gate;
+use crate::stats::CircuitStats;
 use crate::witness::{WireValues, Witness};
 
 #[derive(Clone, Debug)]
@@ -18,11 +21,14 @@ impl SquareCircuit {
         }
     }
 
+    /// Wires of the multiplication row, generated from x alone.
     pub fn wire_values(&self, witness: &Witness) -> WireValues {
+        let cs = self.synthesize(witness);
+        let wires = cs.wire_values(cs.rows().len() - 1);
         WireValues {
-            a: witness.x,
-            b: witness.x,
-            c: self.public_output,
+            a: wires[0],
+            b: wires[1],
+            c: wires[2],
         }
     }
 
@@ -30,6 +36,42 @@ impl SquareCircuit {
         let wires = self.wire_values(witness);
         self.gate.is_satisfied(wires.a, wires.b, wires.c)
     }
+
+    pub fn public_inputs(&self) -> Vec<Fr> {
+        vec![self.public_output]
+    }
+
+    /// Witness assigning x, the only private input.
+    pub fn witness(&self, x: impl Into<Fr>) -> Witness {
+        let (_, _, var) = self.layout();
+        Witness::new().with(var, x)
+    }
+
+    /// Lay the circuit out as rows: the public output, then x·x = output.
+    ///
+    /// The witness only needs x; the output comes from the circuit.
+    pub fn synthesize(&self, witness: &Witness) -> ConstraintSystem {
+        let (mut cs, output, x) = self.layout();
+        let inputs = witness.clone().with(output, self.public_output);
+        cs.set_witness(&inputs)
+            .unwrap_or_else(|_| panic!("Witness must assign x, variable {}", x.index()));
+        cs
+    }
+
+    /// Size report of the laid-out circuit.
+    pub fn stats(&self) -> CircuitStats {
+        let (cs, _, _) = self.layout();
+        CircuitStats::new(&cs)
+    }
+
+    /// Rows without a witness, returning the output and x variables.
+    fn layout(&self) -> (ConstraintSystem, Variable, Variable) {
+        let mut cs = ConstraintSystem::new();
+        let output = cs.public_input(self.public_output);
+        let x = cs.alloc(Fr::zero());
+        cs.add_gate(self.gate.clone(), &[x, x, output]);
+        (cs, output, x)
+    }
 }
 
 impl Default for SquareCircuit {
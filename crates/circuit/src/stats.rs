@@ -0,0 +1,190 @@
+//! Size report and wiring graph of a built constraint system.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use ark_ff::{One, Zero};
+
+use crate::constraint_system::{ConstraintSystem, Row};
+
+/// Shape of a circuit as the prover sees it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub num_rows: usize,
+    pub num_wires: usize,
+    pub num_variables: usize,
+    pub num_public_inputs: usize,
+    /// Rows after padding to a power of two
+    pub domain_size: usize,
+    /// Rows by kind: standard gate shapes, custom gate names and lookups
+    pub gates: BTreeMap<String, usize>,
+    /// Variables held by more than one wire position, i.e. permutation
+    /// cycles that enforce a copy
+    pub copy_cycles: usize,
+    /// Wire positions in the longest cycle, padding included
+    pub longest_cycle: usize,
+}
+
+impl CircuitStats {
+    pub fn new(cs: &ConstraintSystem) -> Self {
+        let mut gates = BTreeMap::new();
+        for (i, row) in cs.rows().iter().enumerate() {
+            for kind in row_kinds(cs, i, row) {
+                *gates.entry(kind).or_insert(0) += 1;
+            }
+        }
+
+        // Padding rows hold the zero variable on every wire
+        let domain_size = cs.domain_size();
+        let mut positions = vec![0; cs.num_variables()];
+        positions[cs.zero().index()] = (domain_size - cs.rows().len()) * cs.num_wires();
+        for var in cs.rows().iter().flat_map(|row| &row.wires) {
+            positions[var.index()] += 1;
+        }
+
+        CircuitStats {
+            num_rows: cs.rows().len(),
+            num_wires: cs.num_wires(),
+            num_variables: cs.num_variables(),
+            num_public_inputs: cs.public_input_rows().len(),
+            domain_size,
+            gates,
+            copy_cycles: positions.iter().filter(|&&count| count > 1).count(),
+            longest_cycle: positions.iter().copied().max().unwrap_or(0),
+        }
+    }
+
+    pub fn padding_rows(&self) -> usize {
+        self.domain_size - self.num_rows
+    }
+
+    /// Share of the domain spent on padding, between 0 and 1.
+    pub fn padding_ratio(&self) -> f64 {
+        self.padding_rows() as f64 / self.domain_size as f64
+    }
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rows           {}", self.num_rows)?;
+        writeln!(f, "wires          {}", self.num_wires)?;
+        writeln!(f, "variables      {}", self.num_variables)?;
+        writeln!(f, "public inputs  {}", self.num_public_inputs)?;
+        writeln!(f, "domain size    {}", self.domain_size)?;
+        writeln!(
+            f,
+            "padding rows   {} ({:.1}%)",
+            self.padding_rows(),
+            100.0 * self.padding_ratio()
+        )?;
+        writeln!(
+            f,
+            "copy cycles    {} (longest {})",
+            self.copy_cycles, self.longest_cycle
+        )?;
+        writeln!(f, "gates")?;
+        let width = self.gates.keys().map(String::len).max().unwrap_or(0);
+        for (kind, count) in &self.gates {
+            writeln!(f, "  {kind:width$}  {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Wiring graph in Graphviz DOT: one node per row, an edge between rows
+/// that share a variable, and a dashed edge from each custom gate row to
+/// the next row it reads. The zero variable, which fills every unused
+/// wire, gets no edges.
+pub fn to_dot(cs: &ConstraintSystem) -> String {
+    let mut dot = String::from("graph circuit {\n    node [shape=record];\n");
+
+    for (i, row) in cs.rows().iter().enumerate() {
+        let wires: Vec<String> = row
+            .wires
+            .iter()
+            .map(|var| format!("v{}", var.index()))
+            .collect();
+        dot.push_str(&format!(
+            "    r{i} [label=\"{i}: {}|{{{}}}\"];\n",
+            row_kinds(cs, i, row).join(", "),
+            wires.join("|")
+        ));
+    }
+
+    // Rows of each variable, linked in order like its permutation cycle
+    let mut rows_of: Vec<Vec<usize>> = vec![Vec::new(); cs.num_variables()];
+    for (i, row) in cs.rows().iter().enumerate() {
+        for var in &row.wires {
+            if *var != cs.zero() && rows_of[var.index()].last() != Some(&i) {
+                rows_of[var.index()].push(i);
+            }
+        }
+    }
+    for (var, rows) in rows_of.iter().enumerate() {
+        for pair in rows.windows(2) {
+            dot.push_str(&format!(
+                "    r{} -- r{} [label=\"v{var}\"];\n",
+                pair[0], pair[1]
+            ));
+        }
+    }
+
+    for (i, row) in cs.rows().iter().enumerate() {
+        if !row.custom.is_empty() && i + 1 < cs.rows().len() {
+            dot.push_str(&format!(
+                "    r{i} -- r{} [style=dashed, label=\"next\"];\n",
+                i + 1
+            ));
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Names under which a row is counted: the shape of its standard gate,
+/// if it has one, then its custom gates and lookup table.
+fn row_kinds(cs: &ConstraintSystem, i: usize, row: &Row) -> Vec<String> {
+    let extras: Vec<String> = row
+        .custom
+        .iter()
+        .map(|(id, _)| cs.custom_gates()[id.index()].name().to_string())
+        .chain(
+            row.lookup
+                .map(|table| format!("lookup {}", cs.tables()[table.index()].name)),
+        )
+        .collect();
+
+    let gate = &row.gate;
+    let others_zero = |skip: &[usize]| {
+        gate.selectors(cs.num_wires())
+            .iter()
+            .enumerate()
+            .all(|(k, q)| skip.contains(&k) || q.is_zero())
+    };
+    let standard = if cs.public_input_rows().contains(&i) {
+        Some("public input")
+    } else if others_zero(&[]) {
+        extras.is_empty().then_some("empty")
+    } else if gate.q_l.is_one()
+        && gate.q_r.is_one()
+        && (-gate.q_o).is_one()
+        && others_zero(&[0, 1, 2])
+    {
+        Some("addition")
+    } else if gate.q_m.is_one() && (-gate.q_o).is_one() && others_zero(&[2, 3]) {
+        Some("multiplication")
+    } else if !gate.q_l.is_zero() && others_zero(&[0, 4]) {
+        Some("constant")
+    } else if gate.q_m.is_zero() {
+        Some("linear")
+    } else {
+        Some("arithmetic")
+    };
+
+    standard
+        .map(str::to_string)
+        .into_iter()
+        .chain(extras)
+        .collect()
+}
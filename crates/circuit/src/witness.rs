@@ -1,4 +1,17 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
 use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use num_bigint::{BigInt, Sign};
+
+use crate::constraint_system::Variable;
+use crate::serialization::{expect_end, invalid_data, read_vec};
 
 #[derive(Clone, Debug)]
 pub struct WireValues {
@@ -7,25 +20,282 @@ pub struct WireValues {
     pub c: Fr,
 }
 
-#[derive(Clone, Debug)]
-pub struct Witness {
-    pub x: Fr,
+/// Input values keyed by variable handle.
+///
+/// Values can be given as anything that converts into the field
+/// (unsigned and signed machine integers, `BigUint`) or through
+/// [`from_bigint`] and the byte-array helpers for wider numbers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Witness<F: PrimeField = Fr> {
+    values: BTreeMap<Variable, F>,
+}
+
+impl<F: PrimeField> Default for Witness<F> {
+    fn default() -> Self {
+        Witness {
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+impl<F: PrimeField> Witness<F> {
+    /// Sets a variable's value, returning the previous one.
+    pub fn insert(&mut self, var: Variable, value: impl Into<F>) -> Option<F> {
+        self.values.insert(var, value.into())
+    }
+
+    /// Builder-style [`insert`](Self::insert).
+    pub fn with(mut self, var: Variable, value: impl Into<F>) -> Self {
+        self.insert(var, value);
+        self
+    }
+
+    pub fn get(&self, var: Variable) -> Option<F> {
+        self.values.get(&var).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Assigned variables and their values, by variable index.
+    pub fn iter(&self) -> impl Iterator<Item = (Variable, F)> + '_ {
+        self.values.iter().map(|(&var, &value)| (var, value))
+    }
 }
 
 impl Witness {
-    pub fn new(x: u64) -> Self {
-        Witness { x: Fr::from(x) }
+    /// Empty witness over BLS12-381's scalar field; other fields start
+    /// from [`Witness::default`].
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub fn new_signed(x: i64) -> Self {
-        if x >= 0 {
-            Witness {
-                x: Fr::from(x as u64),
+    /// Parses a JSON object mapping variable indices to values, e.g.
+    /// `{"2": "-5", "3": "0x1f", "4": 7}`.
+    ///
+    /// Values are JSON integers or strings holding a decimal or `0x`
+    /// hexadecimal integer of any width, optionally negative.
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let object: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(json).map_err(invalid_data)?;
+        object
+            .iter()
+            .map(|(key, value)| {
+                let index: usize = key
+                    .parse()
+                    .map_err(|_| invalid_data(format!("{key} is not a variable index")))?;
+                let value = match value {
+                    serde_json::Value::Number(n) => parse_integer(&n.to_string()),
+                    serde_json::Value::String(s) => parse_integer(s),
+                    _ => None,
+                }
+                .ok_or_else(|| invalid_data(format!("{value} is not an integer")))?;
+                Ok((Variable::new(index), value))
+            })
+            .collect()
+    }
+
+    /// JSON object with one decimal string per variable.
+    pub fn to_json(&self) -> String {
+        let object: serde_json::Map<String, serde_json::Value> = self
+            .iter()
+            .map(|(var, value)| (var.index().to_string(), value.to_string().into()))
+            .collect();
+        serde_json::Value::Object(object).to_string()
+    }
+
+    /// Reads the binary form written by [`write`](Self::write): the
+    /// variable indices, then the values, each as a length-prefixed
+    /// vector in arkworks' compressed encoding. Repeated indices and
+    /// trailing bytes are rejected.
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let indices: Vec<u64> = read_vec(&mut reader)?;
+        let values: Vec<Fr> = read_vec(&mut reader)?;
+        expect_end(&mut reader)?;
+        if indices.len() != values.len() {
+            return Err(invalid_data(
+                "Witness has a different number of indices and values",
+            ));
+        }
+        let mut witness = Witness::new();
+        for (index, value) in indices.into_iter().zip(values) {
+            if witness.insert(Variable::new(index as usize), value).is_some() {
+                return Err(invalid_data(format!("Variable {index} is assigned twice")));
             }
+        }
+        Ok(witness)
+    }
+
+    pub fn write(&self, writer: impl Write) -> io::Result<()> {
+        let indices: Vec<u64> = self.values.keys().map(|var| var.index() as u64).collect();
+        let values: Vec<Fr> = self.values.values().copied().collect();
+        (indices, values)
+            .serialize_compressed(writer)
+            .map_err(invalid_data)
+    }
+
+    /// Loads a witness file: JSON for a `.json` extension, binary
+    /// otherwise.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&fs::read_to_string(path)?)
         } else {
-            Witness {
-                x: -Fr::from((-x) as u64),
+            Self::read(BufReader::new(File::open(path)?))
+        }
+    }
+
+    /// Saves a witness file in the format [`load`](Self::load) picks
+    /// from the extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            fs::write(path, self.to_json())
+        } else {
+            self.write(BufWriter::new(File::create(path)?))
+        }
+    }
+}
+
+impl<F: PrimeField> FromIterator<(Variable, F)> for Witness<F> {
+    fn from_iter<I: IntoIterator<Item = (Variable, F)>>(iter: I) -> Self {
+        Witness {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<F: PrimeField, const N: usize> From<[(Variable, F); N]> for Witness<F> {
+    fn from(pairs: [(Variable, F); N]) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+/// Reduces a signed integer of any width into the field, so -x maps to
+/// p - x.
+pub fn from_bigint(value: &BigInt) -> Fr {
+    let magnitude = Fr::from(value.magnitude().clone());
+    match value.sign() {
+        Sign::Minus => -magnitude,
+        _ => magnitude,
+    }
+}
+
+/// Reads unsigned big-endian bytes, reducing modulo p.
+pub fn from_be_bytes(bytes: &[u8]) -> Fr {
+    Fr::from_be_bytes_mod_order(bytes)
+}
+
+/// Reads unsigned little-endian bytes, reducing modulo p.
+pub fn from_le_bytes(bytes: &[u8]) -> Fr {
+    Fr::from_le_bytes_mod_order(bytes)
+}
+
+/// Reads a two's complement big-endian integer as wide as `bytes`.
+pub fn from_signed_be_bytes(bytes: &[u8]) -> Fr {
+    from_bigint(&BigInt::from_signed_bytes_be(bytes))
+}
+
+/// Reads a two's complement little-endian integer as wide as `bytes`.
+pub fn from_signed_le_bytes(bytes: &[u8]) -> Fr {
+    from_bigint(&BigInt::from_signed_bytes_le(bytes))
+}
+
+/// Parses a decimal or `0x` hexadecimal integer, optionally negative.
+pub fn parse_integer(text: &str) -> Option<Fr> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    // BigInt accepts a sign of its own, so check the digits first
+    let (digits, radix) = match digits.strip_prefix("0x") {
+        Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => (hex, 16),
+        None if digits.bytes().all(|b| b.is_ascii_digit()) => (digits, 10),
+        _ => return None,
+    };
+    let magnitude = BigInt::parse_bytes(digits.as_bytes(), radix)?;
+    let value = from_bigint(&magnitude);
+    Some(if negative { -value } else { value })
+}
+
+/// Why the witness could not be generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WitnessError {
+    /// No value was given for an input variable
+    MissingInput(Variable),
+    /// An assignment read a variable that has no value yet
+    MissingAssignment(Variable),
+    /// The assignment of this variable divided by zero
+    DivisionByZero(Variable),
+}
+
+impl fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WitnessError::MissingInput(var) => {
+                write!(f, "no value given for input variable {}", var.index())
             }
+            WitnessError::MissingAssignment(var) => {
+                write!(f, "variable {} is read before it is assigned", var.index())
+            }
+            WitnessError::DivisionByZero(var) => {
+                write!(f, "assigning variable {} divides by zero", var.index())
+            }
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
+/// Computes a variable's value from the variables allocated before it.
+pub type AssignmentFn<F = Fr> = dyn Fn(&Assignment<F>) -> Result<F, WitnessError> + Send + Sync;
+
+/// Where a variable's value comes from when the witness is generated.
+#[derive(Clone)]
+pub(crate) enum Source<F: PrimeField> {
+    /// Given by the caller
+    Input,
+    /// Derived from earlier variables
+    Derived(Arc<AssignmentFn<F>>),
+}
+
+impl<F: PrimeField> fmt::Debug for Source<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Input => f.write_str("Input"),
+            Source::Derived(_) => f.write_str("Derived"),
         }
     }
 }
+
+/// Values assigned so far, as seen by the assignment of `target`.
+pub struct Assignment<'a, F: PrimeField = Fr> {
+    values: &'a [F],
+    target: Variable,
+}
+
+impl<'a, F: PrimeField> Assignment<'a, F> {
+    pub(crate) fn new(values: &'a [F], target: Variable) -> Self {
+        Assignment { values, target }
+    }
+
+    /// Value of an already assigned variable.
+    pub fn get(&self, var: Variable) -> Result<F, WitnessError> {
+        self.values
+            .get(var.index())
+            .copied()
+            .ok_or(WitnessError::MissingAssignment(var))
+    }
+
+    /// 1 / value, failing for zero.
+    pub fn inverse(&self, value: F) -> Result<F, WitnessError> {
+        value
+            .inverse()
+            .ok_or(WitnessError::DivisionByZero(self.target))
+    }
+}
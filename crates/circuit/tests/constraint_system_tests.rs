@@ -0,0 +1,107 @@
+use ark_bls12_381::Fr;
+use circuit::constraint_system::ConstraintSystem;
+use circuit::gate::Gate;
+use circuit::square_circuit::SquareCircuit;
+
+#[test]
+fn test_add_and_mul_chain() {
+    let mut cs = ConstraintSystem::new();
+
+    // (3 + 4) * 5 = 35
+    let x = cs.alloc(Fr::from(3u64));
+    let y = cs.alloc(Fr::from(4u64));
+    let z = cs.alloc(Fr::from(5u64));
+    let sum = cs.add(x, y);
+    let product = cs.mul(sum, z);
+
+    assert_eq!(cs.value(product), Fr::from(35u64));
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_public_inputs() {
+    let mut cs = ConstraintSystem::new();
+
+    let x = cs.public_input(Fr::from(7u64));
+    let y = cs.public_input(Fr::from(9u64));
+    cs.add(x, y);
+
+    assert_eq!(cs.public_inputs(), vec![Fr::from(7u64), Fr::from(9u64)]);
+    assert_eq!(cs.public_input_rows().len(), 2);
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_constant_rejects_other_value() {
+    let mut cs = ConstraintSystem::new();
+
+    let c = cs.constant(Fr::from(10u64));
+    assert!(cs.is_satisfied());
+
+    // Reusing c on a gate that forces a different value fails
+    let eleven = cs.alloc(Fr::from(11u64));
+    cs.add_gate(Gate::addition(), &[c, cs.zero(), eleven]);
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_domain_size_is_padded() {
+    let mut cs = ConstraintSystem::new();
+    assert_eq!(cs.domain_size(), 2);
+
+    for i in 0..4 {
+        cs.constant(Fr::from(i as u64));
+    }
+    // 1 zero row + 4 constants = 5 rows
+    assert_eq!(cs.rows().len(), 5);
+    assert_eq!(cs.domain_size(), 8);
+}
+
+#[test]
+fn test_square_circuit_synthesize() {
+    let circuit = SquareCircuit::new();
+
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+    assert!(cs.is_satisfied());
+    assert_eq!(cs.public_inputs(), circuit.public_inputs());
+
+    let cs = circuit.synthesize(&circuit.witness(6u64));
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_wide_rows() {
+    let mut cs = ConstraintSystem::with_wires(5);
+    assert_eq!(cs.num_wires(), 5);
+
+    // a + b + c + d = e in one row
+    let inputs = [1u64, 2, 3, 4].map(|v| cs.alloc(Fr::from(v)));
+    let sum = cs.alloc(Fr::from(10u64));
+    let one = Fr::from(1u64);
+    let row = cs.add_gate(
+        Gate::linear(&[one, one, one, one, -one], Fr::from(0u64)),
+        &[inputs[0], inputs[1], inputs[2], inputs[3], sum],
+    );
+    assert_eq!(cs.wire_values(row).len(), 5);
+    assert!(cs.is_satisfied());
+
+    // Three-wire helpers pad the remaining wires with zero
+    let product = cs.mul(sum, inputs[1]);
+    assert_eq!(cs.rows().last().unwrap().wires[3], cs.zero());
+    assert_eq!(cs.value(product), Fr::from(20u64));
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+#[should_panic(expected = "selectors for 5 wires")]
+fn test_gate_wider_than_rows_panics() {
+    let mut cs = ConstraintSystem::new();
+    let one = Fr::from(1u64);
+    cs.add_gate(Gate::linear(&[one; 5], one), &[]);
+}
+
+#[test]
+#[should_panic(expected = "at least 3 wires")]
+fn test_too_few_wires_panics() {
+    ConstraintSystem::<Fr>::with_wires(2);
+}
@@ -0,0 +1,134 @@
+use ark_bls12_381::Fr;
+use ark_ff::{Field, One, Zero};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::custom_gates::{BooleanGate, EdwardsAddGate, Pow5Gate};
+use circuit::gate::{CustomGate, Gate};
+
+/// Jubjub: -x² + y² = 1 + d·x²·y² with d = -(10240/10241)
+fn jubjub() -> EdwardsAddGate {
+    let d = -(Fr::from(10240u64) * Fr::from(10241u64).inverse().unwrap());
+    EdwardsAddGate::new(-Fr::one(), d)
+}
+
+/// Finds a point on the curve by solving for x, starting from y = seed.
+fn point_with_y(gate: &EdwardsAddGate, seed: u64) -> (Fr, Fr) {
+    (seed..)
+        .find_map(|y| {
+            // x² = (y² - 1) / (d·y² - a)
+            let y = Fr::from(y);
+            let x2 = (y.square() - Fr::one()) * (gate.d * y.square() - gate.a).inverse()?;
+            x2.sqrt().map(|x| (x, y))
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_boolean_gate() {
+    let gate = BooleanGate;
+    let on = [Fr::one()];
+
+    assert!(gate.evaluate(&on, &[Fr::zero(); 3], &[])[0].is_zero());
+    assert!(gate.evaluate(&on, &[Fr::one(), Fr::zero(), Fr::zero()], &[])[0].is_zero());
+    assert!(!gate.evaluate(&on, &[Fr::from(2u64), Fr::zero(), Fr::zero()], &[])[0].is_zero());
+
+    // Selector off: any value passes
+    assert!(
+        gate.evaluate(
+            &[Fr::zero()],
+            &[Fr::from(2u64), Fr::zero(), Fr::zero()],
+            &[]
+        )[0]
+        .is_zero()
+    );
+}
+
+#[test]
+fn test_pow5_gate() {
+    let gate = Pow5Gate;
+    let on = [Fr::one()];
+
+    // 3⁵ = 243 ✓
+    let wires = [Fr::from(3u64), Fr::zero(), Fr::from(243u64)];
+    assert!(gate.evaluate(&on, &wires, &[])[0].is_zero());
+
+    // 3⁵ ≠ 242 ✗
+    let wires = [Fr::from(3u64), Fr::zero(), Fr::from(242u64)];
+    assert!(!gate.evaluate(&on, &wires, &[])[0].is_zero());
+}
+
+#[test]
+fn test_edwards_add_gate() {
+    let gate = jubjub();
+    let p1 = point_with_y(&gate, 2);
+    let p2 = point_with_y(&gate, 5);
+    let p3 = gate.add(p1, p2);
+
+    let wires = [p1.0, p1.1, p2.0];
+    let next = [p2.1, p3.0, p3.1];
+    let constraints = gate.evaluate(&[Fr::one()], &wires, &next);
+    assert_eq!(constraints.len(), gate.num_constraints());
+    assert!(constraints.iter().all(|c| c.is_zero()));
+
+    // Wrong x3 ✗
+    let next = [p2.1, p3.0 + Fr::one(), p3.1];
+    assert!(!gate.evaluate(&[Fr::one()], &wires, &next)[0].is_zero());
+}
+
+#[test]
+fn test_edwards_identity_is_neutral() {
+    let gate = jubjub();
+    let p = point_with_y(&gate, 3);
+
+    assert_eq!(gate.add(p, (Fr::zero(), Fr::one())), p);
+}
+
+#[test]
+fn test_custom_gates_in_constraint_system() {
+    let mut cs = ConstraintSystem::new();
+    let boolean = cs.register_gate(BooleanGate);
+    let pow5 = cs.register_gate(Pow5Gate);
+
+    let bit = cs.alloc(Fr::one());
+    cs.custom_gate(boolean, vec![Fr::one()], &[bit, cs.zero(), cs.zero()]);
+
+    let x = cs.alloc(Fr::from(2u64));
+    let x5 = cs.alloc(Fr::from(32u64));
+    cs.custom_gate(pow5, vec![Fr::one()], &[x, cs.zero(), x5]);
+
+    assert!(cs.is_satisfied());
+
+    let not_bit = cs.alloc(Fr::from(3u64));
+    cs.custom_gate(boolean, vec![Fr::one()], &[not_bit, cs.zero(), cs.zero()]);
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_edwards_gate_reads_next_row() {
+    let curve = jubjub();
+    let p1 = point_with_y(&curve, 2);
+    let p2 = point_with_y(&curve, 5);
+    let p3 = curve.add(p1, p2);
+
+    let mut cs = ConstraintSystem::new();
+    let ecc = cs.register_gate(curve);
+
+    let [x1, y1, x2, y2, x3, y3] = [p1.0, p1.1, p2.0, p2.1, p3.0, p3.1].map(|v| cs.alloc(v));
+    cs.custom_gate(ecc, vec![Fr::one()], &[x1, y1, x2]);
+    cs.add_gate(Gate::disabled(), &[y2, x3, y3]);
+    assert!(cs.is_satisfied());
+
+    // Dropping the second row leaves the gate reading padding
+    let mut cs = ConstraintSystem::new();
+    let ecc = cs.register_gate(jubjub());
+    let [x1, y1, x2] = [p1.0, p1.1, p2.0].map(|v| cs.alloc(v));
+    cs.custom_gate(ecc, vec![Fr::one()], &[x1, y1, x2]);
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+#[should_panic(expected = "already registered")]
+fn test_duplicate_gate_registration_panics() {
+    let mut cs: ConstraintSystem = ConstraintSystem::new();
+    cs.register_gate(BooleanGate);
+    cs.register_gate(BooleanGate);
+}
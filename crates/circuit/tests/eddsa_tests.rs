@@ -0,0 +1,195 @@
+use ark_bls12_381::Fr;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ed_on_bls12_381::{EdwardsAffine, Fr as JubjubScalar};
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::eddsa::{SecretKey, Signature, scalar_to_base};
+use circuit::gadgets::boolean::alloc_bit;
+use circuit::gadgets::eddsa::{SignatureVar, jubjub, point, verify};
+use circuit::gadgets::range::{assert_canonical, to_bits_canonical};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn scalar_bits(scalar: JubjubScalar) -> Vec<bool> {
+    scalar.into_bigint().to_bits_le()[..JubjubScalar::MODULUS_BIT_SIZE as usize].to_vec()
+}
+
+#[test]
+fn test_canonical_bits_accept_field_elements() {
+    for value in [Fr::zero(), Fr::one(), -Fr::one(), Fr::from(12345u64)] {
+        let mut cs = ConstraintSystem::new();
+        let x = cs.alloc(value);
+        let bits = to_bits_canonical(&mut cs, x);
+
+        assert_eq!(bits.len(), 255);
+        assert!(cs.is_satisfied());
+    }
+}
+
+#[test]
+fn test_canonical_check_rejects_aliased_bits() {
+    let alloc_bits = |cs: &mut ConstraintSystem, bits: Vec<bool>| -> Vec<_> {
+        bits[..255].iter().map(|&bit| alloc_bit(cs, bit)).collect()
+    };
+
+    // p - 1 is the largest canonical value
+    let mut cs = ConstraintSystem::new();
+    let bits = alloc_bits(&mut cs, (-Fr::one()).into_bigint().to_bits_le());
+    assert_canonical(&mut cs, &bits);
+    assert!(cs.is_satisfied());
+
+    // 1 + p reads as 1 in the field but is not canonical
+    let mut alias = Fr::MODULUS;
+    alias.add_with_carry(&1u64.into());
+    let mut cs = ConstraintSystem::new();
+    let bits = alloc_bits(&mut cs, alias.to_bits_le());
+    assert_canonical(&mut cs, &bits);
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_point_addition_matches_native() {
+    let g = EdwardsAffine::generator();
+    let h = (g * JubjubScalar::from(7u64)).into_affine();
+    let expected = (g + h).into_affine();
+
+    let mut cs = ConstraintSystem::new();
+    let curve = jubjub(&mut cs);
+    let p = curve.alloc_point(&mut cs, point(&g));
+    let q = curve.alloc_point(&mut cs, point(&h));
+    let sum = curve.add(&mut cs, p, q);
+    let doubled = curve.double(&mut cs, p);
+
+    assert_eq!(curve.value(&cs, sum), point(&expected));
+    assert_eq!(curve.value(&cs, doubled), point(&(g + g).into_affine()));
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_point_off_curve_is_rejected() {
+    let mut cs = ConstraintSystem::new();
+    let curve = jubjub(&mut cs);
+    curve.alloc_point(&mut cs, (Fr::from(2u64), Fr::from(3u64)));
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_wrong_sum_is_rejected() {
+    let g = EdwardsAffine::generator();
+
+    let mut cs = ConstraintSystem::new();
+    let curve = jubjub(&mut cs);
+    let p = curve.alloc_point(&mut cs, point(&g));
+    let sum = curve.add(&mut cs, p, p);
+    cs.set_value(sum.x, cs.value(sum.x) + Fr::one());
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_scalar_mul_matches_native() {
+    let g = EdwardsAffine::generator();
+    let scalar = JubjubScalar::from(0xC0FFEEu64);
+
+    let mut cs = ConstraintSystem::new();
+    let curve = jubjub(&mut cs);
+    let p = curve.alloc_point(&mut cs, point(&g));
+    let bits: Vec<_> = scalar_bits(scalar)
+        .into_iter()
+        .map(|bit| alloc_bit(&mut cs, bit))
+        .collect();
+    let product = curve.scalar_mul(&mut cs, &bits, p);
+
+    assert_eq!(
+        curve.value(&cs, product),
+        point(&(g * scalar).into_affine())
+    );
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_native_signature_verifies() {
+    let mut rng = StdRng::seed_from_u64(33);
+    let sk = SecretKey::generate(&mut rng);
+    let pk = sk.public_key();
+    let message = Fr::from(42u64);
+    let signature = sk.sign(message, &mut rng);
+
+    assert!(pk.verify(message, &signature));
+    assert!(!pk.verify(message + Fr::one(), &signature));
+    assert!(
+        !SecretKey::generate(&mut rng)
+            .public_key()
+            .verify(message, &signature)
+    );
+}
+
+fn verify_in_circuit(pk: &EdwardsAffine, message: Fr, signature: &Signature) -> bool {
+    let mut cs = ConstraintSystem::with_wires(4);
+    let curve = jubjub(&mut cs);
+    let public_key = curve.public_point(&mut cs, point(pk));
+    let message = cs.public_input(message);
+    let signature = SignatureVar::alloc(&mut cs, &curve, signature);
+    verify(&mut cs, &curve, public_key, message, &signature);
+    cs.is_satisfied()
+}
+
+#[test]
+fn test_signature_gadget_accepts_valid_signature() {
+    let mut rng = StdRng::seed_from_u64(34);
+    let sk = SecretKey::generate(&mut rng);
+    let message = Fr::from(1234u64);
+    let signature = sk.sign(message, &mut rng);
+
+    assert!(verify_in_circuit(&sk.public_key().0, message, &signature));
+}
+
+#[test]
+fn test_signature_gadget_rejects_forgeries() {
+    let mut rng = StdRng::seed_from_u64(35);
+    let sk = SecretKey::generate(&mut rng);
+    let pk = sk.public_key().0;
+    let message = Fr::from(1234u64);
+    let signature = sk.sign(message, &mut rng);
+
+    assert!(!verify_in_circuit(&pk, message + Fr::one(), &signature));
+
+    let other = SecretKey::generate(&mut rng).public_key().0;
+    assert!(!verify_in_circuit(&other, message, &signature));
+
+    let mut tampered = signature;
+    tampered.s += JubjubScalar::one();
+    assert!(!verify_in_circuit(&pk, message, &tampered));
+}
+
+#[test]
+fn test_signature_gadget_rejects_non_canonical_s() {
+    let mut rng = StdRng::seed_from_u64(36);
+    let sk = SecretKey::generate(&mut rng);
+    let pk = sk.public_key().0;
+    let message = Fr::from(1234u64);
+    let order = Fr::from(JubjubScalar::MODULUS);
+    let bound = Fr::from(2u64).pow([JubjubScalar::MODULUS_BIT_SIZE as u64]);
+
+    // Sign until s + ℓ still fits the bits of s, so only the order check
+    // can reject it
+    let (signature, s) = loop {
+        let signature = sk.sign(message, &mut rng);
+        let s = scalar_to_base(signature.s) + order;
+        if s.into_bigint() < bound.into_bigint() {
+            break (signature, s);
+        }
+    };
+
+    let mut cs = ConstraintSystem::with_wires(4);
+    let curve = jubjub(&mut cs);
+    let public_key = curve.public_point(&mut cs, point(&pk));
+    let message = cs.public_input(message);
+    let signature = SignatureVar {
+        r: curve.alloc_point(&mut cs, point(&signature.r)),
+        s: cs.alloc(s),
+    };
+    verify(&mut cs, &curve, public_key, message, &signature);
+    assert!(!cs.is_satisfied());
+}
@@ -0,0 +1,131 @@
+use ark_bls12_381::Fr;
+use ark_ec::AffineRepr;
+use ark_ed_on_bls12_381::EdwardsAffine;
+use circuit::constraint_system::{ConstraintSystem, Variable};
+use circuit::format::{parse, write};
+use circuit::gadgets::eddsa::{jubjub, point};
+use circuit::gadgets::poseidon;
+use circuit::gadgets::range::range_check_lookup;
+use circuit::lookup::LookupTable;
+use circuit::witness::Witness;
+
+const EXAMPLE: &str = "
+# v1 = v2², v3 = v2⁵, v3 < 4
+wires 3
+variables 4
+gate pow5
+table range_2
+entry 0
+entry 1
+entry 2
+entry 3
+row public : 1
+row q_m=1 q_o=-1 : 2 2 1
+row pow5=1 : 2 0 3
+row lookup=range_2 : 3
+copy 2 3
+";
+
+/// Witness assigning every variable its current value.
+fn full_witness(cs: &ConstraintSystem) -> Witness {
+    (1..cs.num_variables())
+        .map(|i| (Variable::new(i), cs.value(Variable::new(i))))
+        .collect()
+}
+
+#[test]
+fn test_parse_example() {
+    let mut cs = parse(EXAMPLE).unwrap();
+
+    assert_eq!(cs.num_wires(), 3);
+    assert_eq!(cs.num_variables(), 4);
+    assert_eq!(cs.rows().len(), 5);
+    assert_eq!(cs.public_input_rows(), &[1]);
+    assert_eq!(cs.custom_gates()[0].name(), "pow5");
+    assert_eq!(cs.tables()[0], LookupTable::range(2));
+
+    // `copy 2 3` puts variable 2 wherever 3 was used
+    assert_eq!(cs.rows()[3].wires[2], Variable::new(2));
+    assert_eq!(cs.rows()[4].wires[0], Variable::new(2));
+
+    let witness = Witness::new()
+        .with(Variable::new(1), 1u64)
+        .with(Variable::new(2), 1u64)
+        .with(Variable::new(3), 0u64);
+    cs.set_witness(&witness).unwrap();
+    assert!(cs.is_satisfied());
+
+    let witness = witness
+        .with(Variable::new(1), 4u64)
+        .with(Variable::new(2), 2u64);
+    cs.set_witness(&witness).unwrap();
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_written_circuit_parses_back() {
+    let mut cs = ConstraintSystem::with_wires(4);
+    let range = cs.register_table(LookupTable::range(4));
+    let a = cs.public_input(Fr::from(3u64));
+    let b = cs.alloc(Fr::from(200u64));
+    let digest = poseidon::hash_two(&mut cs, a, b);
+    range_check_lookup(&mut cs, range, 4, b, 2);
+    cs.expose(digest);
+
+    let curve = jubjub(&mut cs);
+    let g = curve.alloc_point(&mut cs, point(&EdwardsAffine::generator()));
+    curve.double(&mut cs, g);
+    assert!(cs.is_satisfied());
+
+    let text = write(&cs);
+    let mut parsed = parse(&text).unwrap();
+    assert_eq!(write(&parsed), text);
+    assert_eq!(parsed.rows().len(), cs.rows().len());
+    assert_eq!(parsed.public_input_rows(), cs.public_input_rows());
+    for (ours, theirs) in parsed.rows().iter().zip(cs.rows()) {
+        assert_eq!(ours.gate, theirs.gate);
+        assert_eq!(ours.wires, theirs.wires);
+        assert_eq!(ours.custom, theirs.custom);
+        assert_eq!(ours.lookup, theirs.lookup);
+    }
+
+    parsed.set_witness(&full_witness(&cs)).unwrap();
+    assert!(parsed.is_satisfied());
+    assert_eq!(parsed.public_inputs(), cs.public_inputs());
+}
+
+#[test]
+fn test_values_and_wide_selectors() {
+    let cs = parse("wires 5\nrow q_l=-1 q_5=0x10 q_c=-0x2 : 1 0 0 0 2").unwrap();
+    let gate = &cs.rows()[1].gate;
+
+    assert_eq!(gate.q_l, -Fr::from(1u64));
+    assert_eq!(gate.q_extra, vec![Fr::from(0u64), Fr::from(16u64)]);
+    assert_eq!(gate.q_c, -Fr::from(2u64));
+    assert!(write(&cs).contains("row q_l=-1 q_c=-2 q_5=16 : 1 0 0 0 2"));
+}
+
+#[test]
+fn test_errors_name_the_line() {
+    let cases = [
+        ("wires 2", 1, "at least 3 wires"),
+        ("row q_m=1 2 2", 1, "Expected ':'"),
+        ("\nrow q_m=x : 1", 2, "not an integer"),
+        ("row pow5=1 : 1", 1, "Unknown selector or gate pow5"),
+        ("gate pow5\nrow pow5=1,2 : 1", 2, "takes 1 selectors"),
+        ("gate sha3", 1, "Unknown custom gate"),
+        ("gate edwards_add a=1", 1, "needs parameter d"),
+        ("row lookup=xor : 1", 1, "Unknown lookup table"),
+        ("variables 2\n\nrow : 1 2", 3, "Variable 2 is out of range"),
+        ("row public q_l=1 : 1", 1, "public row"),
+        ("entry 1", 1, "outside of a table"),
+        ("table t\nentry 1\nentry 1 2", 3, "different widths"),
+        ("wire 3", 1, "Unknown declaration"),
+    ];
+
+    for (text, line, message) in cases {
+        let error = parse(text).err().unwrap_or_else(|| panic!("{text} parsed"));
+        assert_eq!(error.line, line, "{text}: {error}");
+        assert!(error.message.contains(message), "{text}: {error}");
+    }
+}
@@ -0,0 +1,254 @@
+use ark_bls12_381::Fr;
+use ark_ff::{Field, One, Zero};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::gadgets::arithmetic::{
+    assert_equal, assert_nonzero, inverse, is_equal, is_zero, select,
+};
+use circuit::gadgets::boolean::{alloc_bit, and, assert_boolean, not, or, xor};
+use circuit::gadgets::comparison::{assert_less_than, less_or_equal, less_than};
+use circuit::gadgets::range::{range_check, range_check_lookup, to_bits};
+use circuit::lookup::LookupTable;
+
+#[test]
+fn test_boolean_accepts_bits() {
+    let mut cs = ConstraintSystem::new();
+    alloc_bit(&mut cs, true);
+    alloc_bit(&mut cs, false);
+
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_boolean_rejects_non_bit() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(2u64));
+    assert_boolean(&mut cs, x);
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_boolean_operations() {
+    for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+        let mut cs = ConstraintSystem::new();
+        let va = alloc_bit(&mut cs, a);
+        let vb = alloc_bit(&mut cs, b);
+        let bit = |v: bool| if v { Fr::one() } else { Fr::zero() };
+
+        let and_out = and(&mut cs, va, vb);
+        let or_out = or(&mut cs, va, vb);
+        let xor_out = xor(&mut cs, va, vb);
+        let not_out = not(&mut cs, va);
+
+        assert_eq!(cs.value(and_out), bit(a && b));
+        assert_eq!(cs.value(or_out), bit(a || b));
+        assert_eq!(cs.value(xor_out), bit(a ^ b));
+        assert_eq!(cs.value(not_out), bit(!a));
+        assert!(cs.is_satisfied());
+    }
+}
+
+#[test]
+fn test_boolean_operation_rejects_wrong_output() {
+    let mut cs = ConstraintSystem::new();
+    let a = alloc_bit(&mut cs, true);
+    let b = alloc_bit(&mut cs, true);
+    let c = xor(&mut cs, a, b);
+    cs.set_value(c, Fr::one());
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_to_bits_decomposes() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(0b1011u64));
+    let bits = to_bits(&mut cs, x, 6);
+
+    let values: Vec<Fr> = bits.iter().map(|&b| cs.value(b)).collect();
+    let expected: Vec<Fr> = [1u64, 1, 0, 1, 0, 0].into_iter().map(Fr::from).collect();
+    assert_eq!(values, expected);
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_range_check_rejects_large_value() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(256u64));
+    range_check(&mut cs, x, 8);
+
+    assert!(!cs.is_satisfied());
+
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(255u64));
+    range_check(&mut cs, x, 8);
+
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_range_check_rejects_negative_value() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(-Fr::one());
+    range_check(&mut cs, x, 16);
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_to_bits_rejects_forged_bit() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(5u64));
+    let bits = to_bits(&mut cs, x, 4);
+    // 5 = 1 + 2·2, with a non-boolean "bit"
+    cs.set_value(bits[0], Fr::one());
+    cs.set_value(bits[1], Fr::from(2u64));
+    cs.set_value(bits[2], Fr::zero());
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_range_check_lookup() {
+    let mut cs = ConstraintSystem::new();
+    let table = cs.register_table(LookupTable::range(4));
+    let x = cs.alloc(Fr::from(0xBEEFu64));
+    let limbs = range_check_lookup(&mut cs, table, 4, x, 4);
+
+    assert_eq!(cs.value(limbs[0]), Fr::from(0xFu64));
+    assert_eq!(cs.value(limbs[3]), Fr::from(0xBu64));
+    assert!(cs.is_satisfied());
+
+    let mut cs = ConstraintSystem::new();
+    let table = cs.register_table(LookupTable::range(4));
+    let x = cs.alloc(Fr::from(0x1_0000u64));
+    range_check_lookup(&mut cs, table, 4, x, 4);
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_less_than() {
+    for (a, b, expected) in [
+        (3u64, 5u64, true),
+        (5, 3, false),
+        (4, 4, false),
+        (0, 255, true),
+    ] {
+        let mut cs = ConstraintSystem::new();
+        let va = cs.alloc(Fr::from(a));
+        let vb = cs.alloc(Fr::from(b));
+        let lt = less_than(&mut cs, va, vb, 8);
+        let le = less_or_equal(&mut cs, va, vb, 8);
+
+        assert_eq!(cs.value(lt), Fr::from(expected as u64));
+        assert_eq!(cs.value(le), Fr::from((a <= b) as u64));
+        assert!(cs.is_satisfied());
+    }
+}
+
+#[test]
+fn test_less_than_rejects_flipped_result() {
+    let mut cs = ConstraintSystem::new();
+    let a = cs.alloc(Fr::from(7u64));
+    let b = cs.alloc(Fr::from(3u64));
+    let lt = less_than(&mut cs, a, b, 8);
+    cs.set_value(lt, Fr::one());
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_assert_less_than() {
+    let mut cs = ConstraintSystem::new();
+    let a = cs.alloc(Fr::from(3u64));
+    let b = cs.alloc(Fr::from(9u64));
+    assert_less_than(&mut cs, a, b, 4);
+    assert!(cs.is_satisfied());
+
+    assert_less_than(&mut cs, b, a, 4);
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_select() {
+    let mut cs = ConstraintSystem::new();
+    let a = cs.alloc(Fr::from(10u64));
+    let b = cs.alloc(Fr::from(20u64));
+    let yes = alloc_bit(&mut cs, true);
+    let no = alloc_bit(&mut cs, false);
+
+    let picked_a = select(&mut cs, yes, a, b);
+    let picked_b = select(&mut cs, no, a, b);
+    assert_eq!(cs.value(picked_a), Fr::from(10u64));
+    assert_eq!(cs.value(picked_b), Fr::from(20u64));
+    assert!(cs.is_satisfied());
+
+    cs.set_value(picked_b, Fr::from(10u64));
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_is_zero() {
+    let mut cs = ConstraintSystem::new();
+    let zero = cs.alloc(Fr::zero());
+    let seven = cs.alloc(Fr::from(7u64));
+
+    let zero_out = is_zero(&mut cs, zero);
+    let seven_out = is_zero(&mut cs, seven);
+    assert_eq!(cs.value(zero_out), Fr::one());
+    assert_eq!(cs.value(seven_out), Fr::zero());
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_is_zero_rejects_wrong_claim() {
+    let mut cs = ConstraintSystem::new();
+    let seven = cs.alloc(Fr::from(7u64));
+    let out = is_zero(&mut cs, seven);
+    cs.set_value(out, Fr::one());
+    assert!(!cs.is_satisfied());
+
+    let mut cs = ConstraintSystem::new();
+    let zero = cs.alloc(Fr::zero());
+    let out = is_zero(&mut cs, zero);
+    cs.set_value(out, Fr::zero());
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_is_equal_and_assert_equal() {
+    let mut cs = ConstraintSystem::new();
+    let a = cs.alloc(Fr::from(4u64));
+    let b = cs.alloc(Fr::from(4u64));
+    let c = cs.alloc(Fr::from(5u64));
+
+    let ab = is_equal(&mut cs, a, b);
+    let ac = is_equal(&mut cs, a, c);
+    assert_eq!(cs.value(ab), Fr::one());
+    assert_eq!(cs.value(ac), Fr::zero());
+    assert_equal(&mut cs, a, b);
+    assert!(cs.is_satisfied());
+
+    assert_equal(&mut cs, a, c);
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_inverse() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(3u64));
+    let inv = inverse(&mut cs, x);
+
+    assert_eq!(cs.value(inv), Fr::from(3u64).inverse().unwrap());
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_inverse_of_zero_is_unsatisfiable() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::zero());
+    assert_nonzero(&mut cs, x);
+
+    assert!(!cs.is_satisfied());
+}
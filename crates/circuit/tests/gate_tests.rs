@@ -1,4 +1,5 @@
 use ark_bls12_381::Fr;
+use ark_ff::Zero;
 use circuit::gate::Gate;
 
 #[test]
@@ -45,3 +46,28 @@ fn test_addition_gate() {
         Fr::from(11u64)
     ));
 }
+
+#[test]
+fn test_linear_gate_over_wide_row() {
+    // 2·a + b + c + 3·d - e + 1 = 0
+    let gate = Gate::linear(
+        &[
+            Fr::from(2u64),
+            Fr::from(1u64),
+            Fr::from(1u64),
+            Fr::from(3u64),
+            -Fr::from(1u64),
+        ],
+        Fr::from(1u64),
+    );
+
+    // 2·1 + 2 + 3 + 3·4 + 1 = 20 ✓
+    let wires = [1u64, 2, 3, 4, 20].map(Fr::from);
+    assert!(gate.evaluate_row(&wires).is_zero());
+
+    let wires = [1u64, 2, 3, 4, 21].map(Fr::from);
+    assert!(!gate.evaluate_row(&wires).is_zero());
+
+    assert_eq!(gate.selectors(5).len(), 7);
+    assert_eq!(Gate::from_selectors(&gate.selectors(5)), gate);
+}
@@ -0,0 +1,80 @@
+use ark_bls12_381::Fr;
+use circuit::constraint_system::ConstraintSystem;
+use circuit::lookup::LookupTable;
+
+#[test]
+fn test_range_table() {
+    let table = LookupTable::range(4);
+
+    assert_eq!(table.len(), 16);
+    assert_eq!(table.width(), 1);
+    assert!(table.contains(&[Fr::from(15u64)]));
+    assert!(!table.contains(&[Fr::from(16u64)]));
+}
+
+#[test]
+fn test_xor_and_tables() {
+    let xor = LookupTable::xor(2);
+    let and = LookupTable::and(2);
+
+    assert_eq!(xor.len(), 16);
+    assert_eq!(xor.width(), 3);
+    assert!(xor.contains(&[Fr::from(3u64), Fr::from(1u64), Fr::from(2u64)]));
+    assert!(!xor.contains(&[Fr::from(3u64), Fr::from(1u64), Fr::from(3u64)]));
+    assert!(and.contains(&[Fr::from(3u64), Fr::from(1u64), Fr::from(1u64)]));
+}
+
+#[test]
+fn test_range_lookup_satisfied() {
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(4));
+    let x = cs.alloc(Fr::from(9u64));
+    cs.lookup(range, &[x]);
+
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_range_lookup_rejects_out_of_range() {
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(4));
+    let x = cs.alloc(Fr::from(16u64));
+    cs.lookup(range, &[x]);
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_lookup_checks_the_right_table() {
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(2));
+    let xor = cs.register_table(LookupTable::xor(2));
+    let a = cs.alloc(Fr::from(2u64));
+    let b = cs.alloc(Fr::from(3u64));
+    let c = cs.alloc(Fr::from(1u64));
+    cs.lookup(xor, &[a, b, c]);
+    cs.lookup(range, &[c]);
+    assert!(cs.is_satisfied());
+
+    // 2 ⊕ 3 is in the range table's values but not a row of the xor table
+    let d = cs.alloc(Fr::from(2u64));
+    cs.lookup(xor, &[a, b, d]);
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_tables_count_towards_domain_size() {
+    let mut cs: ConstraintSystem = ConstraintSystem::new();
+    cs.register_table(LookupTable::range(5));
+
+    assert_eq!(cs.domain_size(), 32);
+}
+
+#[test]
+#[should_panic(expected = "has 1 columns")]
+fn test_lookup_width_mismatch_panics() {
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(2));
+    let x = cs.alloc(Fr::from(1u64));
+    cs.lookup(range, &[x, x]);
+}
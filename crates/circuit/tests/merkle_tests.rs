@@ -0,0 +1,129 @@
+use ark_bls12_381::Fr;
+use ark_ff::{One, Zero};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::gadgets::merkle::{MerklePathVars, assert_membership, compute_root};
+use circuit::merkle::MerkleTree;
+use circuit::poseidon::hash_two;
+
+fn leaves(count: u64) -> Vec<Fr> {
+    (0..count).map(|i| Fr::from(i * 11 + 1)).collect()
+}
+
+#[test]
+fn test_tree_root_matches_manual_hashing() {
+    let leaves = leaves(4);
+    let tree = MerkleTree::new(2, &leaves);
+
+    let expected = hash_two(
+        hash_two(leaves[0], leaves[1]),
+        hash_two(leaves[2], leaves[3]),
+    );
+    assert_eq!(tree.depth(), 2);
+    assert_eq!(tree.root(), expected);
+}
+
+#[test]
+fn test_missing_leaves_are_zero() {
+    let tree = MerkleTree::new(2, &leaves(3));
+
+    assert_eq!(tree.leaf(3), Fr::zero());
+    assert_eq!(
+        tree.root(),
+        MerkleTree::new(2, &[leaves(3), vec![Fr::zero()]].concat()).root()
+    );
+}
+
+#[test]
+fn test_paths_verify_for_every_leaf() {
+    let tree = MerkleTree::new(3, &leaves(8));
+
+    for index in 0..8 {
+        let path = tree.path(index);
+        assert_eq!(path.siblings.len(), 3);
+        assert!(path.verify(tree.root(), tree.leaf(index)));
+        assert!(!path.verify(tree.root(), tree.leaf(index) + Fr::one()));
+    }
+}
+
+#[test]
+fn test_path_index_bits_are_little_endian() {
+    let tree = MerkleTree::new(3, &leaves(8));
+
+    assert_eq!(tree.path(6).index_bits, vec![false, true, true]);
+}
+
+#[test]
+#[should_panic(expected = "holds at most 4 leaves")]
+fn test_too_many_leaves_panics() {
+    MerkleTree::new(2, &leaves(5));
+}
+
+#[test]
+fn test_membership_gadget_accepts_valid_path() {
+    let tree = MerkleTree::new(3, &leaves(8));
+    let index = 5;
+
+    let mut cs = ConstraintSystem::with_wires(4);
+    let root = cs.public_input(tree.root());
+    let leaf = cs.alloc(tree.leaf(index));
+    let path = MerklePathVars::alloc(&mut cs, &tree.path(index));
+    assert_membership(&mut cs, root, leaf, &path);
+
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_membership_gadget_computes_native_root() {
+    let tree = MerkleTree::new(2, &leaves(4));
+
+    let mut cs = ConstraintSystem::new();
+    let leaf = cs.alloc(tree.leaf(2));
+    let path = MerklePathVars::alloc(&mut cs, &tree.path(2));
+    let root = compute_root(&mut cs, leaf, &path);
+
+    assert_eq!(cs.value(root), tree.root());
+}
+
+#[test]
+fn test_membership_gadget_rejects_wrong_leaf() {
+    let tree = MerkleTree::new(2, &leaves(4));
+
+    let mut cs = ConstraintSystem::with_wires(4);
+    let root = cs.public_input(tree.root());
+    let leaf = cs.alloc(Fr::from(999u64));
+    let path = MerklePathVars::alloc(&mut cs, &tree.path(1));
+    assert_membership(&mut cs, root, leaf, &path);
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_membership_gadget_rejects_wrong_index() {
+    let tree = MerkleTree::new(2, &leaves(4));
+
+    let mut path = tree.path(1);
+    path.index_bits[0] = false;
+
+    let mut cs = ConstraintSystem::with_wires(4);
+    let root = cs.public_input(tree.root());
+    let leaf = cs.alloc(tree.leaf(1));
+    let path = MerklePathVars::alloc(&mut cs, &path);
+    assert_membership(&mut cs, root, leaf, &path);
+
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_membership_gadget_rejects_non_boolean_index() {
+    let tree = MerkleTree::new(1, &leaves(2));
+
+    let mut cs = ConstraintSystem::with_wires(4);
+    let root = cs.public_input(tree.root());
+    let leaf = cs.alloc(tree.leaf(0));
+    let path = MerklePathVars::alloc(&mut cs, &tree.path(0));
+    assert_membership(&mut cs, root, leaf, &path);
+    assert!(cs.is_satisfied());
+
+    cs.set_value(path.index_bits[0], Fr::from(2u64));
+    assert!(!cs.is_satisfied());
+}
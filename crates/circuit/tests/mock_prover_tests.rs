@@ -0,0 +1,161 @@
+use ark_bls12_381::Fr;
+use ark_ff::One;
+use circuit::constraint_system::{ConstraintSystem, Variable};
+use circuit::custom_gates::Pow5Gate;
+use circuit::gadgets::poseidon;
+use circuit::gate::Gate;
+use circuit::lookup::LookupTable;
+use circuit::mock_prover::{Failure, MockProver};
+use circuit::optimizer::optimize;
+
+/// x·y = z, then z + 1 = out with out public.
+fn product_plus_one(x: u64, y: u64) -> ConstraintSystem {
+    let mut cs = ConstraintSystem::new();
+    let out = cs.public_input(Fr::from(x * y + 1));
+    let x = cs.alloc(Fr::from(x));
+    let y = cs.alloc(Fr::from(y));
+    cs.set_name(x, "x");
+    cs.set_name(y, "y");
+    let z = cs.region("product", |cs| cs.mul(x, y));
+    cs.set_name(z, "z");
+    let one = cs.constant(Fr::one());
+    let sum = cs.region("sum", |cs| cs.add(z, one));
+    cs.add_gate(Gate::addition(), &[sum, cs.zero(), out]);
+    cs
+}
+
+#[test]
+fn test_satisfied_circuit_passes() {
+    let cs = product_plus_one(3, 4);
+    assert_eq!(MockProver::new(&cs).verify(), Ok(()));
+
+    let mut cs = ConstraintSystem::with_wires(4);
+    let a = cs.alloc(Fr::from(1u64));
+    let b = cs.alloc(Fr::from(2u64));
+    cs.region("hash", |cs| poseidon::hash_two(cs, a, b));
+    assert_eq!(MockProver::new(&cs).verify(), Ok(()));
+    assert!(
+        cs.annotation(cs.rows().len() - 1)
+            .unwrap()
+            .starts_with("hash")
+    );
+}
+
+#[test]
+fn test_failing_gate_names_row_and_variables() {
+    let mut cs = product_plus_one(3, 4);
+    let z = Variable::new(4);
+    assert_eq!(cs.name(z), Some("z"));
+    cs.set_value(z, Fr::from(13u64));
+
+    let failures = MockProver::new(&cs).verify().unwrap_err();
+    // The product and the sum that reads z both break
+    assert_eq!(failures.len(), 2);
+    let Failure::Gate {
+        row, annotation, ..
+    } = &failures[0]
+    else {
+        panic!("{failures:?}");
+    };
+    assert_eq!(cs.rows()[*row].wires[2], z);
+    assert_eq!(annotation.as_deref(), Some("product"));
+    assert_eq!(
+        failures[0].to_string(),
+        format!("row {row} (product): gate evaluates to -1 on x=3 y=4 z=13")
+    );
+    assert!(failures[1].to_string().contains("(sum)"));
+}
+
+#[test]
+fn test_broken_copy_is_reported() {
+    // y = x + 1 on one row and y·y = 16 on another
+    let square_of_sum = |y_on_square: Option<Fr>| {
+        let mut cs = ConstraintSystem::new();
+        let one = cs.constant(Fr::one());
+        let x = cs.alloc(Fr::from(3u64));
+        let y = cs.add(x, one);
+        cs.set_name(y, "y");
+        let out = cs.public_input(Fr::from(16u64));
+        let y = y_on_square.map_or(y, |value| cs.alloc(value));
+        cs.add_gate(Gate::multiplication(), &[y, y, out]);
+        cs
+    };
+    let honest = square_of_sum(None);
+    let cheating = square_of_sum(Some(-Fr::from(4u64)));
+    assert_eq!(MockProver::new(&cheating).verify(), Ok(()));
+
+    let failures = MockProver::with_layout(&honest, &cheating)
+        .verify()
+        .unwrap_err();
+    assert_eq!(
+        failures,
+        vec![Failure::Copy {
+            variable: "y".to_string(),
+            first: (2, 2, Fr::from(4u64)),
+            mismatched: vec![(4, 0, -Fr::from(4u64)), (4, 1, -Fr::from(4u64))],
+        }]
+    );
+    assert_eq!(
+        failures[0].to_string(),
+        "copy of y: 4 at row 2 wire 2, -4 at row 4 wire 0, -4 at row 4 wire 1"
+    );
+}
+
+#[test]
+fn test_custom_gate_lookup_and_public_input_failures() {
+    let mut cs = ConstraintSystem::new();
+    let pow5 = cs.register_gate(Pow5Gate);
+    let range = cs.register_table(LookupTable::range(2));
+    let out = cs.public_input(Fr::from(32u64));
+    let x = cs.alloc(Fr::from(2u64));
+    let row = cs.custom_gate(pow5, vec![Fr::one()], &[x, cs.zero(), out]);
+    cs.annotate(row, "x⁵");
+    cs.lookup(range, &[x]);
+    assert_eq!(MockProver::new(&cs).verify(), Ok(()));
+
+    let failures = MockProver::new(&cs)
+        .with_public_inputs(&[Fr::from(33u64)])
+        .verify()
+        .unwrap_err();
+    assert!(matches!(failures[..], [Failure::Gate { row: 1, .. }]));
+
+    cs.set_value(x, Fr::from(4u64));
+    let failures = MockProver::new(&cs).verify().unwrap_err();
+    assert!(matches!(
+        &failures[..],
+        [
+            Failure::CustomGate {
+                gate: "pow5",
+                constraint: 0,
+                ..
+            },
+            Failure::Lookup { .. },
+        ]
+    ));
+    assert!(
+        failures[0]
+            .to_string()
+            .starts_with("row 2 (x⁵): pow5 constraint 0")
+    );
+    assert_eq!(
+        failures[1].to_string(),
+        "row 3: v2=4 is not in table range_2"
+    );
+}
+
+#[test]
+fn test_annotations_follow_optimized_rows() {
+    let mut cs = product_plus_one(3, 4);
+    let rows = cs.rows().len();
+    optimize(&mut cs);
+    assert!(cs.rows().len() < rows);
+
+    // The sum row merges into the last one, the product row keeps its note
+    let annotated: Vec<usize> = (0..cs.rows().len())
+        .filter(|&i| cs.annotation(i).is_some())
+        .collect();
+    assert_eq!(annotated.len(), 1);
+    assert_eq!(cs.annotation(annotated[0]), Some("product"));
+    assert_eq!(cs.name(cs.rows()[annotated[0]].wires[2]), Some("z"));
+    assert_eq!(MockProver::new(&cs).verify(), Ok(()));
+}
@@ -0,0 +1,190 @@
+use ark_bls12_381::Fr;
+use ark_ff::One;
+use circuit::constraint_system::{ConstraintSystem, Variable};
+use circuit::gadgets::arithmetic::assert_equal;
+use circuit::gadgets::poseidon;
+use circuit::gadgets::range::range_check_lookup;
+use circuit::lookup::LookupTable;
+use circuit::optimizer::{
+    deduplicate_constants, eliminate_dead_rows, fold_constants, merge_linear, optimize,
+};
+use circuit::r1cs::{R1cs, load_wtns};
+use circuit::witness::Witness;
+
+/// Variables the rows still refer to.
+fn used_variables(cs: &ConstraintSystem) -> Vec<Variable> {
+    let mut used: Vec<Variable> = cs
+        .rows()
+        .iter()
+        .flat_map(|row| row.wires.iter().copied())
+        .filter(|&var| var != cs.zero())
+        .collect();
+    used.sort();
+    used.dedup();
+    used
+}
+
+/// Checks the optimized system accepts the same witnesses as the
+/// original: both hold on honestly generated values, and both break when
+/// any variable the optimized rows still use is changed. The circuits
+/// below pin every input down, so no kept variable is free to change.
+fn assert_equivalent(original: &ConstraintSystem, optimized: &ConstraintSystem, inputs: &Witness) {
+    let mut original = original.clone();
+    let mut optimized = optimized.clone();
+    original.set_witness(inputs).unwrap();
+    optimized.set_witness(inputs).unwrap();
+    assert!(original.is_satisfied());
+    assert!(optimized.is_satisfied());
+    assert_eq!(optimized.public_inputs(), original.public_inputs());
+
+    for var in used_variables(&optimized) {
+        let value = optimized.value(var);
+        original.set_value(var, value + Fr::one());
+        optimized.set_value(var, value + Fr::one());
+        assert!(!original.is_satisfied(), "original accepts {var:?}");
+        assert!(!optimized.is_satisfied(), "optimized accepts {var:?}");
+        original.set_value(var, value);
+        optimized.set_value(var, value);
+    }
+}
+
+#[test]
+fn test_duplicate_constants_share_a_variable() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(2u64));
+    let mut acc = x;
+    for _ in 0..3 {
+        let three = cs.constant(Fr::from(3u64));
+        acc = cs.mul(acc, three);
+    }
+    let zero = cs.constant(Fr::from(0u64));
+    acc = cs.add(acc, zero);
+    cs.expose(acc);
+    let original = cs.clone();
+
+    assert_eq!(deduplicate_constants(&mut cs), 3);
+    assert_eq!(cs.rows().len(), original.rows().len() - 3);
+    assert_equivalent(&original, &cs, &Witness::new().with(x, 2u64));
+}
+
+#[test]
+fn test_constants_fold_into_selectors() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(2u64));
+    let five = cs.constant(Fr::from(5u64));
+    let seven = cs.constant(Fr::from(7u64));
+    let scaled = cs.mul(x, five);
+    let shifted = cs.add(scaled, seven);
+    let product = cs.mul(five, seven);
+    let out = cs.add(shifted, product);
+    cs.expose(x);
+    cs.expose(out);
+    let original = cs.clone();
+
+    // Five and seven on the two mul rows and the first add row
+    assert_eq!(fold_constants(&mut cs), 4);
+    let row = cs.rows().iter().find(|row| row.wires[0] == x).unwrap();
+    assert_eq!(row.gate.q_l, Fr::from(5u64));
+    assert_eq!(row.wires[1], cs.zero());
+
+    // The constant rows no longer constrain anything used elsewhere
+    assert_eq!(eliminate_dead_rows(&mut cs), 2);
+    assert_equivalent(&original, &cs, &Witness::new().with(x, 2u64));
+}
+
+#[test]
+fn test_chained_additions_merge() {
+    let mut cs = ConstraintSystem::with_wires(5);
+    let inputs: Vec<Variable> = (1..=6u64).map(|i| cs.alloc(Fr::from(i))).collect();
+    let sum = inputs[1..].iter().fold(inputs[0], |acc, &x| cs.add(acc, x));
+    cs.expose(sum);
+    let original = cs.clone();
+
+    // Each merge absorbs one input, so five additions fit on two rows
+    assert_eq!(merge_linear(&mut cs), 3);
+    assert_eq!(cs.rows().len(), original.rows().len() - 3);
+
+    let witness: Witness = inputs.iter().map(|&x| (x, Fr::from(9u64))).collect();
+    assert_equivalent(&original, &cs, &witness);
+}
+
+#[test]
+fn test_dead_rows_are_removed() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(3u64));
+    let y = cs.alloc(Fr::from(4u64));
+    let product = cs.mul(x, y);
+    cs.expose(product);
+
+    // Computed but never used
+    let square = cs.mul(x, x);
+    cs.add(square, y);
+    // Repeated
+    cs.mul(x, y);
+    let original = cs.clone();
+
+    assert_eq!(eliminate_dead_rows(&mut cs), 2);
+    let stats = optimize(&mut cs);
+    assert_eq!(stats.dead_rows_removed, 1);
+    assert_eq!(cs.rows().len(), original.rows().len() - 3);
+    assert_equivalent(&original, &cs, &Witness::new().with(x, 3u64).with(y, 4u64));
+}
+
+#[test]
+fn test_optimize_keeps_custom_gates_and_lookups() {
+    let mut cs = ConstraintSystem::with_wires(4);
+    let range = cs.register_table(LookupTable::range(4));
+    let expected = cs.public_input(Fr::from(0u64));
+    let a = cs.alloc(Fr::from(3u64));
+    let b = cs.alloc(Fr::from(200u64));
+    let one = cs.constant(Fr::one());
+    let a_plus_one = cs.add(a, one);
+    let digest = poseidon::hash_two(&mut cs, a_plus_one, b);
+    assert_equal(&mut cs, digest, expected);
+    range_check_lookup(&mut cs, range, 4, b, 2);
+    let original = cs.clone();
+
+    let stats = optimize(&mut cs);
+    assert!(stats.rows_saved() > 0, "{stats:?}");
+    assert_eq!(stats.rows_after, cs.rows().len());
+    assert_eq!(
+        cs.rows()
+            .iter()
+            .filter(|row| !row.custom.is_empty())
+            .count(),
+        original
+            .rows()
+            .iter()
+            .filter(|row| !row.custom.is_empty())
+            .count()
+    );
+    assert_eq!(
+        cs.rows().iter().filter(|row| row.lookup.is_some()).count(),
+        original
+            .rows()
+            .iter()
+            .filter(|row| row.lookup.is_some())
+            .count()
+    );
+
+    let digest = circuit::poseidon::hash_two(Fr::from(4u64), Fr::from(200u64));
+    let witness = Witness::new()
+        .with(expected, digest)
+        .with(a, 3u64)
+        .with(b, 200u64);
+    assert_equivalent(&original, &cs, &witness);
+}
+
+#[test]
+fn test_optimized_r1cs_import() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    let r1cs = R1cs::load(format!("{fixtures}/multiplier.r1cs")).unwrap();
+    let values = load_wtns(format!("{fixtures}/multiplier.wtns")).unwrap();
+    let circuit = r1cs.compile(3);
+    let mut cs = circuit.cs.clone();
+
+    let stats = optimize(&mut cs);
+    assert_eq!(stats.rows_before, circuit.cs.rows().len());
+    assert!(stats.rows_saved() >= 3, "{stats:?}");
+    assert_equivalent(&circuit.cs, &cs, &circuit.witness(&values));
+}
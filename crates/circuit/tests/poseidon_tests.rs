@@ -0,0 +1,113 @@
+use ark_bls12_381::Fr;
+use ark_crypto_primitives::sponge::poseidon::{
+    PoseidonConfig, PoseidonSponge, find_poseidon_ark_and_mds,
+};
+use ark_crypto_primitives::sponge::{CryptographicSponge, FieldBasedCryptographicSponge};
+use ark_ff::{One, Zero};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::gadgets::poseidon;
+use circuit::poseidon::{
+    ALPHA, FULL_ROUNDS, PARTIAL_ROUNDS, PoseidonParams, RATE, WIDTH, hash_two,
+};
+
+fn arkworks_config() -> PoseidonConfig<Fr> {
+    let (ark, mds) =
+        find_poseidon_ark_and_mds::<Fr>(255, RATE, FULL_ROUNDS as u64, PARTIAL_ROUNDS as u64, 0);
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA, mds, ark, RATE, 1)
+}
+
+#[test]
+fn test_parameters_match_reference_generator() {
+    let params = PoseidonParams::standard();
+    let reference = arkworks_config();
+
+    assert_eq!(params.round_constants.len(), FULL_ROUNDS + PARTIAL_ROUNDS);
+    for (ours, theirs) in params.round_constants.iter().zip(&reference.ark) {
+        assert_eq!(ours.as_slice(), theirs.as_slice());
+    }
+    for (ours, theirs) in params.mds.iter().zip(&reference.mds) {
+        assert_eq!(ours.as_slice(), theirs.as_slice());
+    }
+}
+
+#[test]
+fn test_hash_matches_reference_sponge() {
+    let params = PoseidonParams::standard();
+    let config = arkworks_config();
+
+    for len in [0, 1, 2, 3, 5] {
+        let inputs: Vec<Fr> = (0..len).map(|i| Fr::from(i as u64 + 7)).collect();
+        let mut sponge = PoseidonSponge::new(&config);
+        sponge.absorb(&inputs);
+        let expected = sponge.squeeze_native_field_elements(1)[0];
+
+        assert_eq!(params.hash(&inputs), expected, "{len} inputs");
+    }
+}
+
+#[test]
+fn test_hash_two_separates_inputs() {
+    let a = Fr::from(1u64);
+    let b = Fr::from(2u64);
+
+    assert_eq!(hash_two(a, b), hash_two(a, b));
+    assert_ne!(hash_two(a, b), hash_two(b, a));
+}
+
+#[test]
+fn test_permutation_gadget_matches_native() {
+    let params = PoseidonParams::standard();
+    let input = [Fr::from(1u64), Fr::from(2u64), Fr::from(3u64)];
+    let mut expected = input;
+    params.permute(&mut expected);
+
+    let mut cs = ConstraintSystem::new();
+    let state: [_; WIDTH] = std::array::from_fn(|i| cs.alloc(input[i]));
+    let out = poseidon::permute(&mut cs, params, state);
+
+    assert_eq!(out.map(|var| cs.value(var)), expected);
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_hash_gadget_matches_native() {
+    let params = PoseidonParams::standard();
+    for len in [1, 2, 3] {
+        let inputs: Vec<Fr> = (0..len).map(|i| Fr::from(100 + i as u64)).collect();
+        let mut cs = ConstraintSystem::new();
+        let vars: Vec<_> = inputs.iter().map(|&x| cs.alloc(x)).collect();
+        let out = poseidon::hash(&mut cs, params, &vars);
+
+        assert_eq!(cs.value(out), params.hash(&inputs));
+        assert!(cs.is_satisfied());
+    }
+}
+
+#[test]
+fn test_hash_gadget_on_wide_rows_matches_native() {
+    let mut cs = ConstraintSystem::with_wires(4);
+    let a = cs.alloc(Fr::from(5u64));
+    let b = cs.alloc(Fr::from(6u64));
+    let out = poseidon::hash_two(&mut cs, a, b);
+
+    assert_eq!(cs.value(out), hash_two(Fr::from(5u64), Fr::from(6u64)));
+    assert!(cs.is_satisfied());
+
+    // Zero row, two absorbing additions, the first round's constants, one
+    // S-box row per S-box and one MDS row per state element and round
+    let sboxes = FULL_ROUNDS * WIDTH + PARTIAL_ROUNDS;
+    let mds_rows = (FULL_ROUNDS + PARTIAL_ROUNDS) * WIDTH;
+    let expected_rows = 1 + 2 + WIDTH + sboxes + mds_rows;
+    assert_eq!(cs.rows().len(), expected_rows);
+}
+
+#[test]
+fn test_hash_gadget_rejects_wrong_output() {
+    let mut cs = ConstraintSystem::new();
+    let a = cs.alloc(Fr::zero());
+    let b = cs.alloc(Fr::one());
+    let out = poseidon::hash_two(&mut cs, a, b);
+    cs.set_value(out, cs.value(out) + Fr::one());
+
+    assert!(!cs.is_satisfied());
+}
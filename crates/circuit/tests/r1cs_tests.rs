@@ -0,0 +1,151 @@
+use ark_bls12_381::Fr;
+use ark_ff::One;
+use circuit::r1cs::{R1cs, load_wtns, read_wtns};
+
+const R1CS: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/multiplier.r1cs"
+);
+const WTNS: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/multiplier.wtns"
+);
+/// Compiled and solved by circom over BN254; see `fixtures/README.md`.
+const CIRCOM_R1CS: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/circuit2.r1cs"
+);
+const CIRCOM_WTNS: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/circuit2.wtns"
+);
+
+fn values(values: &[u64]) -> Vec<Fr> {
+    values.iter().map(|&v| Fr::from(v)).collect()
+}
+
+#[test]
+fn test_read_r1cs_and_witness() {
+    let r1cs = R1cs::load(R1CS).unwrap();
+    let witness = load_wtns(WTNS).unwrap();
+
+    assert_eq!(r1cs.num_wires, 5);
+    assert_eq!(r1cs.num_public(), 2);
+    assert_eq!(r1cs.num_private_inputs, 1);
+    assert_eq!(r1cs.constraints.len(), 3);
+    assert_eq!(r1cs.constraints[0].a, vec![(2, Fr::one())]);
+    assert_eq!(r1cs.constraints[2].a, vec![(2, Fr::one()), (0, -Fr::one())]);
+
+    assert_eq!(witness, values(&[1, 21, 3, 5, 15]));
+    assert!(r1cs.is_satisfied(&witness));
+    assert!(!r1cs.is_satisfied(&values(&[1, 21, 3, 5, 16])));
+}
+
+#[test]
+fn test_compiled_circuit_is_satisfied() {
+    let r1cs = R1cs::<Fr>::load(R1CS).unwrap();
+    let witness = load_wtns(WTNS).unwrap();
+
+    for num_wires in [3, 5] {
+        let circuit = r1cs.compile(num_wires);
+        let cs = circuit.synthesize(&witness).unwrap();
+
+        assert!(cs.is_satisfied(), "{num_wires} wires");
+        assert_eq!(cs.public_inputs(), values(&[21, 3]));
+    }
+}
+
+#[test]
+fn test_compiled_circuit_rejects_bad_witness() {
+    let r1cs = R1cs::load(R1CS).unwrap();
+    let circuit = r1cs.compile(3);
+
+    // a·b = c holds, but out is wrong
+    let cs = circuit.synthesize(&values(&[1, 22, 3, 5, 15])).unwrap();
+    assert!(!cs.is_satisfied());
+
+    // The linear constraint holds, but a·b ≠ c
+    let cs = circuit.synthesize(&values(&[1, 22, 3, 5, 16])).unwrap();
+    assert!(!cs.is_satisfied());
+
+    assert!(circuit.synthesize(&values(&[1, 21, 3])).is_err());
+}
+
+#[test]
+fn test_rejects_foreign_files() {
+    let mut bytes = std::fs::read(R1CS).unwrap();
+    bytes[0] = b'x';
+    assert!(R1cs::<Fr>::read(bytes.as_slice()).is_err());
+
+    // Flip a byte of the prime in the header
+    let mut bytes = std::fs::read(R1CS).unwrap();
+    bytes[4 + 4 + 4 + 4 + 8 + 4] ^= 1;
+    assert!(R1cs::<Fr>::read(bytes.as_slice()).is_err());
+
+    let bytes = std::fs::read(WTNS).unwrap();
+    assert!(read_wtns::<Fr>(&bytes[..bytes.len() - 1]).is_err());
+    assert!(R1cs::<Fr>::read(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn test_rejects_counts_past_the_sections() {
+    // Header offsets: 12 bytes of magic, version and section count, then
+    // the section kind and size, the field size, the prime and num_wires
+    let field_size = 4 + 4 + 4 + 4 + 8;
+    let num_wires = field_size + 4 + 32;
+
+    let mut bytes = std::fs::read(R1CS).unwrap();
+    bytes[field_size..field_size + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(R1cs::<Fr>::read(bytes.as_slice()).is_err());
+
+    // More wires than the wire map lists
+    let mut bytes = std::fs::read(R1CS).unwrap();
+    bytes[num_wires..num_wires + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(R1cs::<Fr>::read(bytes.as_slice()).is_err());
+
+    // More witness values than the witness section holds
+    let mut bytes = std::fs::read(WTNS).unwrap();
+    bytes[field_size..field_size + 4].copy_from_slice(&64u32.to_le_bytes());
+    assert!(read_wtns::<Fr>(bytes.as_slice()).is_err());
+    let mut bytes = std::fs::read(WTNS).unwrap();
+    bytes[num_wires] += 1;
+    assert!(read_wtns::<Fr>(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn test_circom_files_over_bn254() {
+    type Bn254Fr = ark_bn254::Fr;
+    let r1cs = R1cs::<Bn254Fr>::load(CIRCOM_R1CS).unwrap();
+    let witness = load_wtns::<Bn254Fr>(CIRCOM_WTNS).unwrap();
+
+    // c = a·b as the public output, a and b private
+    assert_eq!(r1cs.num_wires, 132);
+    assert_eq!(
+        (
+            r1cs.num_public_outputs,
+            r1cs.num_public_inputs,
+            r1cs.num_private_inputs
+        ),
+        (1, 0, 2)
+    );
+    assert_eq!(r1cs.constraints.len(), 131);
+    let signals: Vec<Bn254Fr> = [1u64, 33, 3, 11].map(Bn254Fr::from).to_vec();
+    assert_eq!(witness[..4], signals);
+    assert!(r1cs.is_satisfied(&witness));
+
+    let mut wrong = witness.clone();
+    wrong[1] += Bn254Fr::one();
+    assert!(!r1cs.is_satisfied(&wrong));
+
+    for num_wires in [3, 5] {
+        let circuit = r1cs.compile(num_wires);
+        let cs = circuit.synthesize(&witness).unwrap();
+        assert!(cs.is_satisfied(), "{num_wires} wires");
+        assert_eq!(cs.public_inputs(), vec![Bn254Fr::from(33u64)]);
+        assert!(!circuit.synthesize(&wrong).unwrap().is_satisfied());
+    }
+
+    // The files name BN254's prime, so they are not read as BLS12-381
+    assert!(R1cs::<Fr>::load(CIRCOM_R1CS).is_err());
+    assert!(load_wtns::<Fr>(CIRCOM_WTNS).is_err());
+}
@@ -0,0 +1,84 @@
+use ark_bls12_381::Fr;
+use ark_ff::One;
+use circuit::constraint_system::ConstraintSystem;
+use circuit::gadgets::sha256::{IV, Word, alloc_message, compress, digest_bytes, sha256};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sha2::digest::generic_array::GenericArray;
+use sha2::{Digest, Sha256};
+
+fn random_bytes(rng: &mut StdRng, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.r#gen()).collect()
+}
+
+#[test]
+fn test_compression_matches_sha2() {
+    let mut rng = StdRng::seed_from_u64(32);
+    let block = random_bytes(&mut rng, 64);
+
+    let mut expected = IV;
+    sha2::compress256(&mut expected, &[*GenericArray::from_slice(&block)]);
+
+    let mut cs = ConstraintSystem::new();
+    let state = IV.map(|value| Word::alloc(&mut cs, value));
+    let words: [Word; 16] = std::array::from_fn(|k| {
+        let value = u32::from_be_bytes(block[4 * k..4 * k + 4].try_into().unwrap());
+        Word::alloc(&mut cs, value)
+    });
+    let out = compress(&mut cs, &state, &words);
+
+    assert_eq!(out.map(|word| word.value(&cs)), expected);
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_digest_matches_sha2_on_random_messages() {
+    let mut rng = StdRng::seed_from_u64(256);
+
+    // Empty, short, just below and at the one-block padding limit, two blocks
+    for len in [0, 3, 55, 56, 64, 100] {
+        let message = random_bytes(&mut rng, len);
+
+        let mut cs = ConstraintSystem::new();
+        let bits = alloc_message(&mut cs, &message);
+        let digest = sha256(&mut cs, &bits);
+
+        assert_eq!(
+            digest_bytes(&cs, &digest),
+            Sha256::digest(&message).to_vec(),
+            "{len} bytes"
+        );
+        assert!(cs.is_satisfied(), "{len} bytes");
+    }
+}
+
+#[test]
+fn test_word_rotations_and_shifts() {
+    let mut cs = ConstraintSystem::new();
+    let word = Word::alloc(&mut cs, 0x8000_0001);
+
+    assert_eq!(word.rotr(1).value(&cs), 0xC000_0000);
+    assert_eq!(word.shr(cs.zero(), 1).value(&cs), 0x4000_0000);
+    assert_eq!(word.rotr(0).value(&cs), 0x8000_0001);
+}
+
+#[test]
+fn test_tampered_digest_is_rejected() {
+    let mut cs = ConstraintSystem::new();
+    let bits = alloc_message(&mut cs, b"abc");
+    let digest = sha256(&mut cs, &bits);
+    assert!(cs.is_satisfied());
+
+    let bit = digest[0].0[0];
+    cs.set_value(bit, Fr::one() - cs.value(bit));
+    assert!(!cs.is_satisfied());
+}
+
+#[test]
+fn test_wrong_message_gives_different_digest() {
+    let mut cs = ConstraintSystem::new();
+    let bits = alloc_message(&mut cs, b"abd");
+    let digest = sha256(&mut cs, &bits);
+
+    assert_ne!(digest_bytes(&cs, &digest), Sha256::digest(b"abc").to_vec());
+}
@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use ark_bls12_381::Fr;
+use circuit::constraint_system::ConstraintSystem;
+use circuit::custom_gates::Pow5Gate;
+use circuit::gate::Gate;
+use circuit::lookup::LookupTable;
+use circuit::square_circuit::SquareCircuit;
+use circuit::stats::{CircuitStats, to_dot};
+
+#[test]
+fn test_square_circuit_stats() {
+    let stats = SquareCircuit::new().stats();
+
+    assert_eq!(stats.num_rows, 3);
+    assert_eq!(stats.num_variables, 3);
+    assert_eq!(stats.num_public_inputs, 1);
+    assert_eq!(stats.domain_size, 4);
+    assert_eq!(stats.padding_rows(), 1);
+    assert_eq!(stats.padding_ratio(), 0.25);
+    // Zero, the output and x each sit on several wires
+    assert_eq!(stats.copy_cycles, 3);
+    assert_eq!(stats.longest_cycle, 8);
+    assert_eq!(
+        stats.gates,
+        BTreeMap::from([
+            ("constant".to_string(), 1),
+            ("multiplication".to_string(), 1),
+            ("public input".to_string(), 1),
+        ])
+    );
+
+    let report = stats.to_string();
+    assert!(report.contains("padding rows   1 (25.0%)"), "{report}");
+    assert!(report.contains("  multiplication  1"), "{report}");
+}
+
+#[test]
+fn test_custom_gates_and_lookups_are_counted() {
+    let mut cs = ConstraintSystem::new();
+    let pow5 = cs.register_gate(Pow5Gate);
+    let range = cs.register_table(LookupTable::range(4));
+    let x = cs.alloc(Fr::from(2u64));
+    let y = cs.alloc(Fr::from(32u64));
+    cs.custom_gate(pow5, vec![Fr::from(1u64)], &[x, cs.zero(), y]);
+    let sum = cs.add(x, y);
+    cs.lookup(range, &[x]);
+    cs.add_gate(Gate::linear(&[Fr::from(2u64)], Fr::from(1u64)), &[sum]);
+
+    let stats = CircuitStats::new(&cs);
+    assert_eq!(stats.gates["pow5"], 1);
+    assert_eq!(stats.gates["lookup range_4"], 1);
+    assert_eq!(stats.gates["addition"], 1);
+    assert_eq!(stats.gates["constant"], 2);
+    // The custom gate and lookup rows carry no standard gate
+    assert!(!stats.gates.contains_key("empty"));
+    assert_eq!(stats.domain_size, 16);
+}
+
+#[test]
+fn test_dot_export() {
+    let mut cs = ConstraintSystem::new();
+    let pow5 = cs.register_gate(Pow5Gate);
+    let x = cs.public_input(Fr::from(2u64));
+    let y = cs.alloc(Fr::from(32u64));
+    cs.custom_gate(pow5, vec![Fr::from(1u64)], &[x, cs.zero(), y]);
+    cs.mul(y, y);
+
+    let dot = to_dot(&cs);
+    assert!(dot.starts_with("graph circuit {"));
+    assert!(
+        dot.contains("r1 [label=\"1: public input|{v1|v0|v0}\"];"),
+        "{dot}"
+    );
+    assert!(dot.contains("r2 [label=\"2: pow5|{v1|v0|v2}\"];"), "{dot}");
+    assert!(dot.contains("r1 -- r2 [label=\"v1\"];"), "{dot}");
+    assert!(dot.contains("r2 -- r3 [label=\"v2\"];"), "{dot}");
+    assert!(
+        dot.contains("r2 -- r3 [style=dashed, label=\"next\"];"),
+        "{dot}"
+    );
+    assert!(!dot.contains("label=\"v0\""), "{dot}");
+    assert!(dot.ends_with("}\n"));
+}
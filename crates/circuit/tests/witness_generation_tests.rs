@@ -0,0 +1,137 @@
+use ark_bls12_381::Fr;
+use ark_ff::{Field, One, Zero};
+use circuit::constraint_system::{ConstraintSystem, Variable};
+use circuit::eddsa::{SecretKey, scalar_to_base};
+use circuit::gadgets::arithmetic::{inverse, is_zero};
+use circuit::gadgets::comparison::less_than;
+use circuit::gadgets::eddsa::{SignatureVar, jubjub, point, verify};
+use circuit::gadgets::poseidon;
+use circuit::poseidon::hash_two;
+use circuit::witness::{Witness, WitnessError};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn inputs(pairs: &[(Variable, Fr)]) -> Witness {
+    pairs.iter().copied().collect()
+}
+
+#[test]
+fn test_generated_witness_matches_built_values() {
+    let mut cs = ConstraintSystem::new();
+    let a = cs.alloc(Fr::from(3u64));
+    let b = cs.public_input(Fr::from(4u64));
+    let lt = less_than(&mut cs, a, b, 8);
+    let product = cs.mul(a, lt);
+    let out = cs.add(product, b);
+
+    let mut regenerated = cs.clone();
+    regenerated
+        .set_witness(&inputs(&[(a, Fr::from(3u64)), (b, Fr::from(4u64))]))
+        .unwrap();
+
+    assert_eq!(cs.inputs(), vec![a, b]);
+    assert_eq!(regenerated.value(out), Fr::from(7u64));
+    for row in 0..cs.rows().len() {
+        assert_eq!(regenerated.wire_values(row), cs.wire_values(row));
+    }
+}
+
+#[test]
+fn test_witness_follows_new_inputs() {
+    let mut cs = ConstraintSystem::new();
+    let a = cs.alloc(Fr::zero());
+    let b = cs.alloc(Fr::zero());
+    let out = poseidon::hash_two(&mut cs, a, b);
+
+    let (x, y) = (Fr::from(11u64), Fr::from(12u64));
+    cs.set_witness(&inputs(&[(a, x), (b, y)])).unwrap();
+
+    assert_eq!(cs.value(out), hash_two(x, y));
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_signature_witness_from_inputs_alone() {
+    let mut rng = StdRng::seed_from_u64(34);
+    let key = SecretKey::generate(&mut rng);
+    let message = Fr::from(42u64);
+    let signature = key.sign(message, &mut rng);
+
+    // Lay the circuit out with one signature, then fill in another
+    let mut cs = ConstraintSystem::new();
+    let curve = jubjub(&mut cs);
+    let other = SecretKey::generate(&mut rng);
+    let public_key = curve.alloc_point(&mut cs, point(&other.public_key().0));
+    let m = cs.alloc(Fr::one());
+    let sig = SignatureVar::alloc(&mut cs, &curve, &other.sign(Fr::one(), &mut rng));
+    verify(&mut cs, &curve, public_key, m, &sig);
+
+    let (pk_x, pk_y) = point(&key.public_key().0);
+    let (r_x, r_y) = point(&signature.r);
+    let s = scalar_to_base(signature.s);
+    let values = inputs(&[
+        (public_key.x, pk_x),
+        (public_key.y, pk_y),
+        (m, message),
+        (sig.r.x, r_x),
+        (sig.r.y, r_y),
+        (sig.s, s),
+    ]);
+    cs.set_witness(&values).unwrap();
+
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_missing_input_is_reported() {
+    let mut cs = ConstraintSystem::new();
+    let a = cs.alloc(Fr::one());
+    let b = cs.alloc(Fr::one());
+    cs.mul(a, b);
+
+    assert_eq!(
+        cs.generate_witness(&inputs(&[(a, Fr::one())])),
+        Err(WitnessError::MissingInput(b))
+    );
+}
+
+#[test]
+fn test_division_by_zero_is_reported() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(2u64));
+    let inv = inverse(&mut cs, x);
+    assert_eq!(cs.value(inv), Fr::from(2u64).inverse().unwrap());
+
+    assert_eq!(
+        cs.generate_witness(&inputs(&[(x, Fr::zero())])),
+        Err(WitnessError::DivisionByZero(inv))
+    );
+}
+
+#[test]
+fn test_is_zero_does_not_divide() {
+    let mut cs = ConstraintSystem::new();
+    let x = cs.alloc(Fr::from(5u64));
+    let out = is_zero(&mut cs, x);
+
+    cs.set_witness(&inputs(&[(x, Fr::zero())])).unwrap();
+
+    assert_eq!(cs.value(out), Fr::one());
+    assert!(cs.is_satisfied());
+}
+
+#[test]
+fn test_reading_unassigned_variable_is_reported() {
+    let mut other = ConstraintSystem::new();
+    let unassigned = (0..3).map(|_| other.alloc(Fr::one())).last().unwrap();
+
+    // The assignment reads a variable past the end of `cs`
+    let mut cs: ConstraintSystem = ConstraintSystem::new();
+    let x = cs.assign(move |w| w.get(unassigned));
+    assert_eq!(cs.value(x), Fr::zero());
+
+    assert_eq!(
+        cs.generate_witness(&Witness::new()),
+        Err(WitnessError::MissingAssignment(unassigned))
+    );
+}
@@ -1,11 +1,17 @@
 use ark_bls12_381::Fr;
+use ark_ff::Field;
+use circuit::constraint_system::Variable;
 use circuit::square_circuit::SquareCircuit;
-use circuit::witness::Witness;
+use circuit::witness::{
+    Witness, from_be_bytes, from_bigint, from_le_bytes, from_signed_be_bytes, from_signed_le_bytes,
+    parse_integer,
+};
+use num_bigint::{BigInt, BigUint};
 
 #[test]
 fn test_wire_values() {
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(5);
+    let witness = circuit.witness(5u64);
 
     let wires = circuit.wire_values(&witness);
 
@@ -13,3 +19,109 @@ fn test_wire_values() {
     assert_eq!(wires.b, Fr::from(5u64));  // right input = x
     assert_eq!(wires.c, Fr::from(25u64)); // output = 25
 }
+
+#[test]
+fn test_witness_map_converts_values() {
+    let (a, b, c) = (Variable::new(1), Variable::new(2), Variable::new(3));
+    let witness = Witness::new()
+        .with(a, 7u64)
+        .with(b, -5i64)
+        .with(c, BigUint::from(1u64) << 300);
+
+    assert_eq!(witness.len(), 3);
+    assert_eq!(witness.get(a), Some(Fr::from(7u64)));
+    assert_eq!(witness.get(b), Some(-Fr::from(5u64)));
+    assert_eq!(witness.get(c), Some(Fr::from(2u64).pow([300])));
+    assert_eq!(witness.get(Variable::new(4)), None);
+}
+
+#[test]
+fn test_signed_values_of_any_width() {
+    let minus_five = -Fr::from(5u64);
+
+    assert_eq!(from_bigint(&BigInt::from(-5)), minus_five);
+    assert_eq!(from_signed_be_bytes(&[0xff, 0xfb]), minus_five);
+    assert_eq!(from_signed_le_bytes(&[0xfb, 0xff, 0xff]), minus_five);
+    // Wider than the field
+    assert_eq!(from_signed_be_bytes(&[0xff; 40]), -Fr::from(1u64));
+    assert_eq!(from_signed_be_bytes(&[0x7f]), Fr::from(127u64));
+}
+
+#[test]
+fn test_unsigned_bytes() {
+    assert_eq!(from_be_bytes(&[0x01, 0x00]), Fr::from(256u64));
+    assert_eq!(from_le_bytes(&[0x01, 0x00]), Fr::from(1u64));
+}
+
+#[test]
+fn test_witness_json() {
+    let witness = Witness::from_json(r#"{"2": "-5", "3": "0x1f", "4": 7, "5": "-0x10"}"#).unwrap();
+
+    assert_eq!(witness.get(Variable::new(2)), Some(-Fr::from(5u64)));
+    assert_eq!(witness.get(Variable::new(3)), Some(Fr::from(31u64)));
+    assert_eq!(witness.get(Variable::new(4)), Some(Fr::from(7u64)));
+    assert_eq!(witness.get(Variable::new(5)), Some(-Fr::from(16u64)));
+    assert_eq!(Witness::from_json(&witness.to_json()).unwrap(), witness);
+
+    assert!(Witness::from_json(r#"{"x": 5}"#).is_err());
+    assert!(Witness::from_json(r#"{"2": "five"}"#).is_err());
+    assert!(Witness::from_json(r#"{"2": 1.5}"#).is_err());
+    for text in ["--5", "-+5", "+5", "0x-5", "0x+5", "-", "0x", ""] {
+        assert!(parse_integer(text).is_none(), "{text:?}");
+    }
+}
+
+#[test]
+fn test_witness_files_round_trip() {
+    let witness = Witness::new()
+        .with(Variable::new(1), -5i64)
+        .with(Variable::new(9), u64::MAX);
+    let dir = std::env::temp_dir().join(format!("witness-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    for name in ["witness.json", "witness.bin"] {
+        let path = dir.join(name);
+        witness.save(&path).unwrap();
+        assert_eq!(Witness::load(&path).unwrap(), witness, "{name}");
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_witness_read_rejects_corrupt_lengths() {
+    let witness = Witness::new()
+        .with(Variable::new(1), 3u64)
+        .with(Variable::new(2), 4u64);
+    let mut bytes = Vec::new();
+    witness.write(&mut bytes).unwrap();
+    assert_eq!(Witness::read(bytes.as_slice()).unwrap(), witness);
+
+    // A huge index count runs out of input instead of allocating
+    let mut corrupt = bytes.clone();
+    corrupt[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(Witness::read(corrupt.as_slice()).is_err());
+
+    // One index fewer than values
+    let mut corrupt = bytes.clone();
+    corrupt[..8].copy_from_slice(&1u64.to_le_bytes());
+    corrupt.drain(16..24);
+    assert!(Witness::read(corrupt.as_slice()).is_err());
+
+    // Variable 1 twice
+    let mut corrupt = bytes.clone();
+    corrupt[16..24].copy_from_slice(&1u64.to_le_bytes());
+    assert!(Witness::read(corrupt.as_slice()).is_err());
+
+    let mut trailing = bytes;
+    trailing.push(0);
+    assert!(Witness::read(trailing.as_slice()).is_err());
+}
+
+#[test]
+fn test_square_circuit_from_json_witness() {
+    let circuit = SquareCircuit::new();
+    let json = circuit.witness(-5i64).to_json();
+
+    let cs = circuit.synthesize(&Witness::from_json(&json).unwrap());
+    assert!(cs.is_satisfied());
+}
@@ -0,0 +1,26 @@
+//! Commitment time, a single MSM, for polynomials of 2¹⁶ to 2²⁰
+//! coefficients. Run with and without `--features parallel`.
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_poly::DenseUVPolynomial;
+use ark_poly::univariate::DensePolynomial;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use kzg::commit;
+use setup::TrustedSetup;
+
+fn bench_commit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("commit");
+    group.sample_size(10);
+    let setup = TrustedSetup::<Bls12_381>::generate((1 << 20) - 1);
+    let mut rng = ark_std::test_rng();
+    for log_len in 16..=20 {
+        let poly = DensePolynomial::<Fr>::rand((1 << log_len) - 1, &mut rng);
+        group.bench_with_input(BenchmarkId::from_parameter(log_len), &poly, |b, poly| {
+            b.iter(|| commit(&setup, poly))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_commit);
+criterion_main!(benches);
@@ -0,0 +1,328 @@
+//! Inner pairing product arguments, as SnarkPack uses them.
+//!
+//! A vector of G1 points A is committed to in the target group under the
+//! two keys of an [`IppSetup`], T = Π e(Aᵢ, h^{aⁱ}) and U = Π e(Aᵢ, h^{bⁱ}).
+//! The multi-exponentiation argument [`prove_mipp`] shows that the
+//! committed points have given inner products ⟨A, b⟩ with public scalar
+//! vectors b. Each of its log₂ n rounds halves the vectors (GIPA): with a
+//! challenge x, A becomes A_L + x·A_R while b and the keys become
+//! b_L + x⁻¹·b_R, and the commitment and products absorb the cross terms
+//! the prover sends. The key the verifier would have to fold in n steps is
+//! h^{f(a)} for f(X) = Π (1 + xⱼ⁻¹·X^{n/2^{j+1}}), so the prover sends it
+//! with a KZG opening of f in G2 instead, and the verifier only evaluates f
+//! at one point.
+//!
+//! The verifier's pairings are constant: two for the final commitment and
+//! two per key opening. Its other work is O(log n) target group
+//! operations, plus O(n) field operations to fold unstructured scalars.
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField, Zero};
+use ark_poly::DenseUVPolynomial;
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::CanonicalSerialize;
+use setup::IppSetup;
+use sha2::{Digest, Sha256};
+
+use crate::divide_by_linear;
+
+/// Fiat-Shamir transcript of an inner pairing product argument.
+///
+/// Messages are appended in arkworks' compressed encoding, and squeezing
+/// hashes the buffer with SHA-256, as `proof::transcript::Sha256Transcript`
+/// does; this one also takes target group and G2 elements.
+#[derive(Clone, Debug)]
+pub struct Challenger {
+    buffer: Vec<u8>,
+}
+
+impl Challenger {
+    pub fn new(label: &[u8]) -> Self {
+        Challenger {
+            buffer: label.to_vec(),
+        }
+    }
+
+    pub fn append(&mut self, label: &[u8], message: &impl CanonicalSerialize) {
+        self.buffer.extend_from_slice(label);
+        message
+            .serialize_compressed(&mut self.buffer)
+            .expect("serializing into a Vec cannot fail");
+    }
+
+    /// A non-zero challenge, so that it can be inverted.
+    pub fn challenge<F: PrimeField>(&mut self, label: &[u8]) -> F {
+        self.buffer.extend_from_slice(label);
+        loop {
+            let digest = Sha256::digest(&self.buffer);
+            self.buffer = digest.to_vec();
+            let challenge = F::from_be_bytes_mod_order(&digest);
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+}
+
+/// Commitment to a vector of G1 points under both keys.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize)]
+pub struct PairCommitment<E: Pairing = Bls12_381> {
+    pub t: PairingOutput<E>,
+    pub u: PairingOutput<E>,
+}
+
+impl<E: Pairing> PairCommitment<E> {
+    fn new(points: &[E::G1Affine], v: &[E::G2Affine], w: &[E::G2Affine]) -> Self {
+        PairCommitment {
+            t: E::multi_pairing(points, &v[..points.len()]),
+            u: E::multi_pairing(points, &w[..points.len()]),
+        }
+    }
+
+    /// C + x·C_L + x⁻¹·C_R
+    fn fold(&self, left: &Self, right: &Self, x: E::ScalarField, x_inv: E::ScalarField) -> Self {
+        PairCommitment {
+            t: self.t + left.t * x + right.t * x_inv,
+            u: self.u + left.u * x + right.u * x_inv,
+        }
+    }
+}
+
+/// Commits to up to [`IppSetup::max_len`] points.
+pub fn commit<E: Pairing>(setup: &IppSetup<E>, points: &[E::G1Affine]) -> PairCommitment<E> {
+    assert!(
+        points.len() <= setup.max_len(),
+        "Committing to {} points with keys for {}",
+        points.len(),
+        setup.max_len()
+    );
+    PairCommitment::new(points, &setup.g2_alpha_powers, &setup.g2_beta_powers)
+}
+
+/// Cross terms of one halving round.
+#[derive(Clone, Debug, CanonicalSerialize)]
+pub struct MippRound<E: Pairing = Bls12_381> {
+    /// A_R committed under the left halves of the keys
+    pub comm_l: PairCommitment<E>,
+    /// A_L committed under the right halves of the keys
+    pub comm_r: PairCommitment<E>,
+    /// ⟨A_R, b_L⟩ for each scalar vector
+    pub products_l: Vec<E::G1Affine>,
+    /// ⟨A_L, b_R⟩ for each scalar vector
+    pub products_r: Vec<E::G1Affine>,
+}
+
+#[derive(Clone, Debug, CanonicalSerialize)]
+pub struct MippProof<E: Pairing = Bls12_381> {
+    pub rounds: Vec<MippRound<E>>,
+    /// The point A folds down to
+    pub point: E::G1Affine,
+    /// The folded keys, h^{f(a)} and h^{f(b)}
+    pub alpha_key: E::G2Affine,
+    pub beta_key: E::G2Affine,
+    /// KZG openings of f at the final challenge, under either key
+    pub alpha_opening: E::G2Affine,
+    pub beta_opening: E::G2Affine,
+}
+
+/// Proves the inner products of `points` with each vector of `scalars`
+/// and returns them with the proof.
+///
+/// The commitment to `points` should already be in `challenger`, along
+/// with whatever fixed the scalars. Vectors are padded with zeros to the
+/// next power of two, which must fit the setup.
+pub fn prove_mipp<E: Pairing>(
+    setup: &IppSetup<E>,
+    challenger: &mut Challenger,
+    points: &[E::G1Affine],
+    scalars: &[Vec<E::ScalarField>],
+) -> (Vec<E::G1Affine>, MippProof<E>) {
+    let n = points.len().next_power_of_two();
+    assert!(
+        n <= setup.max_len(),
+        "Proving over {n} points with keys for {}",
+        setup.max_len()
+    );
+    let pad = |b: &Vec<E::ScalarField>| {
+        let mut b = b.clone();
+        b.resize(n, E::ScalarField::zero());
+        b
+    };
+    let mut a: Vec<E::G1Affine> = points.to_vec();
+    a.resize(n, E::G1Affine::zero());
+    let mut bs: Vec<Vec<E::ScalarField>> = scalars.iter().map(pad).collect();
+    let mut v = setup.g2_alpha_powers[..n].to_vec();
+    let mut w = setup.g2_beta_powers[..n].to_vec();
+
+    let products: Vec<E::G1Affine> = bs.iter().map(|b| msm::<E>(&a, b)).collect();
+    challenger.append(b"products", &products);
+
+    let mut rounds = Vec::new();
+    let mut challenges = Vec::new();
+    while a.len() > 1 {
+        let m = a.len() / 2;
+        let (a_l, a_r) = a.split_at(m);
+        let (v_l, v_r) = v.split_at(m);
+        let (w_l, w_r) = w.split_at(m);
+        let round = MippRound {
+            comm_l: PairCommitment::new(a_r, v_l, w_l),
+            comm_r: PairCommitment::new(a_l, v_r, w_r),
+            products_l: bs.iter().map(|b| msm::<E>(a_r, &b[..m])).collect(),
+            products_r: bs.iter().map(|b| msm::<E>(a_l, &b[m..])).collect(),
+        };
+        challenger.append(b"round", &round);
+        let x: E::ScalarField = challenger.challenge(b"x");
+        let x_inv = x.inverse().expect("challenges are non-zero");
+
+        a = fold_points::<E::G1>(&a, x);
+        v = fold_points::<E::G2>(&v, x_inv);
+        w = fold_points::<E::G2>(&w, x_inv);
+        for b in &mut bs {
+            *b = (0..m).map(|i| b[i] + x_inv * b[m + i]).collect();
+        }
+        rounds.push(round);
+        challenges.push(x);
+    }
+
+    let (point, alpha_key, beta_key) = (a[0], v[0], w[0]);
+    challenger.append(b"final", &(point, alpha_key, beta_key));
+    let z: E::ScalarField = challenger.challenge(b"z");
+
+    let f = DensePolynomial::from_coefficients_vec(key_coefficients(&challenges));
+    let quotient = divide_by_linear(&f, z);
+    let open = |powers: &[E::G2Affine]| {
+        E::G2::msm_unchecked(&powers[..quotient.coeffs.len()], &quotient.coeffs).into_affine()
+    };
+
+    let proof = MippProof {
+        rounds,
+        point,
+        alpha_key,
+        beta_key,
+        alpha_opening: open(&setup.g2_alpha_powers),
+        beta_opening: open(&setup.g2_beta_powers),
+    };
+    (products, proof)
+}
+
+/// Checks that the points committed to in `comm` have inner products
+/// `products` with `scalars`, replaying the prover's `challenger`.
+///
+/// The longest vector of `scalars` fixes the number of committed points,
+/// and with it the number of rounds the proof must have.
+pub fn verify_mipp<E: Pairing>(
+    setup: &IppSetup<E>,
+    challenger: &mut Challenger,
+    comm: &PairCommitment<E>,
+    scalars: &[Vec<E::ScalarField>],
+    products: &[E::G1Affine],
+    proof: &MippProof<E>,
+) -> bool {
+    // The round count comes from the proof, so bound it before shifting
+    let max_rounds = setup.max_len().checked_ilog2().unwrap_or(0) as usize;
+    let len = scalars.iter().map(Vec::len).max().unwrap_or(1);
+    let n = len.next_power_of_two();
+    if proof.rounds.len() > max_rounds
+        || proof.rounds.len() != n.trailing_zeros() as usize
+        || n > setup.max_len()
+        || products.len() != scalars.len()
+        || proof.rounds.iter().any(|round| {
+            round.products_l.len() != scalars.len() || round.products_r.len() != scalars.len()
+        })
+    {
+        return false;
+    }
+
+    challenger.append(b"products", &products.to_vec());
+    let mut comm = comm.clone();
+    let mut products: Vec<E::G1> = products.iter().map(|p| p.into_group()).collect();
+    let mut challenges = Vec::new();
+    for round in &proof.rounds {
+        challenger.append(b"round", round);
+        let x: E::ScalarField = challenger.challenge(b"x");
+        let x_inv = x.inverse().expect("challenges are non-zero");
+
+        comm = comm.fold(&round.comm_l, &round.comm_r, x, x_inv);
+        for ((product, l), r) in products
+            .iter_mut()
+            .zip(&round.products_l)
+            .zip(&round.products_r)
+        {
+            *product += *l * x + *r * x_inv;
+        }
+        challenges.push(x);
+    }
+
+    challenger.append(b"final", &(proof.point, proof.alpha_key, proof.beta_key));
+    let z: E::ScalarField = challenger.challenge(b"z");
+
+    // The folded scalars are ⟨c, b⟩ for the coefficients c of f
+    let coefficients = key_coefficients(&challenges);
+    for (product, b) in products.iter().zip(scalars) {
+        let folded: E::ScalarField = coefficients.iter().zip(b).map(|(c, b)| *c * b).sum();
+        if *product != proof.point * folded {
+            return false;
+        }
+    }
+
+    if comm.t != E::pairing(proof.point, proof.alpha_key)
+        || comm.u != E::pairing(proof.point, proof.beta_key)
+    {
+        return false;
+    }
+
+    // e(g, key - f(z)·h) = e(g^s - z·g, opening) for s = a, b
+    let f_z = evaluate_key_polynomial(&challenges, n, z);
+    let g = setup.g1_gen.into_group();
+    let h_f_z = setup.g2_gen * f_z;
+    [
+        (setup.g1_alpha, proof.alpha_key, proof.alpha_opening),
+        (setup.g1_beta, proof.beta_key, proof.beta_opening),
+    ]
+    .into_iter()
+    .all(|(g_secret, key, opening)| {
+        E::multi_pairing(
+            [g, g * z - g_secret],
+            [(key.into_group() - h_f_z).into_affine(), opening],
+        )
+        .is_zero()
+    })
+}
+
+/// Coefficients of f(X) = Π (1 + xⱼ⁻¹·X^{n/2^{j+1}}), i.e. the weight of
+/// each original entry in the folded vector.
+fn key_coefficients<F: Field>(challenges: &[F]) -> Vec<F> {
+    let mut coefficients = vec![F::one()];
+    for x in challenges.iter().rev() {
+        let x_inv = x.inverse().expect("challenges are non-zero");
+        let shifted: Vec<F> = coefficients.iter().map(|c| *c * x_inv).collect();
+        coefficients.extend(shifted);
+    }
+    coefficients
+}
+
+fn evaluate_key_polynomial<F: Field>(challenges: &[F], n: usize, z: F) -> F {
+    challenges
+        .iter()
+        .enumerate()
+        .map(|(j, x)| {
+            let exponent = (n >> (j + 1)) as u64;
+            F::one() + x.inverse().expect("challenges are non-zero") * z.pow([exponent])
+        })
+        .product()
+}
+
+/// Left half plus `scale` times the right half.
+fn fold_points<G: CurveGroup>(points: &[G::Affine], scale: G::ScalarField) -> Vec<G::Affine> {
+    let m = points.len() / 2;
+    let folded: Vec<G> = (0..m)
+        .map(|i| points[i].into_group() + points[m + i] * scale)
+        .collect();
+    G::normalize_batch(&folded)
+}
+
+fn msm<E: Pairing>(points: &[E::G1Affine], scalars: &[E::ScalarField]) -> E::G1Affine {
+    E::G1::msm_unchecked(points, scalars).into_affine()
+}
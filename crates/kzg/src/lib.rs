@@ -1,19 +1,23 @@
+pub mod ipp;
 #[cfg(test)]
 mod tests;
 
-use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective};
+use ark_bls12_381::Bls12_381;
 use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM, pairing::Pairing};
-use ark_ff::Field;
+use ark_ff::{One, PrimeField, Zero};
 use ark_poly::{DenseUVPolynomial, Polynomial, univariate::DensePolynomial};
 use setup::TrustedSetup;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Commitment(pub G1Affine);
+pub struct Commitment<E: Pairing = Bls12_381>(pub E::G1Affine);
 
 #[derive(Clone, Debug)]
-pub struct OpeningProof(pub G1Affine);
+pub struct OpeningProof<E: Pairing = Bls12_381>(pub E::G1Affine);
 
-pub fn commit(setup: &TrustedSetup, poly: &DensePolynomial<Fr>) -> Commitment {
+pub fn commit<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    poly: &DensePolynomial<E::ScalarField>,
+) -> Commitment<E> {
     let coeffs = poly.coeffs();
 
     assert!(
@@ -23,12 +27,17 @@ pub fn commit(setup: &TrustedSetup, poly: &DensePolynomial<Fr>) -> Commitment {
         setup.max_degree()
     );
 
-    let result = G1Projective::msm_unchecked(&setup.g1_powers[..coeffs.len()], coeffs);
+    let result = E::G1::msm_unchecked(&setup.g1_powers[..coeffs.len()], coeffs);
 
     Commitment(result.into_affine())
 }
 
-pub fn open(setup: &TrustedSetup, poly: &DensePolynomial<Fr>, z: Fr, y: Fr) -> OpeningProof {
+pub fn open<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    poly: &DensePolynomial<E::ScalarField>,
+    z: E::ScalarField,
+    y: E::ScalarField,
+) -> OpeningProof<E> {
     let mut numerator_coeffs = poly.coeffs().to_vec();
     if numerator_coeffs.is_empty() {
         numerator_coeffs.push(-y);
@@ -44,7 +53,27 @@ pub fn open(setup: &TrustedSetup, poly: &DensePolynomial<Fr>, z: Fr, y: Fr) -> O
     OpeningProof(proof_point.0)
 }
 
-fn divide_by_linear(poly: &DensePolynomial<Fr>, root: Fr) -> DensePolynomial<Fr> {
+/// Opens several polynomials at the same point with one proof.
+///
+/// The polynomials are folded into Σ vⁱ·pᵢ and that combination is opened.
+pub fn batch_open<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    polys: &[&DensePolynomial<E::ScalarField>],
+    z: E::ScalarField,
+    v: E::ScalarField,
+) -> OpeningProof<E> {
+    let mut combined = DensePolynomial::from_coefficients_vec(vec![]);
+    let mut power = E::ScalarField::one();
+    for poly in polys {
+        combined += (power, *poly);
+        power *= v;
+    }
+
+    let y = combined.evaluate(&z);
+    open(setup, &combined, z, y)
+}
+
+fn divide_by_linear<F: PrimeField>(poly: &DensePolynomial<F>, root: F) -> DensePolynomial<F> {
     let coeffs = poly.coeffs();
 
     if coeffs.is_empty() {
@@ -56,7 +85,7 @@ fn divide_by_linear(poly: &DensePolynomial<Fr>, root: Fr) -> DensePolynomial<Fr>
     }
 
     let n = coeffs.len();
-    let mut quotient_coeffs = vec![Fr::from(0u64); n - 1];
+    let mut quotient_coeffs = vec![F::zero(); n - 1];
 
     quotient_coeffs[n - 2] = coeffs[n - 1];
 
@@ -67,21 +96,135 @@ fn divide_by_linear(poly: &DensePolynomial<Fr>, root: Fr) -> DensePolynomial<Fr>
     DensePolynomial::from_coefficients_vec(quotient_coeffs)
 }
 
-pub fn verify(
-    setup: &TrustedSetup,
-    commitment: &Commitment,
-    z: Fr,
-    y: Fr,
-    proof: &OpeningProof,
+pub fn verify<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    commitment: &Commitment<E>,
+    z: E::ScalarField,
+    y: E::ScalarField,
+    proof: &OpeningProof<E>,
 ) -> bool {
     let y_g1 = setup.g1_powers[0] * y;
-    let c_minus_y: G1Affine = (commitment.0.into_group() - y_g1).into_affine();
+    let c_minus_y: E::G1Affine = (commitment.0.into_group() - y_g1).into_affine();
 
     let z_g2 = setup.g2_gen * z;
     let tau_minus_z = (setup.g2_tau.into_group() - z_g2).into_affine();
 
-    let lhs = Bls12_381::pairing(c_minus_y, setup.g2_gen);
-    let rhs = Bls12_381::pairing(proof.0, tau_minus_z);
+    let lhs = E::pairing(c_minus_y, setup.g2_gen);
+    let rhs = E::pairing(proof.0, tau_minus_z);
 
     lhs == rhs
 }
+
+/// Verifies a proof produced by [`batch_open`].
+///
+/// Folds the commitments and claimed evaluations with the same powers of v
+/// and checks the single combined opening.
+pub fn batch_verify<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    commitments: &[Commitment<E>],
+    z: E::ScalarField,
+    evals: &[E::ScalarField],
+    v: E::ScalarField,
+    proof: &OpeningProof<E>,
+) -> bool {
+    if commitments.len() != evals.len() {
+        return false;
+    }
+
+    Opening::batch(commitments, z, evals, v, proof.clone()).verify(setup)
+}
+
+/// Folds commitments into Σ vⁱ·Cᵢ, the commitment [`batch_open`] opens.
+pub fn combine<E: Pairing>(commitments: &[Commitment<E>], v: E::ScalarField) -> Commitment<E> {
+    let mut combined = E::G1::zero();
+    let mut power = E::ScalarField::one();
+    for commitment in commitments {
+        combined += commitment.0 * power;
+        power *= v;
+    }
+    Commitment(combined.into_affine())
+}
+
+/// A claim that `commitment` opens to `y` at `z`, not yet checked.
+#[derive(Clone, Debug)]
+pub struct Opening<E: Pairing = Bls12_381> {
+    pub commitment: Commitment<E>,
+    pub z: E::ScalarField,
+    pub y: E::ScalarField,
+    pub proof: OpeningProof<E>,
+}
+
+impl<E: Pairing> Opening<E> {
+    /// The single opening a [`batch_open`] proof stands for.
+    pub fn batch(
+        commitments: &[Commitment<E>],
+        z: E::ScalarField,
+        evals: &[E::ScalarField],
+        v: E::ScalarField,
+        proof: OpeningProof<E>,
+    ) -> Self {
+        let y = evals
+            .iter()
+            .rev()
+            .fold(E::ScalarField::zero(), |acc, eval| acc * v + eval);
+        Opening {
+            commitment: combine(commitments, v),
+            z,
+            y,
+            proof,
+        }
+    }
+
+    pub fn verify(&self, setup: &TrustedSetup<E>) -> bool {
+        verify(setup, &self.commitment, self.z, self.y, &self.proof)
+    }
+
+    /// Moves everything but the pairings out of [`verify`]: with W the
+    /// proof, C - y·G = (τ - z)·W becomes e(C - y·G + z·W, [1]₂) = e(W, [τ]₂).
+    pub fn accumulator(&self, setup: &TrustedSetup<E>) -> Accumulator<E> {
+        let lhs =
+            self.commitment.0.into_group() - setup.g1_powers[0] * self.y + self.proof.0 * self.z;
+        Accumulator {
+            lhs: lhs.into_affine(),
+            rhs: self.proof.0,
+        }
+    }
+}
+
+/// Any number of openings folded into one pairing equation,
+/// e(lhs, [1]₂) = e(rhs, [τ]₂).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Accumulator<E: Pairing = Bls12_381> {
+    pub lhs: E::G1Affine,
+    pub rhs: E::G1Affine,
+}
+
+impl<E: Pairing> Accumulator<E> {
+    /// Σ rⁱ·accᵢ, which decides to true if every accᵢ does and, for r
+    /// chosen after the accumulators, to false except with negligible
+    /// probability otherwise.
+    pub fn fold(accumulators: &[Self], r: E::ScalarField) -> Self {
+        let mut lhs = E::G1::zero();
+        let mut rhs = E::G1::zero();
+        let mut power = E::ScalarField::one();
+        for acc in accumulators {
+            lhs += acc.lhs * power;
+            rhs += acc.rhs * power;
+            power *= r;
+        }
+        Accumulator {
+            lhs: lhs.into_affine(),
+            rhs: rhs.into_affine(),
+        }
+    }
+
+    /// Checks the pairing equation, with one final exponentiation.
+    pub fn decide(&self, setup: &TrustedSetup<E>) -> bool {
+        let neg_rhs = -self.rhs.into_group();
+        E::multi_pairing(
+            [self.lhs.into_group(), neg_rhs],
+            [setup.g2_gen, setup.g2_tau],
+        )
+        .is_zero()
+    }
+}
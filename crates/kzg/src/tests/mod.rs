@@ -1 +1,2 @@
+mod test_ipp;
 mod test_kzg;
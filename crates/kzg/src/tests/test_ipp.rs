@@ -0,0 +1,133 @@
+use crate::ipp::*;
+use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::One;
+use setup::IppSetup;
+
+/// gⁱ⁺¹ for i below `len`
+fn points<E: Pairing>(len: u64) -> Vec<E::G1Affine> {
+    let g = E::G1Affine::generator();
+    (1..=len)
+        .map(|i| (g * E::ScalarField::from(i)).into_affine())
+        .collect()
+}
+
+fn scalars<E: Pairing>(len: u64) -> Vec<Vec<E::ScalarField>> {
+    vec![
+        (0..len).map(|i| E::ScalarField::from(i * i + 1)).collect(),
+        (0..len).map(|i| E::ScalarField::from(7 + i)).collect(),
+    ]
+}
+
+fn check_mipp<E: Pairing>(len: u64) {
+    let setup = IppSetup::<E>::generate(8);
+    let points = points::<E>(len);
+    let scalars = scalars::<E>(len);
+    let comm = commit(&setup, &points);
+
+    let (products, proof) = prove_mipp(&setup, &mut Challenger::new(b"test"), &points, &scalars);
+    assert_eq!(
+        proof.rounds.len(),
+        (len as usize).next_power_of_two().trailing_zeros() as usize
+    );
+    assert!(verify_mipp(
+        &setup,
+        &mut Challenger::new(b"test"),
+        &comm,
+        &scalars,
+        &products,
+        &proof
+    ));
+    // A single point needs no rounds, so nothing depends on the challenges
+    assert!(
+        len == 1
+            || !verify_mipp(
+                &setup,
+                &mut Challenger::new(b"other"),
+                &comm,
+                &scalars,
+                &products,
+                &proof
+            )
+    );
+}
+
+#[test]
+fn test_mipp_verifies() {
+    for len in [1, 2, 5, 8] {
+        check_mipp::<Bls12_381>(len);
+    }
+}
+
+#[test]
+fn test_bn254_mipp_verifies() {
+    check_mipp::<Bn254>(6);
+}
+
+#[test]
+fn test_mipp_rejects_wrong_products() {
+    let setup = IppSetup::<Bls12_381>::generate(8);
+    let points = points::<Bls12_381>(6);
+    let scalars = scalars::<Bls12_381>(6);
+    let comm = commit(&setup, &points);
+    let (products, proof) = prove_mipp(&setup, &mut Challenger::new(b"test"), &points, &scalars);
+    let verify =
+        |comm: &PairCommitment, scalars: &[Vec<Fr>], products: &[G1Affine], proof: &MippProof| {
+            verify_mipp(
+                &setup,
+                &mut Challenger::new(b"test"),
+                comm,
+                scalars,
+                products,
+                proof,
+            )
+        };
+
+    let mut wrong = products.clone();
+    wrong[1] = (wrong[1] + G1Affine::generator()).into_affine();
+    assert!(!verify(&comm, &scalars, &wrong, &proof));
+
+    let mut wrong = scalars.clone();
+    wrong[0][3] += Fr::one();
+    assert!(!verify(&comm, &wrong, &products, &proof));
+
+    let other = commit(&setup, &points[..5]);
+    assert!(!verify(&other, &scalars, &products, &proof));
+
+    let mut tampered = proof.clone();
+    tampered.alpha_key = tampered.beta_key;
+    assert!(!verify(&comm, &scalars, &products, &tampered));
+
+    let mut tampered = proof.clone();
+    tampered.rounds[0].products_l[0] = tampered.rounds[0].products_r[0];
+    assert!(!verify(&comm, &scalars, &products, &tampered));
+
+    let mut tampered = proof;
+    tampered.rounds.pop();
+    assert!(!verify(&comm, &scalars, &products, &tampered));
+}
+
+#[test]
+fn test_mipp_rejects_oversized_rounds() {
+    let setup = IppSetup::<Bls12_381>::generate(8);
+    let points = points::<Bls12_381>(6);
+    let scalars = scalars::<Bls12_381>(6);
+    let comm = commit(&setup, &points);
+    let (products, proof) = prove_mipp(&setup, &mut Challenger::new(b"test"), &points, &scalars);
+
+    // 2⁶⁴ points would overflow the shift, and 2⁴ do not fit the setup
+    for rounds in [64, 4] {
+        let mut tampered = proof.clone();
+        tampered.rounds = vec![proof.rounds[0].clone(); rounds];
+        assert!(!verify_mipp(
+            &setup,
+            &mut Challenger::new(b"test"),
+            &comm,
+            &scalars,
+            &products,
+            &tampered
+        ));
+    }
+}
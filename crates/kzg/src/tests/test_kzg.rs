@@ -1,15 +1,17 @@
 use crate::*;
+use ark_bls12_381::Fr;
+use ark_bn254::Bn254;
 use ark_ff::One;
 
 /// poly(&[1, 2, 3]) = 1 + 2x + 3x²
-fn poly(coeffs: &[i64]) -> DensePolynomial<Fr> {
-    let fr_coeffs: Vec<Fr> = coeffs
+fn poly<F: PrimeField>(coeffs: &[i64]) -> DensePolynomial<F> {
+    let fr_coeffs: Vec<F> = coeffs
         .iter()
         .map(|&c| {
             if c >= 0 {
-                Fr::from(c as u64)
+                F::from(c as u64)
             } else {
-                -Fr::from((-c) as u64)
+                -F::from((-c) as u64)
             }
         })
         .collect();
@@ -18,7 +20,7 @@ fn poly(coeffs: &[i64]) -> DensePolynomial<Fr> {
 
 #[test]
 fn test_commit_and_verify_opening() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
 
     // f(x) = 1 + 2x + 3x²
     let f = poly(&[1, 2, 3]);
@@ -37,7 +39,7 @@ fn test_commit_and_verify_opening() {
 
 #[test]
 fn test_verify_rejects_wrong_evaluation() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
     let f = poly(&[1, 2, 3]);
 
     let commitment = commit(&setup, &f);
@@ -54,7 +56,7 @@ fn test_verify_rejects_wrong_evaluation() {
 
 #[test]
 fn test_verify_rejects_wrong_proof() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
 
     let f = poly(&[1, 2, 3]);
     let g = poly(&[5, 6, 7]);
@@ -71,7 +73,7 @@ fn test_verify_rejects_wrong_proof() {
 
 #[test]
 fn test_constant_polynomial() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
 
     let f = poly(&[42]);
 
@@ -87,7 +89,7 @@ fn test_constant_polynomial() {
 
 #[test]
 fn test_linear_polynomial() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
 
     // f(x) = 3 + 7x
     let f = poly(&[3, 7]);
@@ -112,6 +114,123 @@ fn test_divide_by_linear() {
 
     let quotient = divide_by_linear(&numerator, root);
 
-    let expected = poly(&[1, 1]);
+    let expected = poly::<Fr>(&[1, 1]);
     assert_eq!(quotient.coeffs(), expected.coeffs());
 }
+
+#[test]
+fn test_batch_open_and_verify() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let f = poly(&[1, 2, 3]);
+    let g = poly(&[5, 6, 7, 8]);
+    let h = poly(&[-4]);
+    let commitments = vec![commit(&setup, &f), commit(&setup, &g), commit(&setup, &h)];
+
+    let z = Fr::from(11u64);
+    let v = Fr::from(3u64);
+    let evals = vec![f.evaluate(&z), g.evaluate(&z), h.evaluate(&z)];
+
+    let proof = batch_open(&setup, &[&f, &g, &h], z, v);
+
+    assert!(batch_verify(&setup, &commitments, z, &evals, v, &proof));
+}
+
+#[test]
+fn test_batch_verify_rejects_wrong_evaluation() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let f = poly(&[1, 2, 3]);
+    let g = poly(&[5, 6, 7, 8]);
+    let commitments = vec![commit(&setup, &f), commit(&setup, &g)];
+
+    let z = Fr::from(11u64);
+    let v = Fr::from(3u64);
+    let mut evals = vec![f.evaluate(&z), g.evaluate(&z)];
+
+    let proof = batch_open(&setup, &[&f, &g], z, v);
+    evals[1] += Fr::one();
+
+    assert!(!batch_verify(&setup, &commitments, z, &evals, v, &proof));
+    assert!(!batch_verify(
+        &setup,
+        &commitments[..1],
+        z,
+        &evals,
+        v,
+        &proof
+    ));
+}
+
+#[test]
+fn test_bn254_open_and_batch_verify() {
+    type F = <Bn254 as Pairing>::ScalarField;
+    let setup = TrustedSetup::<Bn254>::generate(10);
+
+    let f = poly(&[1, 2, 3]);
+    let g = poly(&[5, 6, 7, 8]);
+    let commitments = vec![commit(&setup, &f), commit(&setup, &g)];
+
+    let z = F::from(11u64);
+    let y = f.evaluate(&z);
+    let proof = open(&setup, &f, z, y);
+    assert!(verify(&setup, &commitments[0], z, y, &proof));
+    assert!(!verify(&setup, &commitments[0], z, y + F::one(), &proof));
+
+    let v = F::from(3u64);
+    let mut evals = vec![f.evaluate(&z), g.evaluate(&z)];
+    let proof = batch_open(&setup, &[&f, &g], z, v);
+    assert!(batch_verify(&setup, &commitments, z, &evals, v, &proof));
+    evals[1] += F::one();
+    assert!(!batch_verify(&setup, &commitments, z, &evals, v, &proof));
+}
+
+#[test]
+fn test_folded_openings_decide() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let polys = [poly(&[1, 2, 3]), poly(&[4, 0, -1, 7]), poly(&[-2, 5])];
+    let openings: Vec<Opening> = polys
+        .iter()
+        .zip([3u64, 8, 11])
+        .map(|(f, z)| {
+            let z = Fr::from(z);
+            let y = f.evaluate(&z);
+            Opening {
+                commitment: commit(&setup, f),
+                z,
+                y,
+                proof: open(&setup, f, z, y),
+            }
+        })
+        .collect();
+    let r = Fr::from(17u64);
+
+    let accumulators: Vec<Accumulator> = openings.iter().map(|o| o.accumulator(&setup)).collect();
+    assert!(accumulators.iter().all(|acc| acc.decide(&setup)));
+    assert!(Accumulator::fold(&accumulators, r).decide(&setup));
+
+    for i in 0..openings.len() {
+        let mut wrong = openings.clone();
+        wrong[i].y += Fr::one();
+        let accumulators: Vec<Accumulator> = wrong.iter().map(|o| o.accumulator(&setup)).collect();
+        assert!(!Accumulator::fold(&accumulators, r).decide(&setup));
+    }
+}
+
+#[test]
+fn test_batched_opening_matches_batch_verify() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let f = poly(&[1, 2, 3]);
+    let g = poly(&[5, -1]);
+    let polys = [&f, &g];
+    let commitments: Vec<Commitment> = polys.iter().map(|p| commit(&setup, p)).collect();
+    let z = Fr::from(9u64);
+    let v = Fr::from(4u64);
+    let evals: Vec<Fr> = polys.iter().map(|p| p.evaluate(&z)).collect();
+    let proof = batch_open(&setup, &polys, z, v);
+
+    let opening = Opening::batch(&commitments, z, &evals, v, proof.clone());
+    assert!(batch_verify(&setup, &commitments, z, &evals, v, &proof));
+    assert!(opening.verify(&setup));
+    assert!(opening.accumulator(&setup).decide(&setup));
+}
@@ -0,0 +1,37 @@
+//! Proving time for chains of multiplication gates of 2¹⁶ to 2²⁰ rows.
+//!
+//! Compare `cargo bench -p proof` with `cargo bench -p proof --features
+//! parallel` to see how proving scales with the number of threads.
+
+use ark_bls12_381::{Bls12_381, Fr};
+use circuit::constraint_system::ConstraintSystem;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use proof::{preprocess, prove};
+use setup::TrustedSetup;
+
+/// x ↦ x² repeated until the circuit fills a domain of 2^`log_rows` rows.
+fn square_chain(log_rows: usize) -> ConstraintSystem {
+    let mut cs = ConstraintSystem::new();
+    let mut x = cs.public_input(Fr::from(3u64));
+    while cs.rows().len() < 1 << log_rows {
+        x = cs.mul(x, x);
+    }
+    cs
+}
+
+fn bench_prove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prove");
+    group.sample_size(10);
+    for log_rows in 16..=20 {
+        let cs = square_chain(log_rows);
+        let setup = TrustedSetup::<Bls12_381>::generate(cs.domain_size() - 1);
+        let pk = preprocess(&setup, &cs);
+        group.bench_with_input(BenchmarkId::from_parameter(log_rows), &cs, |b, cs| {
+            b.iter(|| prove(&setup, &pk, cs))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_prove);
+criterion_main!(benches);
@@ -0,0 +1,92 @@
+use ark_bls12_381::Fr;
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One, PrimeField};
+use circuit::gate::Gate;
+
+use crate::key::{VerifyingKey, custom_selector_offsets, wire_shifts};
+use crate::lookup::{LookupValues, lookup_constraint};
+
+/// Verifier challenges that weight the parts of the identity.
+#[derive(Clone, Copy, Debug)]
+pub struct Challenges<F: PrimeField = Fr> {
+    pub beta: F,
+    pub gamma: F,
+    pub alpha: F,
+    /// Compresses lookup tuples into one field element
+    pub eta: F,
+    /// Shifts the lookup fractions' denominators
+    pub delta: F,
+}
+
+/// Every column of the circuit evaluated at one point X.
+///
+/// The prover fills this in for each point of the quotient domain, the
+/// verifier once at ζ from the opened evaluations.
+#[derive(Clone, Debug)]
+pub struct ColumnValues<'a, F: PrimeField = Fr> {
+    pub x: F,
+    pub wires: &'a [F],
+    /// Wires at ω·X, i.e. on the next row
+    pub wires_next: &'a [F],
+    pub selectors: &'a [F],
+    pub custom_selectors: &'a [F],
+    pub sigmas: &'a [F],
+    pub z: F,
+    /// Permutation accumulator at ω·X
+    pub z_next: F,
+    /// Public input polynomial
+    pub pi: F,
+    /// First Lagrange basis polynomial
+    pub l1: F,
+    /// Lookup columns, if the circuit has tables
+    pub lookup: Option<LookupValues<'a, F>>,
+}
+
+/// Evaluates the combined identity that must vanish on the whole domain:
+///
+/// ```text
+/// gate + PI
+///   + α·(z·Π(wⱼ + β·kⱼ·X + γ) - z(ωX)·Π(wⱼ + β·σⱼ + γ))
+///   + α²·L1·(z - 1)
+///   + α³·custom₀ + α⁴·custom₁ + …
+///   + αᵏ·lookup
+/// ```
+///
+/// The lookup term takes the power of α after the last custom constraint.
+pub fn combined_constraint<E: Pairing>(
+    vk: &VerifyingKey<E>,
+    values: &ColumnValues<E::ScalarField>,
+    ch: &Challenges<E::ScalarField>,
+) -> E::ScalarField {
+    let gate = Gate::from_selectors(values.selectors);
+    let gate_eval = gate.evaluate_row(values.wires) + values.pi;
+
+    let shifts = wire_shifts::<E::ScalarField>(vk.num_wires);
+    let mut identity_product = values.z;
+    let mut sigma_product = values.z_next;
+    for ((wire, shift), sigma) in values.wires.iter().zip(shifts).zip(values.sigmas) {
+        identity_product *= *wire + ch.beta * shift * values.x + ch.gamma;
+        sigma_product *= *wire + ch.beta * sigma + ch.gamma;
+    }
+    let perm_eval = identity_product - sigma_product;
+
+    let boundary_eval = values.l1 * (values.z - E::ScalarField::one());
+
+    let mut result = gate_eval + ch.alpha * perm_eval + ch.alpha.square() * boundary_eval;
+
+    let offsets = custom_selector_offsets(&vk.custom_gates);
+    let mut alpha_power = ch.alpha.pow([3]);
+    for (gate, offset) in vk.custom_gates.iter().zip(offsets) {
+        let selectors = &values.custom_selectors[offset..offset + gate.selectors().len()];
+        for constraint in gate.evaluate(selectors, values.wires, values.wires_next) {
+            result += alpha_power * constraint;
+            alpha_power *= ch.alpha;
+        }
+    }
+
+    if let Some(lookup) = &values.lookup {
+        result += alpha_power * lookup_constraint(lookup, values.wires, ch.eta, ch.delta);
+    }
+
+    result
+}
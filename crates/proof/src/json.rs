@@ -0,0 +1,415 @@
+//! JSON encoding of proofs and verifying keys.
+//!
+//! Scalars are `0x`-prefixed big-endian hex strings as wide as the scalar
+//! field, 32 bytes on both supported curves. G1 points are `[x, y]` arrays
+//! of affine coordinates, each a `0x`-prefixed big-endian hex string as
+//! wide as the base field (48 bytes on BLS12-381, 32 on BN254), with the
+//! point at infinity written as two zeros, as in EIP-2537. Commitments are
+//! named after the polynomial they commit to, following snarkjs, and every
+//! object carries `"protocol": "plonk"` and the [`JsonCurve::NAME`] of its
+//! curve, `"bls12381"` or `"bn254"`.
+//!
+//! A proof looks like:
+//!
+//! ```text
+//! {
+//!   "protocol": "plonk",
+//!   "curve": "bls12381",
+//!   "wires": [[x, y], …],
+//!   "z": [x, y],
+//!   "t": [[x, y], …],
+//!   "wire_evals": [s, …],
+//!   "wire_next_evals": [s, …],
+//!   "selector_evals": [s, …],
+//!   "custom_selector_evals": [s, …],
+//!   "sigma_evals": [s, …],
+//!   "z_eval": s,
+//!   "z_next_eval": s,
+//!   "t_evals": [s, …],
+//!   "lookup": null | {
+//!     "m": [x, y], "phi": [x, y],
+//!     "q_lookup_eval": s, "q_table_eval": s, "table_tag_eval": s,
+//!     "table_evals": [s, …], "m_eval": s, "phi_eval": s, "phi_next_eval": s
+//!   },
+//!   "opening": [x, y],
+//!   "next_opening": [x, y]
+//! }
+//! ```
+//!
+//! and a verifying key like:
+//!
+//! ```text
+//! {
+//!   "protocol": "plonk",
+//!   "curve": "bls12381",
+//!   "domain_size": 8,
+//!   "num_wires": 3,
+//!   "public_input_rows": [1],
+//!   "selectors": [[x, y], …],
+//!   "custom_selectors": [[x, y], …],
+//!   "sigmas": [[x, y], …],
+//!   "custom_gates": [{ "name": "edwards_add", "params": { "a": s, "d": s } }],
+//!   "lookup": null | {
+//!     "width": 1, "q_lookup": [x, y], "q_table": [x, y],
+//!     "table_tag": [x, y], "tables": [[x, y], …]
+//!   }
+//! }
+//! ```
+//!
+//! Selectors are in column order: q_l, q_r, q_o, q_m, q_c, then one per
+//! wire after c.
+
+use std::io;
+
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ec::AffineRepr;
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use circuit::custom_gates::builtin;
+use kzg::{Commitment, OpeningProof};
+use serde_json::{Map, Value, json};
+
+use crate::lookup::{LookupProof, LookupVerifyingKey};
+use crate::serialization::invalid_data;
+use crate::{Proof, VerifyingKey};
+
+const PROTOCOL: &str = "plonk";
+
+/// Curves the JSON layout can name.
+pub trait JsonCurve: Pairing {
+    /// Value of the `"curve"` field
+    const NAME: &'static str;
+}
+
+impl JsonCurve for Bls12_381 {
+    const NAME: &'static str = "bls12381";
+}
+
+impl JsonCurve for Bn254 {
+    const NAME: &'static str = "bn254";
+}
+
+impl<E, P> Proof<E>
+where
+    E: JsonCurve<G1Affine = Affine<P>>,
+    P: SWCurveConfig<BaseField: PrimeField>,
+{
+    pub fn to_json(&self) -> String {
+        let lookup = self.lookup.as_ref().map(|lookup| {
+            json!({
+                "m": point(&lookup.m_comm.0),
+                "phi": point(&lookup.phi_comm.0),
+                "q_lookup_eval": scalar(&lookup.q_lookup_eval),
+                "q_table_eval": scalar(&lookup.q_table_eval),
+                "table_tag_eval": scalar(&lookup.table_tag_eval),
+                "table_evals": scalars(&lookup.table_evals),
+                "m_eval": scalar(&lookup.m_eval),
+                "phi_eval": scalar(&lookup.phi_eval),
+                "phi_next_eval": scalar(&lookup.phi_next_eval),
+            })
+        });
+        let value = json!({
+            "protocol": PROTOCOL,
+            "curve": E::NAME,
+            "wires": commitments(&self.wire_comms),
+            "z": point(&self.z_comm.0),
+            "t": commitments(&self.t_comms),
+            "wire_evals": scalars(&self.wire_evals),
+            "wire_next_evals": scalars(&self.wire_next_evals),
+            "selector_evals": scalars(&self.selector_evals),
+            "custom_selector_evals": scalars(&self.custom_selector_evals),
+            "sigma_evals": scalars(&self.sigma_evals),
+            "z_eval": scalar(&self.z_eval),
+            "z_next_eval": scalar(&self.z_next_eval),
+            "t_evals": scalars(&self.t_evals),
+            "lookup": lookup,
+            "opening": point(&self.opening.0),
+            "next_opening": point(&self.next_opening.0),
+        });
+        serde_json::to_string_pretty(&value).expect("JSON values serialize")
+    }
+
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let value: Value = serde_json::from_str(json).map_err(invalid_data)?;
+        let object = header(&value, E::NAME)?;
+
+        let lookup = match field(object, "lookup")? {
+            Value::Null => None,
+            lookup => {
+                let lookup = as_object(lookup, "lookup")?;
+                Some(LookupProof {
+                    m_comm: Commitment(parse_point(field(lookup, "m")?)?),
+                    phi_comm: Commitment(parse_point(field(lookup, "phi")?)?),
+                    q_lookup_eval: parse_scalar(field(lookup, "q_lookup_eval")?)?,
+                    q_table_eval: parse_scalar(field(lookup, "q_table_eval")?)?,
+                    table_tag_eval: parse_scalar(field(lookup, "table_tag_eval")?)?,
+                    table_evals: parse_scalars(field(lookup, "table_evals")?)?,
+                    m_eval: parse_scalar(field(lookup, "m_eval")?)?,
+                    phi_eval: parse_scalar(field(lookup, "phi_eval")?)?,
+                    phi_next_eval: parse_scalar(field(lookup, "phi_next_eval")?)?,
+                })
+            }
+        };
+
+        Ok(Proof {
+            wire_comms: parse_commitments(field(object, "wires")?)?,
+            z_comm: Commitment(parse_point(field(object, "z")?)?),
+            t_comms: parse_commitments(field(object, "t")?)?,
+            wire_evals: parse_scalars(field(object, "wire_evals")?)?,
+            wire_next_evals: parse_scalars(field(object, "wire_next_evals")?)?,
+            selector_evals: parse_scalars(field(object, "selector_evals")?)?,
+            custom_selector_evals: parse_scalars(field(object, "custom_selector_evals")?)?,
+            sigma_evals: parse_scalars(field(object, "sigma_evals")?)?,
+            z_eval: parse_scalar(field(object, "z_eval")?)?,
+            z_next_eval: parse_scalar(field(object, "z_next_eval")?)?,
+            t_evals: parse_scalars(field(object, "t_evals")?)?,
+            lookup,
+            opening: OpeningProof(parse_point(field(object, "opening")?)?),
+            next_opening: OpeningProof(parse_point(field(object, "next_opening")?)?),
+        })
+    }
+}
+
+impl<E, P> VerifyingKey<E>
+where
+    E: JsonCurve<G1Affine = Affine<P>>,
+    P: SWCurveConfig<BaseField: PrimeField>,
+{
+    pub fn to_json(&self) -> String {
+        let custom_gates: Vec<Value> = self
+            .custom_gates
+            .iter()
+            .map(|gate| {
+                let params: Map<String, Value> = gate
+                    .params()
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), scalar(&value)))
+                    .collect();
+                json!({ "name": gate.name(), "params": params })
+            })
+            .collect();
+        let lookup = self.lookup.as_ref().map(|lookup| {
+            json!({
+                "width": lookup.width,
+                "q_lookup": point(&lookup.q_lookup_comm.0),
+                "q_table": point(&lookup.q_table_comm.0),
+                "table_tag": point(&lookup.table_tag_comm.0),
+                "tables": commitments(&lookup.table_comms),
+            })
+        });
+        let value = json!({
+            "protocol": PROTOCOL,
+            "curve": E::NAME,
+            "domain_size": self.domain_size,
+            "num_wires": self.num_wires,
+            "public_input_rows": self.public_input_rows,
+            "selectors": commitments(&self.selector_comms),
+            "custom_selectors": commitments(&self.custom_selector_comms),
+            "sigmas": commitments(&self.sigma_comms),
+            "custom_gates": custom_gates,
+            "lookup": lookup,
+        });
+        serde_json::to_string_pretty(&value).expect("JSON values serialize")
+    }
+
+    /// Parses a key and checks that its parts fit together.
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let value: Value = serde_json::from_str(json).map_err(invalid_data)?;
+        let object = header(&value, E::NAME)?;
+
+        let custom_gates = as_array(field(object, "custom_gates")?, "custom_gates")?
+            .iter()
+            .map(|gate| {
+                let gate = as_object(gate, "custom gate")?;
+                let name = as_str(field(gate, "name")?, "name")?;
+                let params = as_object(field(gate, "params")?, "params")?
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), parse_scalar(value)?)))
+                    .collect::<io::Result<Vec<_>>>()?;
+                builtin(name, &params).map_err(invalid_data)
+            })
+            .collect::<io::Result<_>>()?;
+        let lookup = match field(object, "lookup")? {
+            Value::Null => None,
+            lookup => {
+                let lookup = as_object(lookup, "lookup")?;
+                Some(LookupVerifyingKey {
+                    width: parse_count(field(lookup, "width")?)?,
+                    q_lookup_comm: Commitment(parse_point(field(lookup, "q_lookup")?)?),
+                    q_table_comm: Commitment(parse_point(field(lookup, "q_table")?)?),
+                    table_tag_comm: Commitment(parse_point(field(lookup, "table_tag")?)?),
+                    table_comms: parse_commitments(field(lookup, "tables")?)?,
+                })
+            }
+        };
+
+        let vk = VerifyingKey {
+            domain_size: parse_count(field(object, "domain_size")?)?,
+            num_wires: parse_count(field(object, "num_wires")?)?,
+            public_input_rows: as_array(field(object, "public_input_rows")?, "public_input_rows")?
+                .iter()
+                .map(parse_count)
+                .collect::<io::Result<_>>()?,
+            selector_comms: parse_commitments(field(object, "selectors")?)?,
+            custom_selector_comms: parse_commitments(field(object, "custom_selectors")?)?,
+            sigma_comms: parse_commitments(field(object, "sigmas")?)?,
+            custom_gates,
+            lookup,
+        };
+        vk.check_shape()?;
+        Ok(vk)
+    }
+}
+
+/// `0x`-prefixed big-endian hex of a scalar.
+pub fn scalar<F: PrimeField>(value: &F) -> Value {
+    Value::String(hex(&to_bytes(value)))
+}
+
+/// `[x, y]` affine coordinates in big-endian hex, zeros for infinity.
+pub fn point<P: SWCurveConfig<BaseField: PrimeField>>(point: &Affine<P>) -> Value {
+    let (x, y) = point
+        .xy()
+        .unwrap_or((P::BaseField::zero(), P::BaseField::zero()));
+    json!([hex(&to_bytes(&x)), hex(&to_bytes(&y))])
+}
+
+fn scalars<F: PrimeField>(values: &[F]) -> Vec<Value> {
+    values.iter().map(scalar).collect()
+}
+
+fn commitments<E, P>(comms: &[Commitment<E>]) -> Vec<Value>
+where
+    E: Pairing<G1Affine = Affine<P>>,
+    P: SWCurveConfig<BaseField: PrimeField>,
+{
+    comms.iter().map(|comm| point(&comm.0)).collect()
+}
+
+/// Big-endian bytes of a field element, padded to the field's width.
+fn to_bytes<F: PrimeField>(value: &F) -> Vec<u8> {
+    let mut bytes = value.into_bigint().to_bytes_be();
+    bytes.drain(..bytes.len() - width::<F>());
+    bytes
+}
+
+/// Bytes needed for an element of `F`.
+fn width<F: PrimeField>() -> usize {
+    F::MODULUS_BIT_SIZE.div_ceil(8) as usize
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{digits}")
+}
+
+/// Checks the protocol and curve tags.
+fn header<'a>(value: &'a Value, curve: &str) -> io::Result<&'a Map<String, Value>> {
+    let object = as_object(value, "document")?;
+    for (key, expected) in [("protocol", PROTOCOL), ("curve", curve)] {
+        let found = as_str(field(object, key)?, key)?;
+        if found != expected {
+            return Err(invalid_data(format!(
+                "Expected {key} {expected}, found {found}"
+            )));
+        }
+    }
+    Ok(object)
+}
+
+fn field<'a>(object: &'a Map<String, Value>, key: &str) -> io::Result<&'a Value> {
+    object
+        .get(key)
+        .ok_or_else(|| invalid_data(format!("Missing field {key}")))
+}
+
+fn as_object<'a>(value: &'a Value, what: &str) -> io::Result<&'a Map<String, Value>> {
+    value
+        .as_object()
+        .ok_or_else(|| invalid_data(format!("Expected {what} to be an object")))
+}
+
+fn as_array<'a>(value: &'a Value, what: &str) -> io::Result<&'a Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid_data(format!("Expected {what} to be an array")))
+}
+
+fn as_str<'a>(value: &'a Value, what: &str) -> io::Result<&'a str> {
+    value
+        .as_str()
+        .ok_or_else(|| invalid_data(format!("Expected {what} to be a string")))
+}
+
+fn parse_count(value: &Value) -> io::Result<usize> {
+    value
+        .as_u64()
+        .map(|count| count as usize)
+        .ok_or_else(|| invalid_data(format!("{value} is not a count")))
+}
+
+/// Big-endian bytes of a `0x` hex string, left-padded to `len`.
+fn parse_hex(value: &Value, len: usize) -> io::Result<Vec<u8>> {
+    let text = as_str(value, "element")?;
+    let digits = text
+        .strip_prefix("0x")
+        .ok_or_else(|| invalid_data(format!("{text} does not start with 0x")))?;
+    if digits.len() > 2 * len || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid_data(format!("{text} is not {len}-byte hex")));
+    }
+    let padded = format!("{digits:0>width$}", width = 2 * len);
+    Ok((0..len)
+        .map(|i| u8::from_str_radix(&padded[2 * i..2 * i + 2], 16).expect("checked hex digits"))
+        .collect())
+}
+
+/// Field element from big-endian bytes, rejecting values past the modulus.
+fn parse_field<F: PrimeField>(value: &Value) -> io::Result<F> {
+    let bytes = parse_hex(value, width::<F>())?;
+    let element = F::from_be_bytes_mod_order(&bytes);
+    if to_bytes(&element) != bytes {
+        return Err(invalid_data(format!("{value} is not below the modulus")));
+    }
+    Ok(element)
+}
+
+fn parse_scalar<F: PrimeField>(value: &Value) -> io::Result<F> {
+    parse_field(value)
+}
+
+fn parse_scalars<F: PrimeField>(value: &Value) -> io::Result<Vec<F>> {
+    as_array(value, "scalar list")?
+        .iter()
+        .map(parse_scalar)
+        .collect()
+}
+
+/// G1 point, checked to be on the curve and in the prime-order subgroup.
+fn parse_point<P: SWCurveConfig<BaseField: PrimeField>>(value: &Value) -> io::Result<Affine<P>> {
+    let coords = as_array(value, "point")?;
+    let [x, y] = coords.as_slice() else {
+        return Err(invalid_data(format!("{value} is not an [x, y] point")));
+    };
+    let (x, y): (P::BaseField, P::BaseField) = (parse_field(x)?, parse_field(y)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(Affine::zero());
+    }
+    let point = Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(invalid_data(format!("{value} is not a G1 point")));
+    }
+    Ok(point)
+}
+
+fn parse_commitments<E, P>(value: &Value) -> io::Result<Vec<Commitment<E>>>
+where
+    E: Pairing<G1Affine = Affine<P>>,
+    P: SWCurveConfig<BaseField: PrimeField>,
+{
+    as_array(value, "point list")?
+        .iter()
+        .map(|point| Ok(Commitment(parse_point(point)?)))
+        .collect()
+}
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ff::{FftField, PrimeField, Zero};
+use ark_poly::{
+    EvaluationDomain, Evaluations, Radix2EvaluationDomain, univariate::DensePolynomial,
+};
+use ark_std::{cfg_into_iter, cfg_iter};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::gate::{CustomGate, Gate};
+use kzg::{Commitment, commit};
+use setup::TrustedSetup;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::lookup::{LookupProvingKey, LookupVerifyingKey, preprocess_lookup};
+
+/// Public description of a preprocessed circuit.
+#[derive(Clone, Debug)]
+pub struct VerifyingKey<E: Pairing = Bls12_381> {
+    /// Number of rows after padding to a power of two
+    pub domain_size: usize,
+    /// Number of wire columns
+    pub num_wires: usize,
+    /// Rows whose a wire carries a public input, in input order
+    pub public_input_rows: Vec<usize>,
+    /// Commitments to q_l, q_r, q_o, q_m, q_c and the extra wire selectors
+    pub selector_comms: Vec<Commitment<E>>,
+    /// Commitments to custom gate selectors, gate by gate
+    pub custom_selector_comms: Vec<Commitment<E>>,
+    /// Commitments to the permutation polynomials, one per wire column
+    pub sigma_comms: Vec<Commitment<E>>,
+    /// Custom gates, in the order their selectors are laid out
+    pub custom_gates: Vec<Arc<dyn CustomGate<E::ScalarField>>>,
+    /// Lookup tables and query selectors, if the circuit has tables
+    pub lookup: Option<LookupVerifyingKey<E>>,
+}
+
+impl<E: Pairing> VerifyingKey<E> {
+    pub fn domain(&self) -> Radix2EvaluationDomain<E::ScalarField> {
+        Radix2EvaluationDomain::new(self.domain_size).expect("domain size is a power of two")
+    }
+
+    /// Degree of the full constraint identity, in multiples of n.
+    ///
+    /// The permutation product z·Π(wᵢ + β·kᵢ·X + γ) has degree one more
+    /// than the number of wires, the standard gate qM·a·b has degree 3,
+    /// custom gates declare theirs.
+    pub fn quotient_degree(&self) -> usize {
+        self.custom_gates
+            .iter()
+            .map(|gate| gate.degree())
+            .fold(self.num_wires + 1, usize::max)
+    }
+
+    /// Number of n-coefficient chunks the quotient is split into.
+    pub fn num_quotient_chunks(&self) -> usize {
+        self.quotient_degree() - 1
+    }
+}
+
+/// Verifying key plus the polynomials the prover needs.
+#[derive(Clone, Debug)]
+pub struct ProvingKey<E: Pairing = Bls12_381> {
+    pub vk: VerifyingKey<E>,
+    /// Standard gate selectors in coefficient form, in column order
+    pub selector_polys: Vec<DensePolynomial<E::ScalarField>>,
+    pub custom_selector_polys: Vec<DensePolynomial<E::ScalarField>>,
+    pub sigma_polys: Vec<DensePolynomial<E::ScalarField>>,
+    /// σ values over the domain, used to build the accumulator z
+    pub sigma_evals: Vec<Vec<E::ScalarField>>,
+    pub lookup: Option<LookupProvingKey<E>>,
+}
+
+/// Coset shifts kⱼ that give every wire column its own set of labels.
+///
+/// Column j, row i is labelled kⱼ·ωⁱ. Powers of the multiplicative
+/// generator lie in distinct cosets of any power-of-two subgroup.
+pub fn wire_shifts<F: FftField>(num_wires: usize) -> Vec<F> {
+    (0..num_wires)
+        .map(|j| F::GENERATOR.pow([j as u64]))
+        .collect()
+}
+
+/// Turns a constraint system into proving and verifying keys.
+///
+/// Only the circuit structure is used; the witness values are ignored.
+pub fn preprocess<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    cs: &ConstraintSystem<E::ScalarField>,
+) -> ProvingKey<E> {
+    let n = cs.domain_size();
+    let num_wires = cs.num_wires();
+    let domain =
+        Radix2EvaluationDomain::<E::ScalarField>::new(n).expect("domain size is a power of two");
+    let rows = cs.rows();
+
+    let num_selectors = Gate::<E::ScalarField>::disabled()
+        .selectors(num_wires)
+        .len();
+    let mut selector_columns = vec![vec![E::ScalarField::zero(); n]; num_selectors];
+    for (i, row) in rows.iter().enumerate() {
+        for (column, value) in selector_columns
+            .iter_mut()
+            .zip(row.gate.selectors(num_wires))
+        {
+            column[i] = value;
+        }
+    }
+
+    let custom_offsets = custom_selector_offsets(cs.custom_gates());
+    let num_custom_selectors: usize = cs.custom_gates().iter().map(|g| g.selectors().len()).sum();
+    let mut custom_columns = vec![vec![E::ScalarField::zero(); n]; num_custom_selectors];
+    for (i, row) in rows.iter().enumerate() {
+        for (id, selectors) in &row.custom {
+            for (k, value) in selectors.iter().enumerate() {
+                custom_columns[custom_offsets[id.index()] + k][i] = *value;
+            }
+        }
+    }
+
+    let sigma_evals = compute_sigmas(cs, &domain);
+    let lookup = preprocess_lookup(setup, cs, &domain);
+
+    let selector_polys: Vec<_> = cfg_into_iter!(selector_columns)
+        .map(|column| interpolate(&domain, column))
+        .collect();
+    let custom_selector_polys: Vec<_> = cfg_into_iter!(custom_columns)
+        .map(|column| interpolate(&domain, column))
+        .collect();
+    let sigma_polys: Vec<_> = cfg_iter!(sigma_evals)
+        .map(|column| interpolate(&domain, column.clone()))
+        .collect();
+
+    let vk = VerifyingKey {
+        domain_size: n,
+        num_wires,
+        public_input_rows: cs.public_input_rows().to_vec(),
+        selector_comms: cfg_iter!(selector_polys)
+            .map(|poly| commit(setup, poly))
+            .collect(),
+        custom_selector_comms: cfg_iter!(custom_selector_polys)
+            .map(|poly| commit(setup, poly))
+            .collect(),
+        sigma_comms: cfg_iter!(sigma_polys)
+            .map(|poly| commit(setup, poly))
+            .collect(),
+        custom_gates: cs.custom_gates().to_vec(),
+        lookup: lookup.as_ref().map(|lookup| lookup.vk.clone()),
+    };
+
+    ProvingKey {
+        vk,
+        selector_polys,
+        custom_selector_polys,
+        sigma_polys,
+        sigma_evals,
+        lookup,
+    }
+}
+
+/// Index of each custom gate's first selector column.
+pub fn custom_selector_offsets<F: PrimeField>(gates: &[Arc<dyn CustomGate<F>>]) -> Vec<usize> {
+    gates
+        .iter()
+        .scan(0, |offset, gate| {
+            let start = *offset;
+            *offset += gate.selectors().len();
+            Some(start)
+        })
+        .collect()
+}
+
+/// Builds σ so that every wire position points at the next position
+/// holding the same variable, closing each variable's positions into a cycle.
+fn compute_sigmas<F: PrimeField>(
+    cs: &ConstraintSystem<F>,
+    domain: &Radix2EvaluationDomain<F>,
+) -> Vec<Vec<F>> {
+    let n = domain.size();
+    let num_wires = cs.num_wires();
+    let shifts = wire_shifts::<F>(num_wires);
+    let rows = cs.rows();
+
+    let mut positions: Vec<Vec<(usize, usize)>> = vec![Vec::new(); cs.num_variables()];
+    for i in 0..n {
+        for j in 0..num_wires {
+            let var = rows.get(i).map_or(cs.zero(), |row| row.wires[j]);
+            positions[var.index()].push((j, i));
+        }
+    }
+
+    let mut sigmas = vec![vec![F::zero(); n]; num_wires];
+    for cycle in positions.iter().filter(|cycle| !cycle.is_empty()) {
+        for (k, &(j, i)) in cycle.iter().enumerate() {
+            let (next_j, next_i) = cycle[(k + 1) % cycle.len()];
+            sigmas[j][i] = shifts[next_j] * domain.element(next_i);
+        }
+    }
+
+    sigmas
+}
+
+pub(crate) fn interpolate<F: FftField>(
+    domain: &Radix2EvaluationDomain<F>,
+    evals: Vec<F>,
+) -> DensePolynomial<F> {
+    Evaluations::from_vec_and_domain(evals, *domain).interpolate()
+}
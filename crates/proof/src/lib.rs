@@ -1,97 +1,523 @@
-use ark_bls12_381::Fr;
-use ark_ff::{Field, One};
-use ark_poly::{DenseUVPolynomial, Polynomial, univariate::DensePolynomial};
-use circuit::{square_circuit::SquareCircuit, witness::Witness};
-use kzg::{Commitment, OpeningProof, commit, open};
+pub mod constraints;
+pub mod json;
+pub mod key;
+pub mod lookup;
+pub mod serialization;
+pub mod transcript;
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ff::{FftField, Field, One, PrimeField, Zero, batch_inversion};
+use ark_poly::{
+    DenseUVPolynomial, EvaluationDomain, Polynomial, Radix2EvaluationDomain,
+    univariate::DensePolynomial,
+};
+use ark_std::{cfg_into_iter, cfg_iter};
+use circuit::constraint_system::ConstraintSystem;
+use kzg::{Commitment, OpeningProof, batch_open, commit};
 use setup::TrustedSetup;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use constraints::{Challenges, ColumnValues, combined_constraint};
+use key::interpolate;
+pub use key::{ProvingKey, VerifyingKey, preprocess};
+use lookup::{LookupProof, LookupValues, compute_multiplicities, compute_running_sum};
+use transcript::{Scalar, Sha256Transcript, Transcript};
+
 #[derive(Clone, Debug)]
-pub struct Proof {
-    pub a_comm: Commitment,
-    pub b_comm: Commitment,
-    pub c_comm: Commitment,
-
-    pub q_comm: Commitment,
-
-    pub a_eval: Fr,
-    pub b_eval: Fr,
-    pub c_eval: Fr,
-    pub q_eval: Fr,
-
-    pub a_opening: OpeningProof,
-    pub b_opening: OpeningProof,
-    pub c_opening: OpeningProof,
-    pub q_opening: OpeningProof,
+pub struct Proof<E: Pairing = Bls12_381> {
+    /// Commitments to the wire polynomials a, b, c, …
+    pub wire_comms: Vec<Commitment<E>>,
+    /// Commitment to the permutation accumulator z
+    pub z_comm: Commitment<E>,
+    /// Commitments to the quotient t, split into chunks of n coefficients
+    pub t_comms: Vec<Commitment<E>>,
+
+    pub wire_evals: Vec<E::ScalarField>,
+    pub wire_next_evals: Vec<E::ScalarField>,
+    pub selector_evals: Vec<E::ScalarField>,
+    pub custom_selector_evals: Vec<E::ScalarField>,
+    pub sigma_evals: Vec<E::ScalarField>,
+    pub z_eval: E::ScalarField,
+    pub z_next_eval: E::ScalarField,
+    pub t_evals: Vec<E::ScalarField>,
+
+    /// Lookup commitments and evaluations, if the circuit has tables
+    pub lookup: Option<LookupProof<E>>,
+
+    /// Batched opening of every polynomial at ζ
+    pub opening: OpeningProof<E>,
+    /// Batched opening of the wires, z and φ at ζ·ω
+    pub next_opening: OpeningProof<E>,
+}
+
+impl<E: Pairing> Proof<E> {
+    /// Commitments and claimed evaluations opened at ζ, in batching order.
+    ///
+    /// Lookup columns come last, if both the key and the proof have them.
+    pub fn zeta_openings(&self, vk: &VerifyingKey<E>) -> (Vec<Commitment<E>>, Vec<E::ScalarField>) {
+        let mut commitments: Vec<Commitment<E>> = self
+            .wire_comms
+            .iter()
+            .chain(&vk.selector_comms)
+            .chain(&vk.custom_selector_comms)
+            .chain(&vk.sigma_comms)
+            .chain([&self.z_comm])
+            .chain(&self.t_comms)
+            .cloned()
+            .collect();
+
+        let mut evals: Vec<E::ScalarField> = self
+            .wire_evals
+            .iter()
+            .chain(&self.selector_evals)
+            .chain(&self.custom_selector_evals)
+            .chain(&self.sigma_evals)
+            .chain([&self.z_eval])
+            .chain(&self.t_evals)
+            .copied()
+            .collect();
+
+        if let (Some(lookup_vk), Some(lookup)) = (&vk.lookup, &self.lookup) {
+            commitments.extend(lookup_vk.commitments().into_iter().cloned());
+            commitments.extend([lookup.m_comm.clone(), lookup.phi_comm.clone()]);
+            evals.extend(lookup.preprocessed_evals());
+            evals.extend([lookup.m_eval, lookup.phi_eval]);
+        }
+
+        (commitments, evals)
+    }
+
+    /// Commitments and claimed evaluations opened at ζ·ω, in batching order.
+    pub fn zeta_omega_openings(&self) -> (Vec<Commitment<E>>, Vec<E::ScalarField>) {
+        let mut commitments: Vec<Commitment<E>> = self
+            .wire_comms
+            .iter()
+            .chain([&self.z_comm])
+            .cloned()
+            .collect();
+        let mut evals: Vec<E::ScalarField> = self
+            .wire_next_evals
+            .iter()
+            .chain([&self.z_next_eval])
+            .copied()
+            .collect();
+
+        if let Some(lookup) = &self.lookup {
+            commitments.push(lookup.phi_comm.clone());
+            evals.push(lookup.phi_next_eval);
+        }
+
+        (commitments, evals)
+    }
+}
+
+/// Starts the Fiat-Shamir transcript shared by prover and verifier,
+/// binding it to the circuit and its public inputs.
+pub fn new_transcript<T: Transcript>(
+    vk: &VerifyingKey<T::Curve>,
+    public_inputs: &[Scalar<T>],
+) -> T {
+    let mut transcript = T::new(b"plonk");
+    transcript.append_scalar(b"n", &Scalar::<T>::from(vk.domain_size as u64));
+    for comm in vk
+        .selector_comms
+        .iter()
+        .chain(&vk.custom_selector_comms)
+        .chain(&vk.sigma_comms)
+    {
+        transcript.append_commitment(b"vk", comm);
+    }
+    if let Some(lookup) = &vk.lookup {
+        transcript.append_scalar(b"lookup_width", &Scalar::<T>::from(lookup.width as u64));
+        for comm in lookup.commitments() {
+            transcript.append_commitment(b"vk", comm);
+        }
+    }
+    for input in public_inputs {
+        transcript.append_scalar(b"pi", input);
+    }
+    transcript
+}
+
+/// Wire values column by column, padded with zeros to n rows.
+pub fn wire_columns<F: PrimeField>(cs: &ConstraintSystem<F>, n: usize) -> Vec<Vec<F>> {
+    (0..cs.num_wires())
+        .map(|j| {
+            let mut column = vec![F::zero(); n];
+            for (i, row) in cs.rows().iter().enumerate() {
+                column[i] = cs.value(row.wires[j]);
+            }
+            column
+        })
+        .collect()
 }
 
-pub fn prove(setup: &TrustedSetup, circuit: &SquareCircuit, witness: &Witness) -> Proof {
-    let wires = circuit.wire_values(witness);
+/// Lagrange basis polynomial Lᵢ evaluated at a point outside the domain:
+/// Lᵢ(ζ) = ωⁱ·(ζⁿ - 1) / (n·(ζ - ωⁱ))
+pub fn lagrange_eval<F: FftField>(domain: &Radix2EvaluationDomain<F>, i: usize, zeta: F) -> F {
+    let omega_i = domain.element(i);
+    let vanishing = domain.evaluate_vanishing_polynomial(zeta);
+    omega_i
+        * vanishing
+        * (domain.size_as_field_element() * (zeta - omega_i))
+            .inverse()
+            .unwrap()
+}
+
+/// Public input polynomial PI(ζ) = Σ -xₖ·L_rowₖ(ζ)
+pub fn public_input_eval<E: Pairing>(
+    vk: &VerifyingKey<E>,
+    public_inputs: &[E::ScalarField],
+    zeta: E::ScalarField,
+) -> E::ScalarField {
+    let domain = vk.domain();
+    vk.public_input_rows
+        .iter()
+        .zip(public_inputs)
+        .map(|(&row, input)| -*input * lagrange_eval(&domain, row, zeta))
+        .sum()
+}
+
+/// Proves with the default SHA-256 transcript.
+pub fn prove<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    pk: &ProvingKey<E>,
+    cs: &ConstraintSystem<E::ScalarField>,
+) -> Proof<E> {
+    prove_with::<Sha256Transcript<E>>(setup, pk, cs)
+}
+
+/// Proves with the Fiat-Shamir transcript `T`; the verifier must use the
+/// same one.
+pub fn prove_with<T: Transcript>(
+    setup: &TrustedSetup<T::Curve>,
+    pk: &ProvingKey<T::Curve>,
+    cs: &ConstraintSystem<Scalar<T>>,
+) -> Proof<T::Curve> {
+    let vk = &pk.vk;
+    let n = vk.domain_size;
+    let domain = vk.domain();
+    let public_inputs = cs.public_inputs();
+    let mut transcript: T = new_transcript(vk, &public_inputs);
+
+    // Round 1: wire polynomials
+    let wire_columns = wire_columns(cs, n);
+    let wire_polys: Vec<_> = cfg_iter!(wire_columns)
+        .map(|column| interpolate(&domain, column.clone()))
+        .collect();
+    let wire_comms: Vec<_> = cfg_iter!(wire_polys)
+        .map(|poly| commit(setup, poly))
+        .collect();
+    for comm in &wire_comms {
+        transcript.append_commitment(b"wire", comm);
+    }
+
+    // Lookup multiplicities only depend on the witness
+    let m = pk.lookup.as_ref().map(|lookup| {
+        let multiplicities = compute_multiplicities(lookup, &wire_columns);
+        let m_poly = interpolate(&domain, multiplicities.clone());
+        let m_comm = commit(setup, &m_poly);
+        transcript.append_commitment(b"m", &m_comm);
+        (multiplicities, m_poly, m_comm)
+    });
 
-    let a_poly = DensePolynomial::from_coefficients_vec(vec![wires.a]);
-    let b_poly = DensePolynomial::from_coefficients_vec(vec![wires.b]);
-    let c_poly = DensePolynomial::from_coefficients_vec(vec![wires.c]);
+    // Round 2: permutation accumulator and lookup running sum
+    let beta = transcript.challenge_scalar(b"beta");
+    let gamma = transcript.challenge_scalar(b"gamma");
+    let eta = transcript.challenge_scalar(b"eta");
+    let delta = transcript.challenge_scalar(b"delta");
+
+    let shifts = key::wire_shifts::<Scalar<T>>(vk.num_wires);
+    // Each row's factor of the accumulator, with the denominators inverted
+    // in one batch
+    let (numerators, mut denominators): (Vec<_>, Vec<_>) = cfg_into_iter!(0..n - 1)
+        .map(|i| {
+            let omega_i = domain.element(i);
+            let mut numerator = Scalar::<T>::one();
+            let mut denominator = Scalar::<T>::one();
+            for ((column, shift), sigma) in wire_columns.iter().zip(&shifts).zip(&pk.sigma_evals) {
+                numerator *= column[i] + beta * shift * omega_i + gamma;
+                denominator *= column[i] + beta * sigma[i] + gamma;
+            }
+            (numerator, denominator)
+        })
+        .unzip();
+    batch_inversion(&mut denominators);
+    let mut z_values = vec![Scalar::<T>::one(); n];
+    for (i, (numerator, denominator_inv)) in numerators.iter().zip(&denominators).enumerate() {
+        z_values[i + 1] = z_values[i] * numerator * denominator_inv;
+    }
+    let z_poly = interpolate(&domain, z_values);
+    let z_comm = commit(setup, &z_poly);
+    transcript.append_commitment(b"z", &z_comm);
 
-    let a_comm = commit(setup, &a_poly);
-    let b_comm = commit(setup, &b_poly);
-    let c_comm = commit(setup, &c_poly);
+    let phi = pk
+        .lookup
+        .as_ref()
+        .zip(m.as_ref())
+        .map(|(lookup, (multiplicities, ..))| {
+            let phi_values = compute_running_sum(lookup, &wire_columns, multiplicities, eta, delta);
+            let phi_poly = interpolate(&domain, phi_values);
+            let phi_comm = commit(setup, &phi_poly);
+            transcript.append_commitment(b"phi", &phi_comm);
+            (phi_poly, phi_comm)
+        });
+    let lookup_polys = m
+        .as_ref()
+        .zip(phi.as_ref())
+        .map(|((_, m_poly, _), (phi_poly, _))| (m_poly, phi_poly));
 
-    let t_poly = compute_constraint_polynomial(&a_poly, &b_poly, &c_poly);
+    // Round 3: quotient
+    let alpha = transcript.challenge_scalar(b"alpha");
+    let challenges = Challenges {
+        beta,
+        gamma,
+        alpha,
+        eta,
+        delta,
+    };
 
-    let vanishing_root = Fr::one();
-    let q_poly = divide_by_vanishing(&t_poly, vanishing_root);
+    let mut pi_values = vec![Scalar::<T>::zero(); n];
+    for (&row, input) in vk.public_input_rows.iter().zip(&public_inputs) {
+        pi_values[row] = -*input;
+    }
+    let pi_poly = interpolate(&domain, pi_values);
 
-    let q_comm = commit(setup, &q_poly);
+    let t_poly = compute_quotient(
+        pk,
+        &wire_polys,
+        &z_poly,
+        &pi_poly,
+        lookup_polys,
+        &challenges,
+    );
+    let t_chunks = split_quotient(&t_poly, n, vk.num_quotient_chunks());
+    let t_comms: Vec<_> = cfg_iter!(t_chunks)
+        .map(|chunk| commit(setup, chunk))
+        .collect();
+    for comm in &t_comms {
+        transcript.append_commitment(b"t", comm);
+    }
 
-    let zeta = Fr::from(7u64);
+    // Round 4: evaluations
+    let zeta = transcript.challenge_scalar(b"zeta");
+    let zeta_omega = zeta * domain.group_gen();
 
-    let a_eval = a_poly.evaluate(&zeta);
-    let b_eval = b_poly.evaluate(&zeta);
-    let c_eval = c_poly.evaluate(&zeta);
-    let q_eval = q_poly.evaluate(&zeta);
+    let evaluate_all = |polys: &[DensePolynomial<Scalar<T>>], point: Scalar<T>| -> Vec<Scalar<T>> {
+        cfg_iter!(polys).map(|poly| poly.evaluate(&point)).collect()
+    };
+    let wire_evals = evaluate_all(&wire_polys, zeta);
+    let wire_next_evals = evaluate_all(&wire_polys, zeta_omega);
+    let selector_evals = evaluate_all(&pk.selector_polys, zeta);
+    let custom_selector_evals = evaluate_all(&pk.custom_selector_polys, zeta);
+    let sigma_evals = evaluate_all(&pk.sigma_polys, zeta);
+    let z_eval = z_poly.evaluate(&zeta);
+    let z_next_eval = z_poly.evaluate(&zeta_omega);
+    let t_evals = evaluate_all(&t_chunks, zeta);
+    let lookup = pk.lookup.as_ref().zip(m.as_ref().zip(phi.as_ref())).map(
+        |(lookup, ((_, m_poly, m_comm), (phi_poly, phi_comm)))| LookupProof {
+            m_comm: m_comm.clone(),
+            phi_comm: phi_comm.clone(),
+            q_lookup_eval: lookup.q_lookup_poly.evaluate(&zeta),
+            q_table_eval: lookup.q_table_poly.evaluate(&zeta),
+            table_tag_eval: lookup.table_tag_poly.evaluate(&zeta),
+            table_evals: evaluate_all(&lookup.table_polys, zeta),
+            m_eval: m_poly.evaluate(&zeta),
+            phi_eval: phi_poly.evaluate(&zeta),
+            phi_next_eval: phi_poly.evaluate(&zeta_omega),
+        },
+    );
 
-    let a_opening = open(setup, &a_poly, zeta, a_eval);
-    let b_opening = open(setup, &b_poly, zeta, b_eval);
-    let c_opening = open(setup, &c_poly, zeta, c_eval);
-    let q_opening = open(setup, &q_poly, zeta, q_eval);
+    for eval in wire_evals
+        .iter()
+        .chain(&wire_next_evals)
+        .chain(&selector_evals)
+        .chain(&custom_selector_evals)
+        .chain(&sigma_evals)
+        .chain([&z_eval, &z_next_eval])
+        .chain(&t_evals)
+    {
+        transcript.append_scalar(b"eval", eval);
+    }
+    if let Some(lookup) = &lookup {
+        for eval in lookup_evals(lookup) {
+            transcript.append_scalar(b"eval", &eval);
+        }
+    }
+
+    // Round 5: batched openings
+    let v = transcript.challenge_scalar(b"v");
+
+    let mut zeta_polys: Vec<&DensePolynomial<Scalar<T>>> = wire_polys
+        .iter()
+        .chain(&pk.selector_polys)
+        .chain(&pk.custom_selector_polys)
+        .chain(&pk.sigma_polys)
+        .chain([&z_poly])
+        .chain(&t_chunks)
+        .collect();
+    let mut zeta_omega_polys: Vec<&DensePolynomial<Scalar<T>>> =
+        wire_polys.iter().chain([&z_poly]).collect();
+    if let (Some(lookup), Some((m_poly, phi_poly))) = (&pk.lookup, lookup_polys) {
+        zeta_polys.extend(lookup.polys());
+        zeta_polys.extend([m_poly, phi_poly]);
+        zeta_omega_polys.push(phi_poly);
+    }
+
+    let opening = batch_open(setup, &zeta_polys, zeta, v);
+    let next_opening = batch_open(setup, &zeta_omega_polys, zeta_omega, v);
 
     Proof {
-        a_comm,
-        b_comm,
-        c_comm,
-        q_comm,
-        a_eval,
-        b_eval,
-        c_eval,
-        q_eval,
-        a_opening,
-        b_opening,
-        c_opening,
-        q_opening,
+        wire_comms,
+        z_comm,
+        t_comms,
+        wire_evals,
+        wire_next_evals,
+        selector_evals,
+        custom_selector_evals,
+        sigma_evals,
+        z_eval,
+        z_next_eval,
+        t_evals,
+        lookup,
+        opening,
+        next_opening,
     }
 }
 
-pub fn compute_constraint_polynomial(
-    a: &DensePolynomial<Fr>,
-    b: &DensePolynomial<Fr>,
-    c: &DensePolynomial<Fr>,
-) -> DensePolynomial<Fr> {
+/// Lookup evaluations in the order they are absorbed by the transcript.
+pub fn lookup_evals<E: Pairing>(lookup: &LookupProof<E>) -> Vec<E::ScalarField> {
+    let mut evals = lookup.preprocessed_evals();
+    evals.extend([lookup.m_eval, lookup.phi_eval, lookup.phi_next_eval]);
+    evals
+}
+
+/// The lookup's m(X) and φ(X), when the circuit has lookups.
+pub type PolyPair<'a, F> = (&'a DensePolynomial<F>, &'a DensePolynomial<F>);
+
+/// Computes t(X) = combined(X) / Z_H(X) on a coset of a larger domain,
+/// where the division is pointwise and Z_H never vanishes.
+pub fn compute_quotient<E: Pairing>(
+    pk: &ProvingKey<E>,
+    wire_polys: &[DensePolynomial<E::ScalarField>],
+    z_poly: &DensePolynomial<E::ScalarField>,
+    pi_poly: &DensePolynomial<E::ScalarField>,
+    lookup_polys: Option<PolyPair<E::ScalarField>>,
+    challenges: &Challenges<E::ScalarField>,
+) -> DensePolynomial<E::ScalarField> {
+    let vk = &pk.vk;
+    let n = vk.domain_size;
+    let domain = vk.domain();
+    let quotient_size = (vk.quotient_degree() * n).next_power_of_two();
+    let coset = Radix2EvaluationDomain::<E::ScalarField>::new(quotient_size)
+        .and_then(|d| d.get_coset(E::ScalarField::GENERATOR))
+        .expect("quotient domain fits in the field's two-adicity");
+    // ω·X on the coset is the point quotient_size / n steps ahead
+    let next = quotient_size / n;
+
+    let on_coset = |poly: &DensePolynomial<E::ScalarField>| coset.fft(poly.coeffs());
+
+    let wires: Vec<Vec<E::ScalarField>> = cfg_iter!(wire_polys).map(on_coset).collect();
+    let selectors: Vec<Vec<E::ScalarField>> = cfg_iter!(pk.selector_polys).map(on_coset).collect();
+    let custom_selectors: Vec<Vec<E::ScalarField>> =
+        cfg_iter!(pk.custom_selector_polys).map(on_coset).collect();
+    let sigmas: Vec<Vec<E::ScalarField>> = cfg_iter!(pk.sigma_polys).map(on_coset).collect();
+    let z = on_coset(z_poly);
+    let pi = on_coset(pi_poly);
+
+    let mut l1_values = vec![E::ScalarField::zero(); n];
+    l1_values[0] = E::ScalarField::one();
+    let l1 = on_coset(&interpolate(&domain, l1_values));
+
+    // q_lookup, q_table, table tag, table columns…, m, φ
+    let lookup_columns: Option<Vec<Vec<E::ScalarField>>> = pk
+        .lookup
+        .as_ref()
+        .zip(lookup_polys)
+        .map(|(lookup, (m_poly, phi_poly))| {
+            lookup
+                .polys()
+                .into_iter()
+                .chain([m_poly, phi_poly])
+                .map(on_coset)
+                .collect()
+        });
+
+    let quotient_evals: Vec<E::ScalarField> = cfg_into_iter!(0..quotient_size)
+        .map(|i| {
+            let x = coset.element(i);
+            let at = |column: &Vec<E::ScalarField>| column[i];
+            let wires_here: Vec<E::ScalarField> = wires.iter().map(at).collect();
+            let wires_next: Vec<E::ScalarField> = wires
+                .iter()
+                .map(|w| w[(i + next) % quotient_size])
+                .collect();
+            let selectors_here: Vec<E::ScalarField> = selectors.iter().map(at).collect();
+            let custom_here: Vec<E::ScalarField> = custom_selectors.iter().map(at).collect();
+            let sigmas_here: Vec<E::ScalarField> = sigmas.iter().map(at).collect();
+            let lookup_here: Option<Vec<E::ScalarField>> = lookup_columns
+                .as_ref()
+                .map(|columns| columns.iter().map(at).collect());
+            let lookup = lookup_here.as_ref().map(|here| {
+                let phi = lookup_columns.as_ref().unwrap().last().unwrap();
+                let width = here.len() - 5;
+                LookupValues {
+                    q_lookup: here[0],
+                    q_table: here[1],
+                    table_tag: here[2],
+                    table: &here[3..3 + width],
+                    m: here[3 + width],
+                    phi: here[4 + width],
+                    phi_next: phi[(i + next) % quotient_size],
+                }
+            });
+
+            let values = ColumnValues {
+                x,
+                wires: &wires_here,
+                wires_next: &wires_next,
+                selectors: &selectors_here,
+                custom_selectors: &custom_here,
+                sigmas: &sigmas_here,
+                z: z[i],
+                z_next: z[(i + next) % quotient_size],
+                pi: pi[i],
+                l1: l1[i],
+                lookup,
+            };
+
+            let vanishing = domain.evaluate_vanishing_polynomial(x);
+            combined_constraint(vk, &values, challenges) * vanishing.inverse().unwrap()
+        })
+        .collect();
+
+    DensePolynomial::from_coefficients_vec(coset.ifft(&quotient_evals))
+}
+
+/// a·b - c over whole polynomials: the constraint of a single
+/// multiplication gate, which vanishes wherever the gate holds.
+pub fn compute_constraint_polynomial<F: FftField>(
+    a: &DensePolynomial<F>,
+    b: &DensePolynomial<F>,
+    c: &DensePolynomial<F>,
+) -> DensePolynomial<F> {
     let ab = a * b;
     &ab - c
 }
 
-pub fn divide_by_vanishing(poly: &DensePolynomial<Fr>, root: Fr) -> DensePolynomial<Fr> {
+/// Divides by X - root, dropping the remainder.
+pub fn divide_by_vanishing<F: Field>(poly: &DensePolynomial<F>, root: F) -> DensePolynomial<F> {
     let coeffs = poly.coeffs();
 
-    if coeffs.is_empty() {
-        return DensePolynomial::from_coefficients_vec(vec![]);
-    }
-
-    if coeffs.len() == 1 {
+    if coeffs.len() <= 1 {
         return DensePolynomial::from_coefficients_vec(vec![]);
     }
 
     let n = coeffs.len();
-    let mut quotient_coeffs = vec![Fr::from(0u64); n - 1];
+    let mut quotient_coeffs = vec![F::zero(); n - 1];
 
     quotient_coeffs[n - 2] = coeffs[n - 1];
 
@@ -101,3 +527,19 @@ pub fn divide_by_vanishing(poly: &DensePolynomial<Fr>, root: Fr) -> DensePolynom
 
     DensePolynomial::from_coefficients_vec(quotient_coeffs)
 }
+
+/// Splits t into `num_chunks` polynomials of n coefficients so that
+/// t(X) = Σ Xⁱⁿ·tᵢ(X). Coefficients past the last chunk are dropped;
+/// they are zero whenever the witness satisfies the circuit.
+pub fn split_quotient<F: PrimeField>(
+    t_poly: &DensePolynomial<F>,
+    n: usize,
+    num_chunks: usize,
+) -> Vec<DensePolynomial<F>> {
+    let mut coeffs = t_poly.coeffs().to_vec();
+    coeffs.resize(n * num_chunks, F::zero());
+    coeffs
+        .chunks(n)
+        .map(DensePolynomial::from_coefficients_slice)
+        .collect()
+}
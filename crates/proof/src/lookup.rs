@@ -0,0 +1,272 @@
+//! logUp lookup argument.
+//!
+//! Query rows compress their wires into f = q_table + η·w₀ + η²·w₁ + …,
+//! the tables into t = tag + η·t₀ + η²·t₁ + …. With multiplicities m
+//! counting how often each table row is queried, the queries are all in
+//! the table exactly when
+//!
+//! ```text
+//! Σ q_lookup / (δ + f) = Σ m / (δ + t)
+//! ```
+//!
+//! over the domain. A running sum φ proves it: the step identity
+//! (φ(ωX) - φ(X))·(δ + f)·(δ + t) = q_lookup·(δ + t) - m·(δ + f)
+//! telescopes to zero around the whole cyclic domain.
+
+use std::collections::HashMap;
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::{Radix2EvaluationDomain, univariate::DensePolynomial};
+use circuit::constraint_system::ConstraintSystem;
+use kzg::{Commitment, commit};
+use setup::TrustedSetup;
+
+use crate::key::interpolate;
+
+/// Preprocessed lookup columns, committed in the verifying key.
+#[derive(Clone, Debug)]
+pub struct LookupVerifyingKey<E: Pairing = Bls12_381> {
+    /// Number of wires each query compresses
+    pub width: usize,
+    /// 1 on rows that perform a lookup
+    pub q_lookup_comm: Commitment<E>,
+    /// Tag of the table queried on each row
+    pub q_table_comm: Commitment<E>,
+    /// Tag of the table each table row belongs to
+    pub table_tag_comm: Commitment<E>,
+    /// Table columns, all tables stacked on top of each other
+    pub table_comms: Vec<Commitment<E>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LookupProvingKey<E: Pairing = Bls12_381> {
+    pub vk: LookupVerifyingKey<E>,
+    pub q_lookup_poly: DensePolynomial<E::ScalarField>,
+    pub q_table_poly: DensePolynomial<E::ScalarField>,
+    pub table_tag_poly: DensePolynomial<E::ScalarField>,
+    pub table_polys: Vec<DensePolynomial<E::ScalarField>>,
+    /// Table tag queried on each row, zero on rows without a lookup
+    pub query_tags: Vec<E::ScalarField>,
+    /// Table rows over the domain as (tag, values), used for multiplicities
+    pub table_rows: Vec<Vec<E::ScalarField>>,
+}
+
+impl<E: Pairing> LookupProvingKey<E> {
+    /// Preprocessed polynomials in the order they are opened at ζ.
+    pub fn polys(&self) -> Vec<&DensePolynomial<E::ScalarField>> {
+        [
+            &self.q_lookup_poly,
+            &self.q_table_poly,
+            &self.table_tag_poly,
+        ]
+        .into_iter()
+        .chain(&self.table_polys)
+        .collect()
+    }
+}
+
+impl<E: Pairing> LookupVerifyingKey<E> {
+    /// Preprocessed commitments in the order they are opened at ζ.
+    pub fn commitments(&self) -> Vec<&Commitment<E>> {
+        [
+            &self.q_lookup_comm,
+            &self.q_table_comm,
+            &self.table_tag_comm,
+        ]
+        .into_iter()
+        .chain(&self.table_comms)
+        .collect()
+    }
+}
+
+/// Lookup part of a proof.
+#[derive(Clone, Debug)]
+pub struct LookupProof<E: Pairing = Bls12_381> {
+    /// Commitment to the multiplicities m
+    pub m_comm: Commitment<E>,
+    /// Commitment to the running sum φ
+    pub phi_comm: Commitment<E>,
+
+    pub q_lookup_eval: E::ScalarField,
+    pub q_table_eval: E::ScalarField,
+    pub table_tag_eval: E::ScalarField,
+    pub table_evals: Vec<E::ScalarField>,
+    pub m_eval: E::ScalarField,
+    pub phi_eval: E::ScalarField,
+    pub phi_next_eval: E::ScalarField,
+}
+
+impl<E: Pairing> LookupProof<E> {
+    /// Preprocessed evaluations in the order they are opened at ζ.
+    pub fn preprocessed_evals(&self) -> Vec<E::ScalarField> {
+        [self.q_lookup_eval, self.q_table_eval, self.table_tag_eval]
+            .into_iter()
+            .chain(self.table_evals.iter().copied())
+            .collect()
+    }
+}
+
+/// Lookup columns evaluated at one point X.
+#[derive(Clone, Debug)]
+pub struct LookupValues<'a, F: PrimeField = Fr> {
+    pub q_lookup: F,
+    pub q_table: F,
+    pub table_tag: F,
+    pub table: &'a [F],
+    pub m: F,
+    pub phi: F,
+    /// Running sum at ω·X
+    pub phi_next: F,
+}
+
+/// Lays out the circuit's tables and query selectors, or returns `None`
+/// when the circuit has no tables.
+pub fn preprocess_lookup<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    cs: &ConstraintSystem<E::ScalarField>,
+    domain: &Radix2EvaluationDomain<E::ScalarField>,
+) -> Option<LookupProvingKey<E>> {
+    if cs.tables().is_empty() {
+        return None;
+    }
+
+    let n = cs.domain_size();
+    let width = cs.lookup_width();
+
+    let mut q_lookup = vec![E::ScalarField::zero(); n];
+    let mut q_table = vec![E::ScalarField::zero(); n];
+    for (i, row) in cs.rows().iter().enumerate() {
+        if let Some(table) = row.lookup {
+            q_lookup[i] = E::ScalarField::one();
+            q_table[i] = table.tag();
+        }
+    }
+
+    // Rows past the last table hold (0, 0, …), which no query can match
+    let mut table_rows = vec![vec![E::ScalarField::zero(); width + 1]; n];
+    let mut i = 0;
+    for (index, table) in cs.tables().iter().enumerate() {
+        let tag = E::ScalarField::from(index as u64 + 1);
+        for row in &table.rows {
+            table_rows[i][0] = tag;
+            table_rows[i][1..=row.len()].copy_from_slice(row);
+            i += 1;
+        }
+    }
+
+    let column = |k: usize| table_rows.iter().map(|row| row[k]).collect::<Vec<_>>();
+    let table_tag_poly = interpolate(domain, column(0));
+    let table_polys: Vec<_> = (1..=width)
+        .map(|k| interpolate(domain, column(k)))
+        .collect();
+    let q_lookup_poly = interpolate(domain, q_lookup);
+    let q_table_poly = interpolate(domain, q_table.clone());
+
+    let vk = LookupVerifyingKey {
+        width,
+        q_lookup_comm: commit(setup, &q_lookup_poly),
+        q_table_comm: commit(setup, &q_table_poly),
+        table_tag_comm: commit(setup, &table_tag_poly),
+        table_comms: table_polys.iter().map(|poly| commit(setup, poly)).collect(),
+    };
+
+    Some(LookupProvingKey {
+        vk,
+        q_lookup_poly,
+        q_table_poly,
+        table_tag_poly,
+        table_polys,
+        query_tags: q_table,
+        table_rows,
+    })
+}
+
+/// Counts how often each table row is queried. Queries missing from the
+/// table are not counted, which leaves the sums unbalanced.
+pub fn compute_multiplicities<E: Pairing>(
+    pk: &LookupProvingKey<E>,
+    wire_columns: &[Vec<E::ScalarField>],
+) -> Vec<E::ScalarField> {
+    let mut index: HashMap<&[E::ScalarField], usize> = HashMap::new();
+    for (i, row) in pk.table_rows.iter().enumerate().rev() {
+        index.insert(row.as_slice(), i);
+    }
+
+    let mut counts = vec![0u64; pk.table_rows.len()];
+    for (i, tag) in pk.query_tags.iter().enumerate() {
+        if tag.is_zero() {
+            continue;
+        }
+        let query = query_at(pk, wire_columns, i);
+        if let Some(&position) = index.get(query.as_slice()) {
+            counts[position] += 1;
+        }
+    }
+
+    counts.into_iter().map(E::ScalarField::from).collect()
+}
+
+/// (tag, w₀, w₁, …) on row i.
+fn query_at<E: Pairing>(
+    pk: &LookupProvingKey<E>,
+    wire_columns: &[Vec<E::ScalarField>],
+    i: usize,
+) -> Vec<E::ScalarField> {
+    let mut query = vec![pk.query_tags[i]];
+    query.extend(wire_columns[..pk.vk.width].iter().map(|column| column[i]));
+    query
+}
+
+/// Compresses (tag, v₀, v₁, …) into tag + η·v₀ + η²·v₁ + …
+pub fn compress<F: PrimeField>(tag: F, values: &[F], eta: F) -> F {
+    let mut result = tag;
+    let mut power = eta;
+    for value in values {
+        result += power * value;
+        power *= eta;
+    }
+    result
+}
+
+/// Builds the running sum φ with φ(1) = 0 and
+/// φ(ωⁱ⁺¹) = φ(ωⁱ) + q_lookupᵢ/(δ + fᵢ) - mᵢ/(δ + tᵢ).
+pub fn compute_running_sum<E: Pairing>(
+    pk: &LookupProvingKey<E>,
+    wire_columns: &[Vec<E::ScalarField>],
+    multiplicities: &[E::ScalarField],
+    eta: E::ScalarField,
+    delta: E::ScalarField,
+) -> Vec<E::ScalarField> {
+    let n = multiplicities.len();
+
+    let mut phi = vec![E::ScalarField::zero(); n];
+    for i in 0..n - 1 {
+        let query = query_at(pk, wire_columns, i);
+        let f = compress(query[0], &query[1..], eta);
+        let t = compress(pk.table_rows[i][0], &pk.table_rows[i][1..], eta);
+        let q_lookup = if query[0].is_zero() {
+            E::ScalarField::zero()
+        } else {
+            E::ScalarField::one()
+        };
+
+        phi[i + 1] = phi[i] + q_lookup * (delta + f).inverse().unwrap()
+            - multiplicities[i] * (delta + t).inverse().unwrap();
+    }
+    phi
+}
+
+/// Evaluates (φ(ωX) - φ(X))·(δ + f)·(δ + t) - q_lookup·(δ + t) + m·(δ + f).
+pub fn lookup_constraint<F: PrimeField>(
+    values: &LookupValues<F>,
+    wires: &[F],
+    eta: F,
+    delta: F,
+) -> F {
+    let f = delta + compress(values.q_table, &wires[..values.table.len()], eta);
+    let t = delta + compress(values.table_tag, values.table, eta);
+
+    (values.phi_next - values.phi) * f * t - values.q_lookup * t + values.m * f
+}
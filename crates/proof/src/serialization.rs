@@ -0,0 +1,324 @@
+//! Binary encoding of proofs and verifying keys.
+//!
+//! Fields are written in declaration order with arkworks' compressed
+//! encoding: points as compressed G1 elements, scalars as 32 bytes, counts
+//! and lengths as u64, lists as their length followed by the items.
+//! Custom gates are stored by name and parameters and rebuilt with
+//! [`circuit::custom_gates::builtin`] on reading. Files with a `.json`
+//! extension use the layout in [`crate::json`] instead.
+//!
+//! `read` and `write` work over any pairing curve; `load` and `save` over
+//! the curves the JSON layout names.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{FftField, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use circuit::constraint_system::DEFAULT_NUM_WIRES;
+use circuit::custom_gates::builtin;
+pub(crate) use circuit::serialization::invalid_data;
+use circuit::serialization::read_vec;
+use kzg::{Commitment, OpeningProof};
+
+use crate::json::JsonCurve;
+use crate::lookup::{LookupProof, LookupVerifyingKey};
+use crate::{Proof, VerifyingKey};
+
+impl<E: Pairing> Proof<E> {
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let r = &mut reader;
+        let wire_comms = read_commitments(r)?;
+        let z_comm = read_commitment(r)?;
+        let t_comms = read_commitments(r)?;
+        let wire_evals = read_vec(r)?;
+        let wire_next_evals = read_vec(r)?;
+        let selector_evals = read_vec(r)?;
+        let custom_selector_evals = read_vec(r)?;
+        let sigma_evals = read_vec(r)?;
+        let z_eval = read(r)?;
+        let z_next_eval = read(r)?;
+        let t_evals = read_vec(r)?;
+        let lookup = match read::<bool>(r)? {
+            true => Some(LookupProof {
+                m_comm: read_commitment(r)?,
+                phi_comm: read_commitment(r)?,
+                q_lookup_eval: read(r)?,
+                q_table_eval: read(r)?,
+                table_tag_eval: read(r)?,
+                table_evals: read_vec(r)?,
+                m_eval: read(r)?,
+                phi_eval: read(r)?,
+                phi_next_eval: read(r)?,
+            }),
+            false => None,
+        };
+        let opening = OpeningProof(read(r)?);
+        let next_opening = OpeningProof(read(r)?);
+
+        Ok(Proof {
+            wire_comms,
+            z_comm,
+            t_comms,
+            wire_evals,
+            wire_next_evals,
+            selector_evals,
+            custom_selector_evals,
+            sigma_evals,
+            z_eval,
+            z_next_eval,
+            t_evals,
+            lookup,
+            opening,
+            next_opening,
+        })
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        let w = &mut writer;
+        write_commitments(w, &self.wire_comms)?;
+        write(w, &self.z_comm.0)?;
+        write_commitments(w, &self.t_comms)?;
+        write(w, &self.wire_evals)?;
+        write(w, &self.wire_next_evals)?;
+        write(w, &self.selector_evals)?;
+        write(w, &self.custom_selector_evals)?;
+        write(w, &self.sigma_evals)?;
+        write(w, &self.z_eval)?;
+        write(w, &self.z_next_eval)?;
+        write(w, &self.t_evals)?;
+        write(w, &self.lookup.is_some())?;
+        if let Some(lookup) = &self.lookup {
+            write(w, &lookup.m_comm.0)?;
+            write(w, &lookup.phi_comm.0)?;
+            write(w, &lookup.q_lookup_eval)?;
+            write(w, &lookup.q_table_eval)?;
+            write(w, &lookup.table_tag_eval)?;
+            write(w, &lookup.table_evals)?;
+            write(w, &lookup.m_eval)?;
+            write(w, &lookup.phi_eval)?;
+            write(w, &lookup.phi_next_eval)?;
+        }
+        write(w, &self.opening.0)?;
+        write(w, &self.next_opening.0)
+    }
+}
+
+impl<E, P> Proof<E>
+where
+    E: JsonCurve<G1Affine = Affine<P>>,
+    P: SWCurveConfig<BaseField: PrimeField>,
+{
+    /// Loads a file: JSON for a `.json` extension, binary otherwise.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if is_json(path) {
+            Self::from_json(&fs::read_to_string(path)?)
+        } else {
+            Self::read(BufReader::new(File::open(path)?))
+        }
+    }
+
+    /// Saves a file in the format [`load`](Self::load) picks from the
+    /// extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if is_json(path) {
+            return fs::write(path, self.to_json());
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+}
+
+impl<E: Pairing> VerifyingKey<E> {
+    /// Reads a key and checks that its parts fit together.
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let r = &mut reader;
+        let domain_size: u64 = read(r)?;
+        let num_wires: u64 = read(r)?;
+        let public_input_rows: Vec<u64> = read_vec(r)?;
+        let selector_comms = read_commitments(r)?;
+        let custom_selector_comms = read_commitments(r)?;
+        let sigma_comms = read_commitments(r)?;
+        let num_gates: u64 = read(r)?;
+        let mut custom_gates = Vec::new();
+        for _ in 0..num_gates {
+            let name = read_string(r)?;
+            let num_params: u64 = read(r)?;
+            let mut params = Vec::new();
+            for _ in 0..num_params {
+                params.push((read_string(r)?, read(r)?));
+            }
+            custom_gates.push(builtin(&name, &params).map_err(invalid_data)?);
+        }
+        let lookup = match read::<bool>(r)? {
+            true => Some(LookupVerifyingKey {
+                width: read::<u64>(r)? as usize,
+                q_lookup_comm: read_commitment(r)?,
+                q_table_comm: read_commitment(r)?,
+                table_tag_comm: read_commitment(r)?,
+                table_comms: read_commitments(r)?,
+            }),
+            false => None,
+        };
+
+        let vk = VerifyingKey {
+            domain_size: domain_size as usize,
+            num_wires: num_wires as usize,
+            public_input_rows: public_input_rows.iter().map(|&row| row as usize).collect(),
+            selector_comms,
+            custom_selector_comms,
+            sigma_comms,
+            custom_gates,
+            lookup,
+        };
+        vk.check_shape()?;
+        Ok(vk)
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        let w = &mut writer;
+        write(w, &(self.domain_size as u64))?;
+        write(w, &(self.num_wires as u64))?;
+        let rows: Vec<u64> = self
+            .public_input_rows
+            .iter()
+            .map(|&row| row as u64)
+            .collect();
+        write(w, &rows)?;
+        write_commitments(w, &self.selector_comms)?;
+        write_commitments(w, &self.custom_selector_comms)?;
+        write_commitments(w, &self.sigma_comms)?;
+        write(w, &(self.custom_gates.len() as u64))?;
+        for gate in &self.custom_gates {
+            write(w, &gate.name().as_bytes().to_vec())?;
+            write(w, &(gate.params().len() as u64))?;
+            for (key, value) in gate.params() {
+                write(w, &key.as_bytes().to_vec())?;
+                write(w, &value)?;
+            }
+        }
+        write(w, &self.lookup.is_some())?;
+        if let Some(lookup) = &self.lookup {
+            write(w, &(lookup.width as u64))?;
+            write(w, &lookup.q_lookup_comm.0)?;
+            write(w, &lookup.q_table_comm.0)?;
+            write(w, &lookup.table_tag_comm.0)?;
+            write_commitments(w, &lookup.table_comms)?;
+        }
+        Ok(())
+    }
+
+    /// Rejects keys whose column counts disagree with each other.
+    pub(crate) fn check_shape(&self) -> io::Result<()> {
+        let num_custom_selectors: usize = self
+            .custom_gates
+            .iter()
+            .map(|gate| gate.selectors().len())
+            .sum();
+        let num_selectors = self.num_wires.checked_add(2);
+        let problem = if !self.domain_size.is_power_of_two()
+            || !(2..=1 << E::ScalarField::TWO_ADICITY).contains(&self.domain_size)
+        {
+            Some("domain size is not a power of two the field supports")
+        } else if self.num_wires < DEFAULT_NUM_WIRES {
+            Some("rows need at least 3 wires")
+        } else if Some(self.selector_comms.len()) != num_selectors {
+            Some("wrong number of selector commitments")
+        } else if self.sigma_comms.len() != self.num_wires {
+            Some("wrong number of permutation commitments")
+        } else if self.custom_selector_comms.len() != num_custom_selectors {
+            Some("wrong number of custom selector commitments")
+        } else if self
+            .public_input_rows
+            .iter()
+            .any(|&row| row >= self.domain_size)
+        {
+            Some("public input row outside the domain")
+        } else if self
+            .lookup
+            .as_ref()
+            .is_some_and(|lookup| !(1..=self.num_wires).contains(&lookup.width))
+        {
+            Some("lookup width outside 1 to the number of wires")
+        } else if self
+            .lookup
+            .as_ref()
+            .is_some_and(|lookup| lookup.table_comms.len() != lookup.width)
+        {
+            Some("wrong number of table commitments")
+        } else {
+            None
+        };
+        match problem {
+            Some(problem) => Err(invalid_data(format!("Invalid verifying key: {problem}"))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<E, P> VerifyingKey<E>
+where
+    E: JsonCurve<G1Affine = Affine<P>>,
+    P: SWCurveConfig<BaseField: PrimeField>,
+{
+    /// Loads a file: JSON for a `.json` extension, binary otherwise.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if is_json(path) {
+            Self::from_json(&fs::read_to_string(path)?)
+        } else {
+            Self::read(BufReader::new(File::open(path)?))
+        }
+    }
+
+    /// Saves a file in the format [`load`](Self::load) picks from the
+    /// extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if is_json(path) {
+            return fs::write(path, self.to_json());
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+fn read<T: CanonicalDeserialize>(reader: &mut impl Read) -> io::Result<T> {
+    T::deserialize_compressed(reader).map_err(invalid_data)
+}
+
+fn write<T: CanonicalSerialize>(writer: &mut impl Write, value: &T) -> io::Result<()> {
+    value.serialize_compressed(writer).map_err(invalid_data)
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    String::from_utf8(read_vec(reader)?).map_err(invalid_data)
+}
+
+fn read_commitment<E: Pairing>(reader: &mut impl Read) -> io::Result<Commitment<E>> {
+    Ok(Commitment(read(reader)?))
+}
+
+fn read_commitments<E: Pairing>(reader: &mut impl Read) -> io::Result<Vec<Commitment<E>>> {
+    let points: Vec<E::G1Affine> = read_vec(reader)?;
+    Ok(points.into_iter().map(Commitment).collect())
+}
+
+fn write_commitments<E: Pairing>(
+    writer: &mut impl Write,
+    comms: &[Commitment<E>],
+) -> io::Result<()> {
+    let points: Vec<E::G1Affine> = comms.iter().map(|comm| comm.0).collect();
+    write(writer, &points)
+}
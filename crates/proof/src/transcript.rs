@@ -0,0 +1,230 @@
+use std::marker::PhantomData;
+
+use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine};
+use ark_ec::AffineRepr;
+use ark_ec::pairing::Pairing;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_serialize::CanonicalSerialize;
+use circuit::poseidon::{PoseidonParams, label_element};
+use kzg::Commitment;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+/// Fiat-Shamir transcript shared by prover and verifier.
+///
+/// Messages are absorbed with a label; squeezing a challenge depends on
+/// everything absorbed before it. [`crate::prove_with`] and
+/// `verifier::verify_with` take the backend as a type parameter, and the
+/// backend fixes the curve the proof is over.
+pub trait Transcript {
+    type Curve: Pairing;
+
+    fn new(label: &[u8]) -> Self;
+
+    fn append_commitment(&mut self, label: &[u8], commitment: &Commitment<Self::Curve>);
+
+    fn append_scalar(&mut self, label: &[u8], scalar: &Scalar<Self>);
+
+    fn challenge_scalar(&mut self, label: &[u8]) -> Scalar<Self>;
+}
+
+/// Scalar field of a transcript's curve.
+pub type Scalar<T> = <<T as Transcript>::Curve as Pairing>::ScalarField;
+
+/// Transcript backed by SHA-256, the default.
+///
+/// Messages are appended to a buffer in arkworks' compressed encoding;
+/// squeezing a challenge hashes the buffer and restarts it from the
+/// digest.
+#[derive(Clone, Debug)]
+pub struct Sha256Transcript<E: Pairing = Bls12_381> {
+    buffer: Vec<u8>,
+    curve: PhantomData<E>,
+}
+
+impl<E: Pairing> Sha256Transcript<E> {
+    /// Bytes absorbed since the last challenge.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl<E: Pairing> Transcript for Sha256Transcript<E> {
+    type Curve = E;
+
+    fn new(label: &[u8]) -> Self {
+        Sha256Transcript {
+            buffer: label.to_vec(),
+            curve: PhantomData,
+        }
+    }
+
+    fn append_commitment(&mut self, label: &[u8], commitment: &Commitment<E>) {
+        self.buffer.extend_from_slice(label);
+        commitment
+            .0
+            .serialize_compressed(&mut self.buffer)
+            .expect("serializing into a Vec cannot fail");
+    }
+
+    fn append_scalar(&mut self, label: &[u8], scalar: &E::ScalarField) {
+        self.buffer.extend_from_slice(label);
+        scalar
+            .serialize_compressed(&mut self.buffer)
+            .expect("serializing into a Vec cannot fail");
+    }
+
+    fn challenge_scalar(&mut self, label: &[u8]) -> E::ScalarField {
+        self.buffer.extend_from_slice(label);
+        let digest = Sha256::digest(&self.buffer);
+        self.buffer = digest.to_vec();
+        E::ScalarField::from_be_bytes_mod_order(&digest)
+    }
+}
+
+/// Transcript backed by Keccak-256, in the byte layout an EVM contract
+/// builds in memory.
+///
+/// Points are absorbed as [`evm_g1`] encodes them, the input format of
+/// the curve's precompiles, and scalars as 32-byte big-endian words, so a
+/// verifier contract hashes its calldata as is. Challenges work as in
+/// [`Sha256Transcript`].
+#[derive(Clone, Debug)]
+pub struct Keccak256Transcript<E: Pairing = Bls12_381> {
+    buffer: Vec<u8>,
+    curve: PhantomData<E>,
+}
+
+impl<E: Pairing> Keccak256Transcript<E> {
+    /// Bytes absorbed since the last challenge.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl<E: Pairing> Transcript for Keccak256Transcript<E> {
+    type Curve = E;
+
+    fn new(label: &[u8]) -> Self {
+        Keccak256Transcript {
+            buffer: label.to_vec(),
+            curve: PhantomData,
+        }
+    }
+
+    fn append_commitment(&mut self, label: &[u8], commitment: &Commitment<E>) {
+        self.buffer.extend_from_slice(label);
+        self.buffer.extend(evm_g1::<E>(&commitment.0));
+    }
+
+    fn append_scalar(&mut self, label: &[u8], scalar: &E::ScalarField) {
+        self.buffer.extend_from_slice(label);
+        self.buffer.extend(scalar.into_bigint().to_bytes_be());
+    }
+
+    fn challenge_scalar(&mut self, label: &[u8]) -> E::ScalarField {
+        self.buffer.extend_from_slice(label);
+        let digest = Keccak256::digest(&self.buffer);
+        self.buffer = digest.to_vec();
+        E::ScalarField::from_be_bytes_mod_order(&digest)
+    }
+}
+
+/// Transcript backed by Poseidon, for proofs checked inside a circuit.
+/// Only over BLS12-381, whose scalar field the circuits are built in.
+///
+/// Messages are field elements: each label becomes one element (see
+/// [`label_element`]), scalars are absorbed as they are and points as the
+/// [`POINT_LIMBS`] limbs of [`poseidon_limbs`]. A challenge is the
+/// Poseidon hash of everything since the previous challenge, which then
+/// restarts the transcript, so `circuit::gadgets::transcript::TranscriptVar`
+/// recomputes it with the same calls. Every hash input ends with a
+/// non-zero label, so the unpadded sponge is unambiguous.
+#[derive(Clone, Debug)]
+pub struct PoseidonTranscript {
+    elements: Vec<Fr>,
+}
+
+impl Transcript for PoseidonTranscript {
+    type Curve = Bls12_381;
+
+    fn new(label: &[u8]) -> Self {
+        PoseidonTranscript {
+            elements: vec![label_element(label)],
+        }
+    }
+
+    fn append_commitment(&mut self, label: &[u8], commitment: &Commitment) {
+        self.elements.push(label_element(label));
+        self.elements.extend(poseidon_limbs(&commitment.0));
+    }
+
+    fn append_scalar(&mut self, label: &[u8], scalar: &Fr) {
+        self.elements.push(label_element(label));
+        self.elements.push(*scalar);
+    }
+
+    fn challenge_scalar(&mut self, label: &[u8]) -> Fr {
+        self.elements.push(label_element(label));
+        let challenge = PoseidonParams::standard().hash(&self.elements);
+        self.elements = vec![challenge];
+        challenge
+    }
+}
+
+/// Number of scalar field limbs a G1 point is absorbed as.
+pub const POINT_LIMBS: usize = 4;
+
+/// Bits in the low limb of a coordinate; the high limb holds the other
+/// 133 bits of the 381-bit base field element.
+const LIMB_BITS: usize = 248;
+
+/// Splits a G1 point into `[x_lo, x_hi, y_lo, y_hi]`, the low limbs
+/// holding 248 bits. The point at infinity is all zeros.
+pub fn poseidon_limbs(point: &G1Affine) -> [Fr; POINT_LIMBS] {
+    let (x, y) = point.xy().unwrap_or((Fq::zero(), Fq::zero()));
+    let split = |value: Fq| {
+        let bytes = value.into_bigint().to_bytes_le();
+        let (lo, hi) = bytes.split_at(LIMB_BITS / 8);
+        [
+            Fr::from_le_bytes_mod_order(lo),
+            Fr::from_le_bytes_mod_order(hi),
+        ]
+    };
+    let [x_lo, x_hi] = split(x);
+    let [y_lo, y_hi] = split(y);
+    [x_lo, x_hi, y_lo, y_hi]
+}
+
+/// G1 point as the EVM precompiles take it: x and y as big-endian
+/// integers left-padded to a multiple of 32 bytes, all zeros for the
+/// point at infinity. That is 128 bytes in the EIP-2537 encoding on
+/// BLS12-381 and 64 bytes in the EIP-196 one on BN254.
+pub fn evm_g1<E: Pairing>(point: &E::G1Affine) -> Vec<u8> {
+    let zero = <E::G1Affine as AffineRepr>::BaseField::zero();
+    let (x, y) = point.xy().unwrap_or((zero, zero));
+    let word = |value: <E::G1Affine as AffineRepr>::BaseField| {
+        let mut bytes = Vec::new();
+        value
+            .serialize_uncompressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+        bytes.reverse();
+        let mut padded = vec![0; bytes.len().next_multiple_of(32) - bytes.len()];
+        padded.extend(bytes);
+        padded
+    };
+    [word(x), word(y)].concat()
+}
+
+/// G1 point in the EIP-2537 encoding: x and y as 64-byte big-endian
+/// words, all zeros for the point at infinity.
+pub fn eip2537_g1(point: &G1Affine) -> Vec<u8> {
+    evm_g1::<Bls12_381>(point)
+}
+
+/// Base field element as a 64-byte big-endian word, as EIP-2537 pads it.
+pub fn eip2537_fp(value: &Fq) -> Vec<u8> {
+    let mut bytes = vec![0; 16];
+    bytes.extend(value.into_bigint().to_bytes_be());
+    bytes
+}
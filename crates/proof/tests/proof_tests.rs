@@ -1,14 +1,33 @@
-use ark_bls12_381::Fr;
-use ark_ff::{Field, One, Zero};
-use ark_poly::{DenseUVPolynomial, Polynomial, univariate::DensePolynomial};
-use circuit::{square_circuit::SquareCircuit, witness::Witness};
-use proof::{compute_constraint_polynomial, divide_by_vanishing, prove};
+use std::collections::HashSet;
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+use ark_poly::{
+    DenseUVPolynomial, EvaluationDomain, Polynomial, Radix2EvaluationDomain,
+    univariate::DensePolynomial,
+};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::custom_gates::Pow5Gate;
+use circuit::gadgets::transcript::TranscriptVar;
+use circuit::lookup::LookupTable;
+use circuit::square_circuit::SquareCircuit;
+use kzg::Commitment;
+use proof::key::wire_shifts;
+use proof::lookup::{compress, compute_multiplicities, compute_running_sum};
+use proof::transcript::{
+    Keccak256Transcript, PoseidonTranscript, Sha256Transcript, Transcript, poseidon_limbs,
+};
+use proof::{
+    Proof, compute_constraint_polynomial, divide_by_vanishing, lagrange_eval, preprocess, prove,
+    split_quotient, wire_columns,
+};
 use setup::TrustedSetup;
 
 #[test]
 fn test_constraint_polynomial_is_zero_at_gate() {
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(5);
+    let witness = circuit.witness(5u64);
     let wires = circuit.wire_values(&witness);
 
     let a_poly = DensePolynomial::from_coefficients_vec(vec![wires.a]);
@@ -24,7 +43,7 @@ fn test_constraint_polynomial_is_zero_at_gate() {
 #[test]
 fn test_constraint_polynomial_nonzero_for_bad_witness() {
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(6);
+    let witness = circuit.witness(6u64);
     let wires = circuit.wire_values(&witness);
 
     let a_poly = DensePolynomial::from_coefficients_vec(vec![wires.a]);
@@ -41,7 +60,7 @@ fn test_constraint_polynomial_nonzero_for_bad_witness() {
 #[test]
 fn test_quotient_polynomial() {
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(5);
+    let witness = circuit.witness(5u64);
     let wires = circuit.wire_values(&witness);
 
     let a_poly = DensePolynomial::from_coefficients_vec(vec![wires.a]);
@@ -59,29 +78,366 @@ fn test_quotient_polynomial() {
     assert_eq!(t_at_x, q_at_x * vanishing_at_x);
 }
 
+/// Gate row values of the wire columns the proof commits to.
+fn committed_gate_values(
+    setup: &TrustedSetup<Bls12_381>,
+    cs: &ConstraintSystem,
+    proof: &Proof,
+) -> (Fr, Fr, Fr) {
+    let n = cs.domain_size();
+    let domain = Radix2EvaluationDomain::<Fr>::new(n).unwrap();
+    let columns = wire_columns(cs, n);
+    for (column, comm) in columns.iter().zip(&proof.wire_comms) {
+        let poly = DensePolynomial::from_coefficients_vec(domain.ifft(column));
+        assert_eq!(&kzg::commit(setup, &poly), comm);
+    }
+    // Row 2 is the multiplication gate
+    (columns[0][2], columns[1][2], columns[2][2])
+}
+
 #[test]
 fn test_prove_creates_valid_structure() {
     let setup = TrustedSetup::generate(10);
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(5);
+    let witness = circuit.witness(5u64);
 
-    let proof = prove(&setup, &circuit, &witness);
+    let cs = circuit.synthesize(&witness);
+    let proof = prove(&setup, &preprocess(&setup, &cs), &cs);
+    let (a_eval, b_eval, c_eval) = committed_gate_values(&setup, &cs, &proof);
 
-    assert_eq!(proof.a_eval, Fr::from(5u64));
-    assert_eq!(proof.b_eval, Fr::from(5u64));
-    assert_eq!(proof.c_eval, Fr::from(25u64));
+    assert_eq!(a_eval, Fr::from(5u64));
+    assert_eq!(b_eval, Fr::from(5u64));
+    assert_eq!(c_eval, Fr::from(25u64));
 }
 
 #[test]
 fn test_prove_with_negative_root() {
     let setup = TrustedSetup::generate(10);
     let circuit = SquareCircuit::new();
-    let witness = Witness::new_signed(-5);
+    let witness = circuit.witness(-5i64);
+
+    let cs = circuit.synthesize(&witness);
+    let proof = prove(&setup, &preprocess(&setup, &cs), &cs);
+    let (a_eval, b_eval, c_eval) = committed_gate_values(&setup, &cs, &proof);
+
+    let neg_five = -Fr::from(5u64);
+    assert_eq!(a_eval, neg_five);
+    assert_eq!(b_eval, neg_five);
+    assert_eq!(c_eval, Fr::from(25u64));
+}
+
+#[test]
+fn test_wire_columns_hold_gate_values() {
+    let circuit = SquareCircuit::new();
+    let witness = circuit.witness(5u64);
+    let cs = circuit.synthesize(&witness);
+
+    let columns = wire_columns(&cs, cs.domain_size());
+
+    // Row 2 is the multiplication gate x·x = 25
+    assert_eq!(columns[0][2], Fr::from(5u64));
+    assert_eq!(columns[1][2], Fr::from(5u64));
+    assert_eq!(columns[2][2], Fr::from(25u64));
+
+    // Padding rows are zero
+    assert!(columns.iter().all(|column| column[3].is_zero()));
+}
+
+#[test]
+fn test_wire_columns_with_negative_root() {
+    let circuit = SquareCircuit::new();
+    let witness = circuit.witness(-5i64);
+    let cs = circuit.synthesize(&witness);
 
-    let proof = prove(&setup, &circuit, &witness);
+    let columns = wire_columns(&cs, cs.domain_size());
 
     let neg_five = -Fr::from(5u64);
-    assert_eq!(proof.a_eval, neg_five);
-    assert_eq!(proof.b_eval, neg_five);
-    assert_eq!(proof.c_eval, Fr::from(25u64));
+    assert_eq!(columns[0][2], neg_five);
+    assert_eq!(columns[1][2], neg_five);
+    assert_eq!(columns[2][2], Fr::from(25u64));
+}
+
+#[test]
+fn test_prove_multi_row_structure() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let circuit = SquareCircuit::new();
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+
+    assert_eq!(pk.vk.domain_size, 4);
+    assert_eq!(proof.t_comms.len(), pk.vk.num_quotient_chunks());
+    assert_eq!(proof.t_evals.len(), pk.vk.num_quotient_chunks());
+    assert!(proof.custom_selector_evals.is_empty());
+}
+
+#[test]
+fn test_sigma_is_permutation_of_labels() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let circuit = SquareCircuit::new();
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+
+    let pk = preprocess(&setup, &cs);
+    let domain = pk.vk.domain();
+    let shifts: Vec<Fr> = wire_shifts(3);
+
+    let labels: HashSet<Fr> = shifts
+        .iter()
+        .flat_map(|k| domain.elements().map(move |omega_i| *k * omega_i))
+        .collect();
+    let sigma_values: HashSet<Fr> = pk.sigma_evals.iter().flatten().copied().collect();
+    assert_eq!(labels.len(), 3 * domain.size());
+    assert_eq!(sigma_values, labels);
+
+    // x sits on a and b of row 2, so σ_a(ω²) points at b, row 2
+    assert_eq!(pk.sigma_evals[0][2], shifts[1] * domain.element(2));
+}
+
+#[test]
+fn test_quotient_degree_includes_custom_gates() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let cs = ConstraintSystem::new();
+    assert_eq!(preprocess(&setup, &cs).vk.quotient_degree(), 4);
+
+    let mut cs = ConstraintSystem::new();
+    cs.register_gate(Pow5Gate);
+    let pk = preprocess(&setup, &cs);
+    assert_eq!(pk.vk.quotient_degree(), 6);
+    assert_eq!(pk.vk.custom_selector_comms.len(), 1);
+}
+
+#[test]
+fn test_split_quotient_recombines() {
+    let coeffs: Vec<Fr> = (1..=10u64).map(Fr::from).collect();
+    let t_poly = DensePolynomial::from_coefficients_vec(coeffs);
+
+    let chunks = split_quotient(&t_poly, 4, 3);
+    assert_eq!(chunks.len(), 3);
+
+    let x = Fr::from(7u64);
+    let x_n = x.pow([4]);
+    let recombined = chunks[0].evaluate(&x)
+        + x_n * chunks[1].evaluate(&x)
+        + x_n.square() * chunks[2].evaluate(&x);
+    assert_eq!(recombined, t_poly.evaluate(&x));
+}
+
+#[test]
+fn test_lagrange_eval() {
+    let domain = Radix2EvaluationDomain::<Fr>::new(8).unwrap();
+    let zeta = Fr::from(12345u64);
+
+    let mut unit = vec![Fr::zero(); 8];
+    unit[3] = Fr::one();
+    let l3 = DensePolynomial::from_coefficients_vec(domain.ifft(&unit));
+
+    assert_eq!(lagrange_eval(&domain, 3, zeta), l3.evaluate(&zeta));
+}
+
+#[test]
+fn test_wide_circuit_keys() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let mut cs = ConstraintSystem::with_wires(5);
+    let x = cs.alloc(Fr::from(3u64));
+    cs.mul(x, x);
+
+    let pk = preprocess(&setup, &cs);
+    assert_eq!(pk.vk.num_wires, 5);
+    assert_eq!(pk.vk.sigma_comms.len(), 5);
+    // q_l, q_r, q_o, q_m, q_c, q_4, q_5
+    assert_eq!(pk.vk.selector_comms.len(), 7);
+    // z·Π over five wires
+    assert_eq!(pk.vk.quotient_degree(), 6);
+
+    let domain = pk.vk.domain();
+    let labels: HashSet<Fr> = wire_shifts::<Fr>(5)
+        .iter()
+        .flat_map(|k| domain.elements().map(move |omega_i| *k * omega_i))
+        .collect();
+    let sigma_values: HashSet<Fr> = pk.sigma_evals.iter().flatten().copied().collect();
+    assert_eq!(labels.len(), 5 * domain.size());
+    assert_eq!(sigma_values, labels);
+}
+
+fn range_lookup_circuit(values: &[u64]) -> ConstraintSystem {
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(3));
+    for &value in values {
+        let var = cs.alloc(Fr::from(value));
+        cs.lookup(range, &[var]);
+    }
+    cs
+}
+
+#[test]
+fn test_circuit_without_tables_has_no_lookup_key() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let circuit = SquareCircuit::new();
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+
+    let pk = preprocess(&setup, &cs);
+    assert!(pk.lookup.is_none());
+    assert!(pk.vk.lookup.is_none());
+    assert!(prove(&setup, &pk, &cs).lookup.is_none());
+}
+
+#[test]
+fn test_multiplicities_count_queries() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let cs = range_lookup_circuit(&[2, 5, 2, 7]);
+    let pk = preprocess(&setup, &cs);
+    let lookup = pk.lookup.as_ref().unwrap();
+
+    assert_eq!(lookup.vk.width, 1);
+    let columns = wire_columns(&cs, cs.domain_size());
+    let m = compute_multiplicities(lookup, &columns);
+
+    // The range table occupies rows 0..8 in order
+    assert_eq!(m[2], Fr::from(2u64));
+    assert_eq!(m[5], Fr::one());
+    assert_eq!(m[7], Fr::one());
+    assert_eq!(m.iter().sum::<Fr>(), Fr::from(4u64));
+}
+
+#[test]
+fn test_running_sum_closes_only_for_valid_queries() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let (eta, delta) = (Fr::from(11u64), Fr::from(13u64));
+
+    let closes = |cs: &ConstraintSystem| {
+        let pk = preprocess(&setup, cs);
+        let lookup = pk.lookup.as_ref().unwrap();
+        let columns = wire_columns(cs, cs.domain_size());
+        let m = compute_multiplicities(lookup, &columns);
+        let phi = compute_running_sum(lookup, &columns, &m, eta, delta);
+
+        // Closing the cycle: the last step must bring φ back to zero
+        let n = phi.len();
+        let last = n - 1;
+        let f = compress(lookup.query_tags[last], &[columns[0][last]], eta);
+        let t = compress(
+            lookup.table_rows[last][0],
+            &lookup.table_rows[last][1..],
+            eta,
+        );
+        let q = if lookup.query_tags[last].is_zero() {
+            Fr::zero()
+        } else {
+            Fr::one()
+        };
+        phi[last] + q * (delta + f).inverse().unwrap() - m[last] * (delta + t).inverse().unwrap()
+            == Fr::zero()
+    };
+
+    assert!(closes(&range_lookup_circuit(&[1, 6, 6])));
+    assert!(!closes(&range_lookup_circuit(&[1, 8])));
+}
+
+#[test]
+fn test_json_layout() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let circuit = SquareCircuit::new();
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+
+    let json: serde_json::Value = serde_json::from_str(&proof.to_json()).unwrap();
+    assert_eq!(json["protocol"], "plonk");
+    assert_eq!(json["curve"], "bls12381");
+    assert!(json["lookup"].is_null());
+    let z_eval = json["z_eval"].as_str().unwrap();
+    assert_eq!(z_eval.len(), 2 + 64);
+    assert_eq!(json["z"].as_array().unwrap().len(), 2);
+    assert_eq!(json["z"][0].as_str().unwrap().len(), 2 + 96);
+    assert_eq!(
+        Proof::<Bls12_381>::from_json(&proof.to_json()).unwrap().to_json(),
+        proof.to_json()
+    );
+
+    let vk: serde_json::Value = serde_json::from_str(&pk.vk.to_json()).unwrap();
+    assert_eq!(vk["domain_size"], 4);
+    assert_eq!(vk["public_input_rows"], serde_json::json!([1]));
+    assert_eq!(vk["selectors"].as_array().unwrap().len(), 5);
+
+    // Scalars are checked against the modulus and points against the curve
+    let mut bad = json.clone();
+    bad["z_eval"] = format!("0x{}", "f".repeat(64)).into();
+    assert!(Proof::<Bls12_381>::from_json(&bad.to_string()).is_err());
+    let mut bad = json.clone();
+    bad["z"][1] = bad["z"][0].clone();
+    assert!(Proof::<Bls12_381>::from_json(&bad.to_string()).is_err());
+    let mut bad = json;
+    bad["curve"] = "bn254".into();
+    assert!(Proof::<Bls12_381>::from_json(&bad.to_string()).is_err());
+}
+
+#[test]
+fn test_keccak_transcript_uses_evm_encoding() {
+    let generator = G1Affine::generator();
+    let mut transcript = Keccak256Transcript::<Bls12_381>::new(b"plonk");
+    transcript.append_commitment(b"wire", &Commitment(generator));
+    transcript.append_commitment(b"z", &Commitment(G1Affine::identity()));
+    transcript.append_scalar(b"pi", &Fr::from(258u64));
+
+    let buffer = transcript.buffer();
+    assert_eq!(buffer.len(), 5 + 4 + 128 + 1 + 128 + 2 + 32);
+    // x and y padded to 64 bytes each, big-endian
+    let point = &buffer[9..9 + 128];
+    assert_eq!(&point[..16], &[0; 16]);
+    assert_eq!(point[16..64], generator.x.into_bigint().to_bytes_be());
+    assert_eq!(point[80..128], generator.y.into_bigint().to_bytes_be());
+    // Infinity is all zeros
+    assert!(
+        buffer[9 + 128 + 1..9 + 256 + 1]
+            .iter()
+            .all(|&byte| byte == 0)
+    );
+    assert_eq!(&buffer[buffer.len() - 2..], &[1, 2]);
+
+    // Same messages, different hash
+    let mut sha = Sha256Transcript::<Bls12_381>::new(b"plonk");
+    sha.append_scalar(b"pi", &Fr::from(258u64));
+    let mut keccak = Keccak256Transcript::<Bls12_381>::new(b"plonk");
+    keccak.append_scalar(b"pi", &Fr::from(258u64));
+    let challenge = keccak.challenge_scalar(b"beta");
+    assert_ne!(sha.challenge_scalar(b"beta"), challenge);
+    assert_eq!(keccak.buffer().len(), 32);
+}
+
+#[test]
+fn test_poseidon_transcript_matches_gadget() {
+    let point = (G1Affine::generator() * Fr::from(7u64)).into();
+    let scalar = Fr::from(258u64);
+
+    let mut native = PoseidonTranscript::new(b"plonk");
+    native.append_commitment(b"wire", &Commitment(point));
+    native.append_scalar(b"pi", &scalar);
+    let beta = native.challenge_scalar(b"beta");
+    native.append_commitment(b"z", &Commitment(G1Affine::identity()));
+    let alpha = native.challenge_scalar(b"alpha");
+
+    let mut cs = ConstraintSystem::new();
+    let limbs: Vec<_> = poseidon_limbs(&point)
+        .iter()
+        .map(|&limb| cs.alloc(limb))
+        .collect();
+    let infinity: Vec<_> = poseidon_limbs(&G1Affine::identity())
+        .iter()
+        .map(|&limb| cs.alloc(limb))
+        .collect();
+    let scalar_var = cs.alloc(scalar);
+    let mut transcript = TranscriptVar::new(&mut cs, b"plonk");
+    transcript.append_point(&mut cs, b"wire", &limbs);
+    transcript.append_scalar(&mut cs, b"pi", scalar_var);
+    let beta_var = transcript.challenge(&mut cs, b"beta");
+    transcript.append_point(&mut cs, b"z", &infinity);
+    let alpha_var = transcript.challenge(&mut cs, b"alpha");
+
+    assert_eq!(cs.value(beta_var), beta);
+    assert_eq!(cs.value(alpha_var), alpha);
+    assert!(cs.is_satisfied());
+    assert_ne!(beta, alpha);
 }
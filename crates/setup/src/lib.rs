@@ -1,37 +1,47 @@
-use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
-use ark_ec::PrimeGroup;
-use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{Field, UniformRand};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ec::scalar_mul::ScalarMul;
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_ff::{One, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use rand::thread_rng;
 #[cfg(test)]
 mod tests;
 
+/// Powers of a secret τ in G1, with τ in G2, over the pairing curve `E`.
+///
+/// BLS12-381 is the default; the protocol crates are generic over the
+/// curve, so e.g. `TrustedSetup::<Bn254>::generate` works the same way.
 #[derive(Clone, Debug)]
-pub struct TrustedSetup {
-    pub g1_powers: Vec<G1Affine>,
+pub struct TrustedSetup<E: Pairing = Bls12_381> {
+    pub g1_powers: Vec<E::G1Affine>,
 
-    pub g2_gen: G2Affine,
+    pub g2_gen: E::G2Affine,
 
-    pub g2_tau: G2Affine,
+    pub g2_tau: E::G2Affine,
 }
 
-impl TrustedSetup {
+impl<E: Pairing> TrustedSetup<E> {
     pub fn generate(max_degree: usize) -> Self {
         let mut rng = thread_rng();
 
-        let tau: Fr = Fr::rand(&mut rng);
+        let tau = E::ScalarField::rand(&mut rng);
 
-        let g1_generator = G1Projective::generator();
-        let g2_generator = G2Projective::generator();
+        let g1_generator = E::G1::generator();
+        let g2_generator = E::G2::generator();
 
-        let mut g1_powers = Vec::with_capacity(max_degree + 1);
-        let mut current_tau_power = Fr::from(1u64);
+        let mut tau_powers = Vec::with_capacity(max_degree + 1);
+        let mut current_tau_power = E::ScalarField::one();
 
         for _ in 0..=max_degree {
-            let point = g1_generator * current_tau_power;
-            g1_powers.push(point.into_affine());
+            tau_powers.push(current_tau_power);
             current_tau_power *= tau;
         }
+        let g1_powers = g1_generator.batch_mul(&tau_powers);
 
         let g2_tau = (g2_generator * tau).into_affine();
 
@@ -45,4 +55,105 @@ impl TrustedSetup {
     pub fn max_degree(&self) -> usize {
         self.g1_powers.len() - 1
     }
+
+    /// Reads a setup written by [`TrustedSetup::write`]. Every point is
+    /// checked to be on the curve and in the prime-order subgroup.
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+        // Read point by point rather than trusting the length to allocate
+        let num_powers = u64::deserialize_compressed(&mut reader).map_err(invalid)?;
+        let mut g1_powers = Vec::new();
+        for _ in 0..num_powers {
+            g1_powers.push(E::G1Affine::deserialize_compressed(&mut reader).map_err(invalid)?);
+        }
+        let g2_gen = E::G2Affine::deserialize_compressed(&mut reader).map_err(invalid)?;
+        let g2_tau = E::G2Affine::deserialize_compressed(&mut reader).map_err(invalid)?;
+        if g1_powers.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Setup has no G1 powers",
+            ));
+        }
+        Ok(TrustedSetup {
+            g1_powers,
+            g2_gen,
+            g2_tau,
+        })
+    }
+
+    /// Writes the powers and G2 points as compressed points.
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        self.g1_powers
+            .serialize_compressed(&mut writer)
+            .and_then(|_| self.g2_gen.serialize_compressed(&mut writer))
+            .and_then(|_| self.g2_tau.serialize_compressed(&mut writer))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+}
+
+/// Structured keys for committing to vectors of G1 points in the target
+/// group, as inner pairing product arguments (SnarkPack) use them: powers
+/// of two secrets a and b in G2, with a and b in G1 to check folded keys.
+///
+/// Like [`TrustedSetup::generate`], [`IppSetup::generate`] samples the
+/// secrets itself, so it is only fit for testing.
+#[derive(Clone, Debug)]
+pub struct IppSetup<E: Pairing = Bls12_381> {
+    pub g1_gen: E::G1Affine,
+    pub g2_gen: E::G2Affine,
+    /// a in G1
+    pub g1_alpha: E::G1Affine,
+    /// b in G1
+    pub g1_beta: E::G1Affine,
+    /// aⁱ in G2, for i below the maximum vector length
+    pub g2_alpha_powers: Vec<E::G2Affine>,
+    /// bⁱ in G2, for i below the maximum vector length
+    pub g2_beta_powers: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> IppSetup<E> {
+    pub fn generate(max_len: usize) -> Self {
+        let mut rng = thread_rng();
+
+        let alpha = E::ScalarField::rand(&mut rng);
+        let beta = E::ScalarField::rand(&mut rng);
+
+        let g1_generator = E::G1::generator();
+        let g2_generator = E::G2::generator();
+        let powers = |secret: E::ScalarField| {
+            let mut current = E::ScalarField::one();
+            let exponents: Vec<E::ScalarField> = (0..max_len)
+                .map(|_| {
+                    let exponent = current;
+                    current *= secret;
+                    exponent
+                })
+                .collect();
+            g2_generator.batch_mul(&exponents)
+        };
+
+        IppSetup {
+            g1_gen: g1_generator.into_affine(),
+            g2_gen: g2_generator.into_affine(),
+            g1_alpha: (g1_generator * alpha).into_affine(),
+            g1_beta: (g1_generator * beta).into_affine(),
+            g2_alpha_powers: powers(alpha),
+            g2_beta_powers: powers(beta),
+        }
+    }
+
+    /// Longest vector the keys can commit to.
+    pub fn max_len(&self) -> usize {
+        self.g2_alpha_powers.len()
+    }
 }
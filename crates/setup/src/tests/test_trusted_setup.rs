@@ -1,27 +1,71 @@
 use crate::*;
 use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
 use ark_ec::pairing::Pairing;
+use ark_ec::AffineRepr;
 
 #[test]
 fn test_setup_size() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
     assert_eq!(setup.g1_powers.len(), 11);
     assert_eq!(setup.max_degree(), 10);
 }
 
 #[test]
 fn test_first_power_is_generator() {
-    let setup = TrustedSetup::generate(5);
-    assert_eq!(setup.g1_powers[0], G1Affine::generator());
+    let setup = TrustedSetup::<Bls12_381>::generate(5);
+    assert_eq!(
+        setup.g1_powers[0],
+        <Bls12_381 as Pairing>::G1Affine::generator()
+    );
 }
 
-#[test]
-fn test_powers_are_consistent() {
-    let setup = TrustedSetup::generate(5);
+fn check_powers_are_consistent<E: Pairing>() {
+    let setup = TrustedSetup::<E>::generate(5);
 
     for i in 0..setup.max_degree() {
-        let lhs = Bls12_381::pairing(setup.g1_powers[i], setup.g2_tau);
-        let rhs = Bls12_381::pairing(setup.g1_powers[i + 1], setup.g2_gen);
+        let lhs = E::pairing(setup.g1_powers[i], setup.g2_tau);
+        let rhs = E::pairing(setup.g1_powers[i + 1], setup.g2_gen);
         assert_eq!(lhs, rhs, "Pairing check failed at power {}", i);
     }
 }
+
+#[test]
+fn test_powers_are_consistent() {
+    check_powers_are_consistent::<Bls12_381>();
+}
+
+#[test]
+fn test_bn254_powers_are_consistent() {
+    check_powers_are_consistent::<Bn254>();
+}
+
+fn check_setup_round_trips<E: Pairing>() {
+    let setup = TrustedSetup::<E>::generate(5);
+    let mut bytes = Vec::new();
+    setup.write(&mut bytes).unwrap();
+
+    let read = TrustedSetup::<E>::read(bytes.as_slice()).unwrap();
+    assert_eq!(read.g1_powers, setup.g1_powers);
+    assert_eq!(read.g2_gen, setup.g2_gen);
+    assert_eq!(read.g2_tau, setup.g2_tau);
+
+    assert!(TrustedSetup::<E>::read(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn test_setup_round_trips() {
+    check_setup_round_trips::<Bls12_381>();
+}
+
+#[test]
+fn test_bn254_setup_round_trips() {
+    check_setup_round_trips::<Bn254>();
+
+    // A BLS12-381 file is not a BN254 setup
+    let mut bytes = Vec::new();
+    TrustedSetup::<Bls12_381>::generate(2)
+        .write(&mut bytes)
+        .unwrap();
+    assert!(TrustedSetup::<Bn254>::read(bytes.as_slice()).is_err());
+}
@@ -0,0 +1,49 @@
+//! Aggregation of many proofs into one KZG accumulator.
+//!
+//! Verifying a proof is cheap scalar work followed by two KZG openings,
+//! each a pairing equation e(C - y·G + z·W, [1]₂) = e(W, [τ]₂).
+//! [`accumulate`] does the scalar work for every proof and folds all the
+//! openings into one such equation, with powers of a challenge drawn from
+//! a transcript of the openings. [`decide`] then checks the lot with a
+//! single pairing check.
+
+use ark_ec::pairing::Pairing;
+use kzg::{Accumulator, Commitment};
+use proof::transcript::{Scalar, Transcript};
+use proof::{Proof, VerifyingKey};
+use setup::TrustedSetup;
+
+use crate::openings;
+
+/// A proof and its public inputs.
+pub type Instance<'a, T> = (&'a [Scalar<T>], &'a Proof<<T as Transcript>::Curve>);
+
+/// Folds proofs made with the transcript `T` into one accumulator, or
+/// returns `None` if any of them fails the checks that need no pairing.
+/// All of them prove the circuit of `vk`.
+pub fn accumulate<T: Transcript>(
+    setup: &TrustedSetup<T::Curve>,
+    vk: &VerifyingKey<T::Curve>,
+    instances: &[Instance<T>],
+) -> Option<Accumulator<T::Curve>> {
+    let mut accumulators = Vec::with_capacity(2 * instances.len());
+    for (public_inputs, proof) in instances {
+        for opening in openings::<T>(vk, public_inputs, proof)? {
+            accumulators.push(opening.accumulator(setup));
+        }
+    }
+
+    let mut transcript = T::new(b"aggregate");
+    for acc in &accumulators {
+        transcript.append_commitment(b"lhs", &Commitment(acc.lhs));
+        transcript.append_commitment(b"rhs", &Commitment(acc.rhs));
+    }
+    let r = transcript.challenge_scalar(b"r");
+    Some(Accumulator::fold(&accumulators, r))
+}
+
+/// Checks an accumulator with one pairing check. True if every proof
+/// folded into it verifies.
+pub fn decide<E: Pairing>(setup: &TrustedSetup<E>, accumulator: &Accumulator<E>) -> bool {
+    accumulator.decide(setup)
+}
@@ -0,0 +1,155 @@
+//! Aggregation of the KZG opening proofs of many proofs with an inner
+//! pairing product argument.
+//!
+//! This is not SnarkPack: only the opening proofs are aggregated, and the
+//! verifier stays linear in the number of proofs N. [`crate::aggregate`]
+//! folds every opening into one accumulator, but its decider still needs
+//! each opening proof W. Here the prover commits to the 2N opening proofs
+//! in the target group with an [`IppSetup`] and proves, with
+//! [`kzg::ipp::prove_mipp`], SnarkPack's multi-exponentiation argument,
+//! the two inner products the folded equation needs,
+//!
+//!   Z₁ = Σ rʲ·zⱼ·Wⱼ and Z₂ = Σ rʲ·Wⱼ,
+//!
+//! for r drawn after the commitment. The verifier then checks
+//! e(Σ rʲ·Cⱼ - (Σ rʲ·yⱼ)·G + Z₁, [1]₂) = e(Z₂, [τ]₂), which holds for
+//! every opening at once except with negligible probability.
+//!
+//! The aggregate is logarithmic in N and its verifier makes a constant
+//! number of pairings, but it needs every other commitment and evaluation
+//! of each proof. PLONK derives each proof's challenges by hashing that
+//! proof's own commitments, so the verifier re-derives them and the
+//! commitments and points Cⱼ and zⱼ one proof at a time, and folds the
+//! weights rʲ·zⱼ, which have no product form, with O(N) group and field
+//! operations. Verifying from the aggregate, the public inputs and the key
+//! alone would need challenges shared across the proofs, and so a prover
+//! that proves them together. The opening fields of the proofs it is
+//! given are ignored.
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{One, Zero};
+use kzg::ipp::{Challenger, MippProof, PairCommitment, commit, prove_mipp, verify_mipp};
+use kzg::{Accumulator, Opening};
+use proof::VerifyingKey;
+use proof::transcript::{Scalar, Transcript};
+use setup::{IppSetup, TrustedSetup};
+
+use crate::aggregate::Instance;
+use crate::openings;
+
+#[derive(Clone, Debug)]
+pub struct AggregateProof<E: Pairing = Bls12_381> {
+    /// Commitment to the opening proofs, at ζ and ζ·ω for each proof in turn
+    pub opening_comm: PairCommitment<E>,
+    /// Z₁ and Z₂
+    pub products: Vec<E::G1Affine>,
+    pub mipp: MippProof<E>,
+}
+
+/// Aggregates the openings of proofs made with the transcript `T`, or
+/// returns `None` if any of them fails the checks that need no pairing.
+/// All of them prove the circuit of `vk`, and `ipp` fits twice as many
+/// openings as there are proofs, rounded up to a power of two.
+pub fn aggregate<T: Transcript>(
+    ipp: &IppSetup<T::Curve>,
+    vk: &VerifyingKey<T::Curve>,
+    instances: &[Instance<T>],
+) -> Option<AggregateProof<T::Curve>> {
+    let openings = all_openings::<T>(vk, instances)?;
+    let points: Vec<_> = openings.iter().map(|opening| opening.proof.0).collect();
+    let opening_comm = commit(ipp, &points);
+
+    let mut challenger = Challenger::new(b"ipp-aggregate");
+    let scalars = fold_scalars(&mut challenger, &openings, &opening_comm);
+    let (products, mipp) = prove_mipp(ipp, &mut challenger, &points, &scalars);
+    Some(AggregateProof {
+        opening_comm,
+        products,
+        mipp,
+    })
+}
+
+/// Verifies an aggregate of the openings of `instances`, which only need
+/// to be valid up to their opening proofs.
+pub fn verify_aggregate<T: Transcript>(
+    setup: &TrustedSetup<T::Curve>,
+    ipp: &IppSetup<T::Curve>,
+    vk: &VerifyingKey<T::Curve>,
+    instances: &[Instance<T>],
+    proof: &AggregateProof<T::Curve>,
+) -> bool {
+    let Some(openings) = all_openings::<T>(vk, instances) else {
+        return false;
+    };
+    let mut challenger = Challenger::new(b"ipp-aggregate");
+    let scalars = fold_scalars(&mut challenger, &openings, &proof.opening_comm);
+    if proof.products.len() != 2
+        || !verify_mipp(
+            ipp,
+            &mut challenger,
+            &proof.opening_comm,
+            &scalars,
+            &proof.products,
+            &proof.mipp,
+        )
+    {
+        return false;
+    }
+
+    // Σ rʲ·(Cⱼ - yⱼ·G), with the rʲ in the second scalar vector
+    let mut lhs = proof.products[0].into_group();
+    let mut y = Scalar::<T>::zero();
+    for (opening, power) in openings.iter().zip(&scalars[1]) {
+        lhs += opening.commitment.0 * power;
+        y += opening.y * power;
+    }
+    lhs -= setup.g1_powers[0] * y;
+    Accumulator {
+        lhs: lhs.into_affine(),
+        rhs: proof.products[1],
+    }
+    .decide(setup)
+}
+
+fn all_openings<T: Transcript>(
+    vk: &VerifyingKey<T::Curve>,
+    instances: &[Instance<T>],
+) -> Option<Vec<Opening<T::Curve>>> {
+    if instances.is_empty() {
+        return None;
+    }
+    let mut all = Vec::with_capacity(2 * instances.len());
+    for (public_inputs, proof) in instances {
+        all.extend(openings::<T>(vk, public_inputs, proof)?);
+    }
+    Some(all)
+}
+
+/// Draws r after the claims and the commitment to their proofs and
+/// returns the scalars of Z₁ and Z₂, rʲ·zⱼ and rʲ.
+fn fold_scalars<E: Pairing>(
+    challenger: &mut Challenger,
+    openings: &[Opening<E>],
+    opening_comm: &PairCommitment<E>,
+) -> Vec<Vec<E::ScalarField>> {
+    for opening in openings {
+        challenger.append(b"claim", &(opening.commitment.0, opening.z, opening.y));
+    }
+    challenger.append(b"openings", opening_comm);
+    let r: E::ScalarField = challenger.challenge(b"r");
+
+    let mut powers = Vec::with_capacity(openings.len());
+    let mut power = E::ScalarField::one();
+    for _ in openings {
+        powers.push(power);
+        power *= r;
+    }
+    let weighted = openings
+        .iter()
+        .zip(&powers)
+        .map(|(opening, power)| opening.z * power)
+        .collect();
+    vec![weighted, powers]
+}
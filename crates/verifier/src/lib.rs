@@ -1,45 +1,163 @@
-use ark_bls12_381::Fr;
-use ark_ff::One;
-use circuit::square_circuit::SquareCircuit;
-use kzg::verify as kzg_verify;
-use proof::Proof;
+pub mod aggregate;
+pub mod ipp_aggregate;
+pub mod recursion;
+pub mod solidity;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One, Zero};
+use ark_poly::EvaluationDomain;
+use kzg::Opening;
+use proof::constraints::{Challenges, ColumnValues, combined_constraint};
+use proof::lookup::LookupValues;
+use proof::transcript::{Scalar, Sha256Transcript, Transcript};
+use proof::{Proof, VerifyingKey, lagrange_eval, lookup_evals, new_transcript, public_input_eval};
 use setup::TrustedSetup;
 
-pub fn verify(
-    setup: &TrustedSetup,
-    circuit: &SquareCircuit,
-    proof: &Proof,
+/// Verifies a proof made with the default SHA-256 transcript.
+pub fn verify<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    vk: &VerifyingKey<E>,
+    public_inputs: &[E::ScalarField],
+    proof: &Proof<E>,
 ) -> bool {
-    let zeta = Fr::from(7u64);
+    verify_with::<Sha256Transcript<E>>(setup, vk, public_inputs, proof)
+}
 
-    if !kzg_verify(setup, &proof.a_comm, zeta, proof.a_eval, &proof.a_opening) {
-        return false;
-    }
+/// Verifies a proof made with the Fiat-Shamir transcript `T`.
+pub fn verify_with<T: Transcript>(
+    setup: &TrustedSetup<T::Curve>,
+    vk: &VerifyingKey<T::Curve>,
+    public_inputs: &[Scalar<T>],
+    proof: &Proof<T::Curve>,
+) -> bool {
+    openings::<T>(vk, public_inputs, proof)
+        .is_some_and(|openings| openings.iter().all(|opening| opening.verify(setup)))
+}
 
-    if !kzg_verify(setup, &proof.b_comm, zeta, proof.b_eval, &proof.b_opening) {
-        return false;
+/// Runs every check of [`verify_with`] except the pairings and returns the
+/// batched openings at ζ and ζ·ω left to check, or `None` if a check
+/// fails.
+pub fn openings<T: Transcript>(
+    vk: &VerifyingKey<T::Curve>,
+    public_inputs: &[Scalar<T>],
+    proof: &Proof<T::Curve>,
+) -> Option<[Opening<T::Curve>; 2]> {
+    if public_inputs.len() != vk.public_input_rows.len()
+        || proof.wire_comms.len() != vk.num_wires
+        || proof.wire_evals.len() != vk.num_wires
+        || proof.wire_next_evals.len() != vk.num_wires
+        || proof.sigma_evals.len() != vk.num_wires
+        || proof.selector_evals.len() != vk.selector_comms.len()
+        || proof.t_comms.len() != vk.num_quotient_chunks()
+        || proof.t_evals.len() != vk.num_quotient_chunks()
+        || proof.custom_selector_evals.len() != vk.custom_selector_comms.len()
+    {
+        return None;
     }
-
-    if !kzg_verify(setup, &proof.c_comm, zeta, proof.c_eval, &proof.c_opening) {
-        return false;
+    match (&vk.lookup, &proof.lookup) {
+        (None, None) => {}
+        (Some(lookup_vk), Some(lookup)) if lookup.table_evals.len() == lookup_vk.width => {}
+        _ => return None,
     }
 
-    if !kzg_verify(setup, &proof.q_comm, zeta, proof.q_eval, &proof.q_opening) {
-        return false;
+    let mut transcript: T = new_transcript(vk, public_inputs);
+    for comm in &proof.wire_comms {
+        transcript.append_commitment(b"wire", comm);
     }
+    if let Some(lookup) = &proof.lookup {
+        transcript.append_commitment(b"m", &lookup.m_comm);
+    }
+    let beta = transcript.challenge_scalar(b"beta");
+    let gamma = transcript.challenge_scalar(b"gamma");
+    let eta = transcript.challenge_scalar(b"eta");
+    let delta = transcript.challenge_scalar(b"delta");
+    transcript.append_commitment(b"z", &proof.z_comm);
+    if let Some(lookup) = &proof.lookup {
+        transcript.append_commitment(b"phi", &lookup.phi_comm);
+    }
+    let alpha = transcript.challenge_scalar(b"alpha");
+    for comm in &proof.t_comms {
+        transcript.append_commitment(b"t", comm);
+    }
+    let zeta = transcript.challenge_scalar(b"zeta");
+    for eval in proof
+        .wire_evals
+        .iter()
+        .chain(&proof.wire_next_evals)
+        .chain(&proof.selector_evals)
+        .chain(&proof.custom_selector_evals)
+        .chain(&proof.sigma_evals)
+        .chain([&proof.z_eval, &proof.z_next_eval])
+        .chain(&proof.t_evals)
+    {
+        transcript.append_scalar(b"eval", eval);
+    }
+    if let Some(lookup) = &proof.lookup {
+        for eval in lookup_evals(lookup) {
+            transcript.append_scalar(b"eval", &eval);
+        }
+    }
+    let v = transcript.challenge_scalar(b"v");
 
-    let vanishing_eval = zeta - Fr::one();
+    let domain = vk.domain();
+    let vanishing_eval = domain.evaluate_vanishing_polynomial(zeta);
+    if vanishing_eval.is_zero() {
+        return None;
+    }
 
-    let constraint_eval = proof.a_eval * proof.b_eval - proof.c_eval;
-    let quotient_check = proof.q_eval * vanishing_eval;
+    let values = ColumnValues {
+        x: zeta,
+        wires: &proof.wire_evals,
+        wires_next: &proof.wire_next_evals,
+        selectors: &proof.selector_evals,
+        custom_selectors: &proof.custom_selector_evals,
+        sigmas: &proof.sigma_evals,
+        z: proof.z_eval,
+        z_next: proof.z_next_eval,
+        pi: public_input_eval(vk, public_inputs, zeta),
+        l1: lagrange_eval(&domain, 0, zeta),
+        lookup: proof.lookup.as_ref().map(|lookup| LookupValues {
+            q_lookup: lookup.q_lookup_eval,
+            q_table: lookup.q_table_eval,
+            table_tag: lookup.table_tag_eval,
+            table: &lookup.table_evals,
+            m: lookup.m_eval,
+            phi: lookup.phi_eval,
+            phi_next: lookup.phi_next_eval,
+        }),
+    };
+    let challenges = Challenges {
+        beta,
+        gamma,
+        alpha,
+        eta,
+        delta,
+    };
+    let constraint_eval = combined_constraint(vk, &values, &challenges);
 
-    if constraint_eval != quotient_check {
-        return false;
+    // t(ζ) = Σ ζⁱⁿ·tᵢ(ζ)
+    let zeta_n = zeta.pow([vk.domain_size as u64]);
+    let mut t_eval = Scalar::<T>::zero();
+    let mut zeta_power = Scalar::<T>::one();
+    for chunk_eval in &proof.t_evals {
+        t_eval += zeta_power * chunk_eval;
+        zeta_power *= zeta_n;
     }
 
-    if proof.c_eval != circuit.public_output {
-        return false;
+    if constraint_eval != t_eval * vanishing_eval {
+        return None;
     }
 
-    true
+    let (commitments, evals) = proof.zeta_openings(vk);
+    let (next_commitments, next_evals) = proof.zeta_omega_openings();
+    Some([
+        Opening::batch(&commitments, zeta, &evals, v, proof.opening.clone()),
+        Opening::batch(
+            &next_commitments,
+            zeta * domain.group_gen(),
+            &next_evals,
+            v,
+            proof.next_opening.clone(),
+        ),
+    ])
 }
@@ -1,16 +1,343 @@
-use circuit::square_circuit::SquareCircuit;
-use circuit::witness::Witness;
-use proof::prove;
+//! Command-line front end: generate a setup, preprocess a circuit, prove,
+//! verify and inspect, and export a Solidity verifier with its calldata.
+//!
+//! Circuits are read from the textual format, or from circom `.r1cs`
+//! files. Witnesses are JSON or binary [`Witness`] files, or circom
+//! `.wtns` files for `.r1cs` circuits.
+//!
+//! Exit codes: 0 on success, 1 when a proof is rejected or a witness does
+//! not satisfy the circuit, 2 on any other error.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use ark_bls12_381::{Bls12_381, Fr};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::format;
+use circuit::mock_prover::MockProver;
+use circuit::r1cs::{R1cs, R1csCircuit, load_wtns};
+use circuit::stats::{CircuitStats, to_dot};
+use circuit::witness::{Witness, parse_integer};
+use clap::{Parser, Subcommand, ValueEnum};
+use proof::transcript::{Keccak256Transcript, PoseidonTranscript, Sha256Transcript};
+use proof::{Proof, VerifyingKey, preprocess, prove_with};
 use setup::TrustedSetup;
-use verifier::verify;
+use verifier::solidity::{encode_calldata, generate_verifier};
+use verifier::verify_with;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "plonk", about = "PLONK prover and verifier")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a structured reference string
+    Setup {
+        /// Largest polynomial degree the setup supports
+        #[arg(long)]
+        degree: usize,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Compute a circuit's verifying key
+    Preprocess {
+        #[arg(long)]
+        srs: PathBuf,
+        #[arg(long)]
+        circuit: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Prove that a witness satisfies a circuit
+    Prove {
+        #[arg(long)]
+        srs: PathBuf,
+        #[arg(long)]
+        circuit: PathBuf,
+        #[arg(long)]
+        witness: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+        /// Also write the public inputs, as a JSON array
+        #[arg(long)]
+        public: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = Hash::Sha256)]
+        transcript: Hash,
+    },
+    /// Check a proof against a verifying key and public inputs
+    Verify {
+        #[arg(long)]
+        srs: PathBuf,
+        #[arg(long)]
+        vk: PathBuf,
+        #[arg(long)]
+        proof: PathBuf,
+        /// JSON array of public inputs, as integers or strings
+        #[arg(long)]
+        public: PathBuf,
+        #[arg(long, value_enum, default_value_t = Hash::Sha256)]
+        transcript: Hash,
+    },
+    /// Generate a Solidity verifier contract for a verifying key
+    Solidity {
+        #[arg(long)]
+        srs: PathBuf,
+        #[arg(long)]
+        vk: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+        #[arg(long, value_enum, default_value_t = Hash::Sha256)]
+        transcript: Hash,
+    },
+    /// Encode a proof and its public inputs as a call to the Solidity
+    /// verifier, written as 0x-prefixed hex
+    Calldata {
+        #[arg(long)]
+        proof: PathBuf,
+        /// JSON array of public inputs, as integers or strings
+        #[arg(long)]
+        public: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Print circuit statistics and check a witness without proving
+    Inspect {
+        #[arg(long)]
+        circuit: PathBuf,
+        #[arg(long)]
+        witness: Option<PathBuf>,
+        /// Write the wiring graph in Graphviz DOT
+        #[arg(long)]
+        dot: Option<PathBuf>,
+    },
+}
+
+/// Hash behind the Fiat-Shamir transcript. Proofs verify only with the
+/// transcript they were made with.
+#[derive(Clone, Copy, ValueEnum)]
+enum Hash {
+    Sha256,
+    Keccak256,
+    /// Poseidon, for proofs checked by a verifier circuit
+    Poseidon,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(command: Command) -> Result<ExitCode> {
+    match command {
+        Command::Setup { degree, out } => {
+            TrustedSetup::<Bls12_381>::generate(degree).save(&out)?;
+            println!("Wrote setup of degree {degree} to {}", out.display());
+        }
+        Command::Preprocess { srs, circuit, out } => {
+            let setup = TrustedSetup::<Bls12_381>::load(srs)?;
+            let cs = Circuit::load(&circuit)?.cs;
+            check_setup_size(&setup, &cs)?;
+            preprocess(&setup, &cs).vk.save(&out)?;
+            println!("Wrote verifying key to {}", out.display());
+        }
+        Command::Prove {
+            srs,
+            circuit,
+            witness,
+            out,
+            public,
+            transcript,
+        } => {
+            let setup = TrustedSetup::<Bls12_381>::load(srs)?;
+            let cs = Circuit::load(&circuit)?.synthesize(&witness)?;
+            check_setup_size(&setup, &cs)?;
+            if !check_witness(&cs) {
+                return Ok(ExitCode::FAILURE);
+            }
+
+            let pk = preprocess(&setup, &cs);
+            let proof = match transcript {
+                Hash::Sha256 => prove_with::<Sha256Transcript>(&setup, &pk, &cs),
+                Hash::Keccak256 => prove_with::<Keccak256Transcript>(&setup, &pk, &cs),
+                Hash::Poseidon => prove_with::<PoseidonTranscript>(&setup, &pk, &cs),
+            };
+            proof.save(&out)?;
+            println!("Wrote proof to {}", out.display());
+            if let Some(public) = public {
+                fs::write(&public, public_inputs_json(&cs.public_inputs()))?;
+                println!("Wrote public inputs to {}", public.display());
+            }
+        }
+        Command::Verify {
+            srs,
+            vk,
+            proof,
+            public,
+            transcript,
+        } => {
+            let setup = TrustedSetup::<Bls12_381>::load(srs)?;
+            let vk = VerifyingKey::load(vk)?;
+            let proof = Proof::load(proof)?;
+            let public_inputs = read_public_inputs(&public)?;
 
-fn main() {
-    let setup = TrustedSetup::generate(10);
-    let circuit = SquareCircuit::new();
-    let witness = Witness::new(5);
+            let valid = match transcript {
+                Hash::Sha256 => {
+                    verify_with::<Sha256Transcript>(&setup, &vk, &public_inputs, &proof)
+                }
+                Hash::Keccak256 => {
+                    verify_with::<Keccak256Transcript>(&setup, &vk, &public_inputs, &proof)
+                }
+                Hash::Poseidon => {
+                    verify_with::<PoseidonTranscript>(&setup, &vk, &public_inputs, &proof)
+                }
+            };
+            println!("Proof valid: {valid}");
+            if !valid {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Solidity {
+            srs,
+            vk,
+            out,
+            transcript,
+        } => {
+            let setup = TrustedSetup::<Bls12_381>::load(srs)?;
+            let vk = VerifyingKey::load(vk)?;
+            let contract = match transcript {
+                Hash::Sha256 => generate_verifier::<Sha256Transcript>(&setup, &vk)?,
+                Hash::Keccak256 => generate_verifier::<Keccak256Transcript>(&setup, &vk)?,
+                Hash::Poseidon => {
+                    return Err(
+                        "the Solidity verifier supports sha256 and keccak256 transcripts".into(),
+                    );
+                }
+            };
+            fs::write(&out, contract)?;
+            println!("Wrote Solidity verifier to {}", out.display());
+        }
+        Command::Calldata { proof, public, out } => {
+            let proof = Proof::load(proof)?;
+            let calldata = encode_calldata(&proof, &read_public_inputs(&public)?);
+            let hex: String = calldata.iter().map(|byte| format!("{byte:02x}")).collect();
+            fs::write(&out, format!("0x{hex}"))?;
+            println!("Wrote calldata to {}", out.display());
+        }
+        Command::Inspect {
+            circuit,
+            witness,
+            dot,
+        } => {
+            let circuit = Circuit::load(&circuit)?;
+            print!("{}", CircuitStats::new(&circuit.cs));
+            if let Some(dot) = dot {
+                fs::write(&dot, to_dot(&circuit.cs))?;
+                println!("Wrote wiring graph to {}", dot.display());
+            }
+            if let Some(witness) = witness {
+                let cs = circuit.synthesize(&witness)?;
+                if !check_witness(&cs) {
+                    return Ok(ExitCode::FAILURE);
+                }
+                println!("Witness satisfies the circuit");
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// A circuit read from a file, with the R1CS wiring if it came from one.
+struct Circuit {
+    cs: ConstraintSystem,
+    r1cs: Option<R1csCircuit>,
+}
+
+impl Circuit {
+    fn load(path: &Path) -> Result<Self> {
+        if has_extension(path, "r1cs") {
+            let r1cs = R1cs::load(path)?.compile(3);
+            Ok(Circuit {
+                cs: r1cs.cs.clone(),
+                r1cs: Some(r1cs),
+            })
+        } else {
+            Ok(Circuit {
+                cs: format::parse(&fs::read_to_string(path)?)?,
+                r1cs: None,
+            })
+        }
+    }
 
-    let proof = prove(&setup, &circuit, &witness);
-    let valid = verify(&setup, &circuit, &proof);
+    /// The circuit with every variable generated from a witness file.
+    fn synthesize(&self, path: &Path) -> Result<ConstraintSystem> {
+        let mut cs = self.cs.clone();
+        let witness = match &self.r1cs {
+            Some(r1cs) if has_extension(path, "wtns") => r1cs.witness(&load_wtns(path)?),
+            _ => Witness::load(path)?,
+        };
+        cs.set_witness(&witness)?;
+        Ok(cs)
+    }
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|ext| ext == extension)
+}
+
+/// Prints every failing constraint; true if there are none.
+fn check_witness(cs: &ConstraintSystem) -> bool {
+    match MockProver::new(cs).verify() {
+        Ok(()) => true,
+        Err(failures) => {
+            eprintln!("Witness does not satisfy the circuit:");
+            for failure in failures {
+                eprintln!("  {failure}");
+            }
+            false
+        }
+    }
+}
+
+fn check_setup_size(setup: &TrustedSetup, cs: &ConstraintSystem) -> Result<()> {
+    let needed = cs.domain_size() - 1;
+    if setup.max_degree() < needed {
+        return Err(format!(
+            "Setup supports degree {}, the circuit needs {needed}",
+            setup.max_degree()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn public_inputs_json(inputs: &[Fr]) -> String {
+    let values: Vec<String> = inputs.iter().map(Fr::to_string).collect();
+    serde_json::to_string(&values).expect("strings serialize")
+}
 
-    println!("Proof valid: {valid}");
+fn read_public_inputs(path: &Path) -> Result<Vec<Fr>> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(path)?)?;
+    values
+        .iter()
+        .map(|value| {
+            let text = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            parse_integer(&text).ok_or_else(|| format!("{value} is not an integer").into())
+        })
+        .collect()
 }
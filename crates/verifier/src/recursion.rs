@@ -0,0 +1,452 @@
+//! One-layer in-circuit verification with deferred pairings.
+//!
+//! [`verifier_circuit`] runs the verifier of an inner proof inside an outer
+//! circuit over the same scalar field: it replays the inner proof's
+//! Poseidon transcript, derives every challenge and checks the constraint
+//! identity at ζ. The KZG openings are the part it cannot do, since G1
+//! coordinates live in the 381-bit base field. They are deferred instead:
+//! the circuit folds the opened evaluations into one claimed value per
+//! opening point, makes ζ, v and both values public, and
+//! [`verify_recursive`] finishes the two openings outside the circuit.
+//! Moving the group operations into the circuit as well needs a curve
+//! cycle or a 2-chain such as BLS12-377/BW6-761.
+//!
+//! This is one layer, not full recursion. A [`RecursiveProof`] is only
+//! valid together with its deferred openings, and no circuit checks
+//! those, so wrapping the outer proof in another outer circuit would
+//! leave the inner openings unchecked. Chains therefore do not compress:
+//! each layer adds two native pairing checks. Folding the deferred
+//! openings into an accumulator carried through the circuits would fix
+//! that, but needs the same in-circuit group arithmetic.
+//!
+//! Inner circuits must be proven with [`PoseidonTranscript`], have no
+//! lookups and use no custom gates other than [`BooleanGate`] and
+//! [`Pow5Gate`].
+//!
+//! [`BooleanGate`]: circuit::custom_gates::BooleanGate
+//! [`Pow5Gate`]: circuit::custom_gates::Pow5Gate
+
+use ark_bls12_381::Fr;
+use ark_ff::{One, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use circuit::constraint_system::{ConstraintSystem, Variable};
+use circuit::gadgets::arithmetic::{assert_equal, assert_nonzero, linear_combination, sub};
+use circuit::gadgets::transcript::TranscriptVar;
+use circuit::gate::Gate;
+use kzg::{Commitment, OpeningProof, combine};
+use proof::key::{ProvingKey, VerifyingKey, custom_selector_offsets, wire_shifts};
+use proof::transcript::{PoseidonTranscript, poseidon_limbs};
+use proof::{Proof, prove_with};
+use setup::TrustedSetup;
+
+use crate::verify_with;
+
+/// Wires per row of the outer circuit. Four lets every Poseidon round
+/// constant fold into one linear row.
+pub const OUTER_WIRES: usize = 4;
+
+/// Custom gates whose constraints the outer circuit can evaluate.
+const IN_CIRCUIT_GATES: [&str; 2] = ["boolean", "pow5"];
+
+/// The inner proof's KZG openings, as the outer circuit batched them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeferredOpenings {
+    pub zeta: Fr,
+    /// Batching challenge of both openings
+    pub v: Fr,
+    /// Σ vⁱ·yᵢ over the evaluations opened at ζ
+    pub zeta_eval: Fr,
+    /// Σ vⁱ·yᵢ over the evaluations opened at ζ·ω
+    pub zeta_omega_eval: Fr,
+}
+
+/// An outer proof together with the inner commitments its deferred
+/// openings refer to.
+///
+/// The inner proof's evaluations are checked by the outer circuit and not
+/// carried along.
+#[derive(Clone, Debug)]
+pub struct RecursiveProof {
+    pub outer: Proof,
+    pub wire_comms: Vec<Commitment>,
+    pub z_comm: Commitment,
+    pub t_comms: Vec<Commitment>,
+    pub opening: OpeningProof,
+    pub next_opening: OpeningProof,
+    pub deferred: DeferredOpenings,
+}
+
+impl RecursiveProof {
+    /// Public inputs of the outer circuit: the inner public inputs, the
+    /// limbs of each inner commitment in transcript order, then ζ, v and
+    /// the two batched evaluations.
+    pub fn outer_public_inputs(&self, public_inputs: &[Fr]) -> Vec<Fr> {
+        let mut inputs = public_inputs.to_vec();
+        for comm in self
+            .wire_comms
+            .iter()
+            .chain([&self.z_comm])
+            .chain(&self.t_comms)
+        {
+            inputs.extend(poseidon_limbs(&comm.0));
+        }
+        let deferred = &self.deferred;
+        inputs.extend([
+            deferred.zeta,
+            deferred.v,
+            deferred.zeta_eval,
+            deferred.zeta_omega_eval,
+        ]);
+        inputs
+    }
+}
+
+/// Builds the outer circuit verifying `proof` against `vk`.
+///
+/// The rows depend only on `vk` and the number of public inputs, so the
+/// outer key can be preprocessed from any proof for the same circuit. The
+/// inner verifying key is baked in as constants.
+pub fn verifier_circuit(
+    vk: &VerifyingKey,
+    public_inputs: &[Fr],
+    proof: &Proof,
+) -> Result<(ConstraintSystem, DeferredOpenings), String> {
+    if let Some(lookup) = &vk.lookup {
+        return Err(format!(
+            "lookup argument over {} table columns of width {} cannot be verified in-circuit",
+            lookup.table_comms.len(),
+            lookup.width
+        ));
+    }
+    if proof.lookup.is_some() {
+        return Err("proof has a lookup argument but the verifying key has none".to_string());
+    }
+    if let Some(gate) = vk
+        .custom_gates
+        .iter()
+        .find(|gate| !IN_CIRCUIT_GATES.contains(&gate.name()))
+    {
+        return Err(format!(
+            "custom gate {} cannot be verified in-circuit; only {} can",
+            gate.name(),
+            IN_CIRCUIT_GATES.join(" and ")
+        ));
+    }
+    if public_inputs.len() != vk.public_input_rows.len()
+        || proof.wire_comms.len() != vk.num_wires
+        || proof.wire_evals.len() != vk.num_wires
+        || proof.wire_next_evals.len() != vk.num_wires
+        || proof.sigma_evals.len() != vk.num_wires
+        || proof.selector_evals.len() != vk.selector_comms.len()
+        || proof.t_comms.len() != vk.num_quotient_chunks()
+        || proof.t_evals.len() != vk.num_quotient_chunks()
+        || proof.custom_selector_evals.len() != vk.custom_selector_comms.len()
+    {
+        return Err("proof does not match the verifying key".to_string());
+    }
+
+    let mut outer = ConstraintSystem::with_wires(OUTER_WIRES);
+    let cs = &mut outer;
+
+    // Transcript, in the order of `new_transcript` and `verify_with`
+    let mut transcript = TranscriptVar::new(cs, b"plonk");
+    let n = cs.constant(Fr::from(vk.domain_size as u64));
+    transcript.append_scalar(cs, b"n", n);
+    for comm in vk
+        .selector_comms
+        .iter()
+        .chain(&vk.custom_selector_comms)
+        .chain(&vk.sigma_comms)
+    {
+        let limbs = poseidon_limbs(&comm.0).map(|limb| cs.constant(limb));
+        transcript.append_point(cs, b"vk", &limbs);
+    }
+    let inputs: Vec<Variable> = public_inputs
+        .iter()
+        .map(|&input| cs.public_input(input))
+        .collect();
+    for &input in &inputs {
+        transcript.append_scalar(cs, b"pi", input);
+    }
+
+    for comm in &proof.wire_comms {
+        let limbs = public_limbs(cs, comm);
+        transcript.append_point(cs, b"wire", &limbs);
+    }
+    let beta = transcript.challenge(cs, b"beta");
+    let gamma = transcript.challenge(cs, b"gamma");
+    // Lookup challenges go unused but still advance the transcript
+    transcript.challenge(cs, b"eta");
+    transcript.challenge(cs, b"delta");
+    let limbs = public_limbs(cs, &proof.z_comm);
+    transcript.append_point(cs, b"z", &limbs);
+    let alpha = transcript.challenge(cs, b"alpha");
+    for comm in &proof.t_comms {
+        let limbs = public_limbs(cs, comm);
+        transcript.append_point(cs, b"t", &limbs);
+    }
+    let zeta = transcript.challenge(cs, b"zeta");
+
+    let mut alloc_evals = |cs: &mut ConstraintSystem, evals: &[Fr]| -> Vec<Variable> {
+        evals
+            .iter()
+            .map(|&eval| {
+                let var = cs.alloc(eval);
+                transcript.append_scalar(cs, b"eval", var);
+                var
+            })
+            .collect()
+    };
+    let wires = alloc_evals(cs, &proof.wire_evals);
+    let wires_next = alloc_evals(cs, &proof.wire_next_evals);
+    let selectors = alloc_evals(cs, &proof.selector_evals);
+    let custom_selectors = alloc_evals(cs, &proof.custom_selector_evals);
+    let sigmas = alloc_evals(cs, &proof.sigma_evals);
+    let z = alloc_evals(cs, &[proof.z_eval])[0];
+    let z_next = alloc_evals(cs, &[proof.z_next_eval])[0];
+    let t_evals = alloc_evals(cs, &proof.t_evals);
+    let v = transcript.challenge(cs, b"v");
+
+    // Z_H(ζ) = ζⁿ - 1, which must not vanish
+    let domain = vk.domain();
+    let mut zeta_n = zeta;
+    for _ in 0..vk.domain_size.trailing_zeros() {
+        zeta_n = cs.mul(zeta_n, zeta_n);
+    }
+    let vanishing = linear_combination(cs, &[(Fr::one(), zeta_n)], -Fr::one());
+    assert_nonzero(cs, vanishing);
+
+    // Gate, with PI = -Σ xᵢ·L_rowᵢ(ζ)
+    let mut gate_terms = Vec::new();
+    for (&row, &input) in vk.public_input_rows.iter().zip(&inputs) {
+        let lagrange = lagrange_eval(cs, &domain, row, zeta, vanishing);
+        gate_terms.push((-Fr::one(), cs.mul(input, lagrange)));
+    }
+    let ab = cs.mul(wires[0], wires[1]);
+    let products = [wires[0], wires[1], wires[2], ab];
+    let extra = selectors[5..].iter().zip(&wires[3..]);
+    for (&selector, &wire) in selectors[..4].iter().zip(&products).chain(extra) {
+        gate_terms.push((Fr::one(), cs.mul(selector, wire)));
+    }
+    gate_terms.push((Fr::one(), selectors[4]));
+    let gate_eval = linear_combination(cs, &gate_terms, Fr::zero());
+
+    // z·Π(wⱼ + β·kⱼ·ζ + γ) - z(ωζ)·Π(wⱼ + β·σⱼ + γ)
+    let beta_zeta = cs.mul(beta, zeta);
+    let mut identity_product = z;
+    let mut sigma_product = z_next;
+    for ((&wire, shift), &sigma) in wires.iter().zip(wire_shifts(vk.num_wires)).zip(&sigmas) {
+        let term = linear_combination(
+            cs,
+            &[(Fr::one(), wire), (shift, beta_zeta), (Fr::one(), gamma)],
+            Fr::zero(),
+        );
+        identity_product = cs.mul(identity_product, term);
+        let beta_sigma = cs.mul(beta, sigma);
+        let term = linear_combination(
+            cs,
+            &[
+                (Fr::one(), wire),
+                (Fr::one(), beta_sigma),
+                (Fr::one(), gamma),
+            ],
+            Fr::zero(),
+        );
+        sigma_product = cs.mul(sigma_product, term);
+    }
+    let perm_eval = sub(cs, identity_product, sigma_product);
+
+    let l1 = lagrange_eval(cs, &domain, 0, zeta, vanishing);
+    let z_minus_one = linear_combination(cs, &[(Fr::one(), z)], -Fr::one());
+    let boundary_eval = cs.mul(l1, z_minus_one);
+
+    let alpha_squared = cs.mul(alpha, alpha);
+    let mut terms = vec![
+        (Fr::one(), gate_eval),
+        (Fr::one(), cs.mul(alpha, perm_eval)),
+        (Fr::one(), cs.mul(alpha_squared, boundary_eval)),
+    ];
+    let mut alpha_power = cs.mul(alpha_squared, alpha);
+    let offsets = custom_selector_offsets(&vk.custom_gates);
+    for (gate, offset) in vk.custom_gates.iter().zip(offsets) {
+        let selector = custom_selectors[offset];
+        let constraint = match gate.name() {
+            // q_bool·(a² - a)
+            "boolean" => {
+                let a_squared = cs.mul(wires[0], wires[0]);
+                let difference = sub(cs, a_squared, wires[0]);
+                cs.mul(selector, difference)
+            }
+            // q_pow5·(a⁵ - c)
+            "pow5" => {
+                let a_squared = cs.mul(wires[0], wires[0]);
+                let a_fourth = cs.mul(a_squared, a_squared);
+                let a_fifth = cs.mul(a_fourth, wires[0]);
+                let difference = sub(cs, a_fifth, wires[2]);
+                cs.mul(selector, difference)
+            }
+            name => unreachable!("custom gate {name} was checked up front"),
+        };
+        terms.push((Fr::one(), cs.mul(alpha_power, constraint)));
+        alpha_power = cs.mul(alpha_power, alpha);
+    }
+    let constraint_eval = linear_combination(cs, &terms, Fr::zero());
+
+    // t(ζ) = Σ ζⁱⁿ·tᵢ(ζ)
+    let t_eval = horner(cs, &t_evals, zeta_n);
+    let expected = cs.mul(t_eval, vanishing);
+    assert_equal(cs, constraint_eval, expected);
+
+    // Batched evaluations, in the order of `zeta_openings` and
+    // `zeta_omega_openings`
+    let zeta_values: Vec<Variable> = wires
+        .iter()
+        .chain(&selectors)
+        .chain(&custom_selectors)
+        .chain(&sigmas)
+        .chain([&z])
+        .chain(&t_evals)
+        .copied()
+        .collect();
+    let zeta_eval = horner(cs, &zeta_values, v);
+    let zeta_omega_values: Vec<Variable> = wires_next.iter().chain([&z_next]).copied().collect();
+    let zeta_omega_eval = horner(cs, &zeta_omega_values, v);
+
+    for var in [zeta, v, zeta_eval, zeta_omega_eval] {
+        cs.expose(var);
+    }
+    let deferred = DeferredOpenings {
+        zeta: cs.value(zeta),
+        v: cs.value(v),
+        zeta_eval: cs.value(zeta_eval),
+        zeta_omega_eval: cs.value(zeta_omega_eval),
+    };
+
+    Ok((outer, deferred))
+}
+
+/// Proves that `proof` verifies against `inner_vk`, with `outer_pk`
+/// preprocessed from [`verifier_circuit`] for the same inner key.
+///
+/// Fails if the inner proof does not verify, so no unsatisfiable outer
+/// circuit is ever proven.
+pub fn prove_recursive(
+    setup: &TrustedSetup,
+    outer_pk: &ProvingKey,
+    inner_vk: &VerifyingKey,
+    public_inputs: &[Fr],
+    proof: &Proof,
+) -> Result<RecursiveProof, String> {
+    if !verify_with::<PoseidonTranscript>(setup, inner_vk, public_inputs, proof) {
+        return Err("inner proof does not verify".to_string());
+    }
+    let (cs, deferred) = verifier_circuit(inner_vk, public_inputs, proof)?;
+    let outer = prove_with::<PoseidonTranscript>(setup, outer_pk, &cs);
+
+    Ok(RecursiveProof {
+        outer,
+        wire_comms: proof.wire_comms.clone(),
+        z_comm: proof.z_comm.clone(),
+        t_comms: proof.t_comms.clone(),
+        opening: proof.opening.clone(),
+        next_opening: proof.next_opening.clone(),
+        deferred,
+    })
+}
+
+/// Verifies a [`RecursiveProof`]: the outer proof, then the two openings
+/// the outer circuit deferred.
+///
+/// `outer_vk` must have been preprocessed for `inner_vk`; the outer
+/// circuit holds the inner key's commitments as constants.
+pub fn verify_recursive(
+    setup: &TrustedSetup,
+    outer_vk: &VerifyingKey,
+    inner_vk: &VerifyingKey,
+    public_inputs: &[Fr],
+    proof: &RecursiveProof,
+) -> bool {
+    if proof.wire_comms.len() != inner_vk.num_wires
+        || proof.t_comms.len() != inner_vk.num_quotient_chunks()
+    {
+        return false;
+    }
+
+    let outer_inputs = proof.outer_public_inputs(public_inputs);
+    if !verify_with::<PoseidonTranscript>(setup, outer_vk, &outer_inputs, &proof.outer) {
+        return false;
+    }
+
+    let deferred = &proof.deferred;
+    let zeta_comms: Vec<Commitment> = proof
+        .wire_comms
+        .iter()
+        .chain(&inner_vk.selector_comms)
+        .chain(&inner_vk.custom_selector_comms)
+        .chain(&inner_vk.sigma_comms)
+        .chain([&proof.z_comm])
+        .chain(&proof.t_comms)
+        .cloned()
+        .collect();
+    if !kzg::verify(
+        setup,
+        &combine(&zeta_comms, deferred.v),
+        deferred.zeta,
+        deferred.zeta_eval,
+        &proof.opening,
+    ) {
+        return false;
+    }
+
+    let zeta_omega_comms: Vec<Commitment> = proof
+        .wire_comms
+        .iter()
+        .chain([&proof.z_comm])
+        .cloned()
+        .collect();
+    kzg::verify(
+        setup,
+        &combine(&zeta_omega_comms, deferred.v),
+        deferred.zeta * inner_vk.domain().group_gen(),
+        deferred.zeta_omega_eval,
+        &proof.next_opening,
+    )
+}
+
+/// Allocates a commitment's limbs as public inputs.
+fn public_limbs(cs: &mut ConstraintSystem, comm: &Commitment) -> [Variable; 4] {
+    poseidon_limbs(&comm.0).map(|limb| cs.public_input(limb))
+}
+
+/// Returns L_i(ζ) = ωⁱ·Z_H(ζ) / (n·(ζ - ωⁱ)), constrained by
+/// L_i·n·(ζ - ωⁱ) = ωⁱ·Z_H(ζ). The caller has ruled out Z_H(ζ) = 0, so
+/// the divisor is non-zero.
+fn lagrange_eval(
+    cs: &mut ConstraintSystem,
+    domain: &Radix2EvaluationDomain<Fr>,
+    i: usize,
+    zeta: Variable,
+    vanishing: Variable,
+) -> Variable {
+    let omega_i = domain.element(i);
+    let n = domain.size_as_field_element();
+    let divisor = linear_combination(cs, &[(n, zeta)], -n * omega_i);
+    let lagrange =
+        cs.assign(move |w| Ok(omega_i * w.get(vanishing)? * w.inverse(w.get(divisor)?)?));
+    let gate = Gate {
+        q_o: -omega_i,
+        q_m: Fr::one(),
+        ..Gate::disabled()
+    };
+    cs.add_gate(gate, &[lagrange, divisor, vanishing]);
+    lagrange
+}
+
+/// Returns Σ xⁱ·coeffsᵢ.
+fn horner(cs: &mut ConstraintSystem, coeffs: &[Variable], x: Variable) -> Variable {
+    let (&last, rest) = coeffs.split_last().expect("at least one coefficient");
+    rest.iter().rev().fold(last, |acc, &coeff| {
+        let shifted = cs.mul(acc, x);
+        cs.add(shifted, coeff)
+    })
+}
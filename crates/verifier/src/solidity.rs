@@ -0,0 +1,1138 @@
+//! Solidity verifier generation.
+//!
+//! [`generate_verifier`] turns a verifying key into a contract whose
+//! `verifyProof(bytes proof, uint256[] publicInputs)` runs the same checks
+//! as [`crate::verify_with`]. The verification itself is inline assembly:
+//! it recomputes the transcript byte for byte, evaluates the constraint
+//! identity at ζ and checks both batched openings with the EIP-2537
+//! BLS12-381 precompiles. Either byte transcript works; the Keccak-256 one
+//! absorbs the proof as the contract holds it and is the cheaper of the two.
+//!
+//! The contract reads proofs in the layout of [`encode_proof`]: every
+//! field of [`Proof`] in declaration order, G1 points in the 128-byte
+//! EIP-2537 encoding and scalars as 32-byte big-endian words.
+//! [`encode_calldata`] wraps a proof and its public inputs into a call.
+
+use std::fmt::Write;
+
+use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_poly::EvaluationDomain;
+use circuit::gate::CustomGate;
+use proof::key::{custom_selector_offsets, wire_shifts};
+use proof::transcript::{
+    Keccak256Transcript, Sha256Transcript, Transcript, eip2537_fp, eip2537_g1,
+};
+use proof::{Proof, VerifyingKey, new_transcript};
+use setup::TrustedSetup;
+use sha3::{Digest, Keccak256};
+
+/// Signature of the generated contract's entry point.
+pub const VERIFY_SIGNATURE: &str = "verifyProof(bytes,uint256[])";
+
+const POINT: usize = 128;
+const WORD: usize = 32;
+
+/// Precompiles the contract calls: SHA-256, modexp and the EIP-2537
+/// G1 multi-scalar multiplication and pairing.
+const PRECOMPILES: [(&str, u8); 4] = [
+    ("SHA256_PRECOMPILE", 0x02),
+    ("MODEXP_PRECOMPILE", 0x05),
+    ("G1_MSM_PRECOMPILE", 0x0c),
+    ("PAIRING_PRECOMPILE", 0x0f),
+];
+
+/// Transcripts the generated contract can replay.
+pub trait SolidityTranscript: Transcript<Curve = Bls12_381> {
+    /// Yul for `absorbScalar`, `absorbPoint` and `squeeze`
+    const ABSORB: &'static str;
+
+    /// Bytes absorbed since the last challenge.
+    fn buffer(&self) -> &[u8];
+}
+
+impl SolidityTranscript for Sha256Transcript {
+    const ABSORB: &'static str = SHA256_ABSORB;
+
+    fn buffer(&self) -> &[u8] {
+        self.buffer()
+    }
+}
+
+impl SolidityTranscript for Keccak256Transcript {
+    const ABSORB: &'static str = KECCAK256_ABSORB;
+
+    fn buffer(&self) -> &[u8] {
+        self.buffer()
+    }
+}
+
+/// Proof bytes as the generated contract reads them.
+pub fn encode_proof(proof: &Proof) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let points = |bytes: &mut Vec<u8>, points: &[&G1Affine]| {
+        for point in points {
+            bytes.extend(eip2537_g1(point));
+        }
+    };
+    let scalars = |bytes: &mut Vec<u8>, scalars: &[Fr]| {
+        for scalar in scalars {
+            bytes.extend(scalar_bytes(scalar));
+        }
+    };
+
+    points(
+        &mut bytes,
+        &proof.wire_comms.iter().map(|c| &c.0).collect::<Vec<_>>(),
+    );
+    points(&mut bytes, &[&proof.z_comm.0]);
+    points(
+        &mut bytes,
+        &proof.t_comms.iter().map(|c| &c.0).collect::<Vec<_>>(),
+    );
+    scalars(&mut bytes, &proof.wire_evals);
+    scalars(&mut bytes, &proof.wire_next_evals);
+    scalars(&mut bytes, &proof.selector_evals);
+    scalars(&mut bytes, &proof.custom_selector_evals);
+    scalars(&mut bytes, &proof.sigma_evals);
+    scalars(&mut bytes, &[proof.z_eval, proof.z_next_eval]);
+    scalars(&mut bytes, &proof.t_evals);
+    if let Some(lookup) = &proof.lookup {
+        points(&mut bytes, &[&lookup.m_comm.0, &lookup.phi_comm.0]);
+        scalars(
+            &mut bytes,
+            &[
+                lookup.q_lookup_eval,
+                lookup.q_table_eval,
+                lookup.table_tag_eval,
+            ],
+        );
+        scalars(&mut bytes, &lookup.table_evals);
+        scalars(
+            &mut bytes,
+            &[lookup.m_eval, lookup.phi_eval, lookup.phi_next_eval],
+        );
+    }
+    points(&mut bytes, &[&proof.opening.0, &proof.next_opening.0]);
+    bytes
+}
+
+/// ABI-encoded call of `verifyProof` with a proof and its public inputs.
+pub fn encode_calldata(proof: &Proof, public_inputs: &[Fr]) -> Vec<u8> {
+    let proof = encode_proof(proof);
+    let padded_len = proof.len().div_ceil(WORD) * WORD;
+
+    let mut calldata = Keccak256::digest(VERIFY_SIGNATURE)[..4].to_vec();
+    calldata.extend(u256_bytes(2 * WORD));
+    calldata.extend(u256_bytes(3 * WORD + padded_len));
+    calldata.extend(u256_bytes(proof.len()));
+    calldata.extend(&proof);
+    calldata.resize(calldata.len() + padded_len - proof.len(), 0);
+    calldata.extend(u256_bytes(public_inputs.len()));
+    for input in public_inputs {
+        calldata.extend(scalar_bytes(input));
+    }
+    calldata
+}
+
+/// G2 point in the EIP-2537 encoding: x.c0, x.c1, y.c0, y.c1.
+pub fn g2_bytes(point: &G2Affine) -> Vec<u8> {
+    let (x, y) = point.xy().expect("setup points are not at infinity");
+    [
+        eip2537_fp(&x.c0),
+        eip2537_fp(&x.c1),
+        eip2537_fp(&y.c0),
+        eip2537_fp(&y.c1),
+    ]
+    .concat()
+}
+
+fn scalar_bytes(value: &Fr) -> Vec<u8> {
+    value.into_bigint().to_bytes_be()
+}
+
+fn u256_bytes(value: usize) -> Vec<u8> {
+    let mut bytes = vec![0; WORD - 8];
+    bytes.extend((value as u64).to_be_bytes());
+    bytes
+}
+
+/// Generates the verifier contract for one circuit and proofs made with
+/// the transcript `T`.
+///
+/// Fails if the circuit uses a custom gate with no Solidity counterpart.
+pub fn generate_verifier<T: SolidityTranscript>(
+    setup: &TrustedSetup,
+    vk: &VerifyingKey,
+) -> Result<String, String> {
+    let gates = vk
+        .custom_gates
+        .iter()
+        .map(|gate| GateCode::new(gate.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let prefix = new_transcript::<T>(vk, &[]).buffer().to_vec();
+    let layout = Layout::new(vk, prefix.len());
+    let mut out = String::new();
+    contract::<T>(&mut out, setup, vk, &prefix, &layout, &gates)
+        .expect("writing to a String cannot fail");
+    Ok(out)
+}
+
+/// Memory addresses of everything the contract keeps in memory.
+///
+/// The proof is copied to memory as is, so each proof field's constant
+/// is its address there.
+struct Layout {
+    constants: Vec<(&'static str, usize)>,
+    proof_length: usize,
+    /// Polynomials opened at ζ
+    num_zeta_openings: usize,
+}
+
+/// Scratch slots for challenges and running values.
+const SLOTS: [&str; 14] = [
+    "BETA",
+    "GAMMA",
+    "ETA",
+    "DELTA",
+    "ALPHA",
+    "ZETA",
+    "V",
+    "ZETA_N",
+    "VANISHING",
+    "L1",
+    "PI_EVAL",
+    "POWER",
+    "ACC",
+    "SCRATCH",
+];
+
+impl Layout {
+    fn new(vk: &VerifyingKey, prefix_len: usize) -> Self {
+        let num_wires = vk.num_wires;
+        let chunks = vk.num_quotient_chunks();
+        let num_custom = vk.custom_selector_comms.len();
+
+        let mut constants = Vec::new();
+        let mut address = 0x80;
+        for slot in SLOTS {
+            constants.push((slot, address));
+            address += WORD;
+        }
+        // SCRATCH holds the six words of a modexp call
+        address += 5 * WORD;
+
+        let proof_start = address;
+        let mut field = |name, size| {
+            constants.push((name, address));
+            address += size;
+        };
+        field("WIRES", num_wires * POINT);
+        field("Z", POINT);
+        field("T", chunks * POINT);
+        field("WIRE_EVALS", num_wires * WORD);
+        field("WIRE_NEXT_EVALS", num_wires * WORD);
+        field("SELECTOR_EVALS", vk.selector_comms.len() * WORD);
+        field("CUSTOM_SELECTOR_EVALS", num_custom * WORD);
+        field("SIGMA_EVALS", num_wires * WORD);
+        field("Z_EVAL", WORD);
+        field("Z_NEXT_EVAL", WORD);
+        field("T_EVALS", chunks * WORD);
+        if let Some(lookup) = &vk.lookup {
+            field("M", POINT);
+            field("PHI", POINT);
+            field("Q_LOOKUP_EVAL", WORD);
+            field("Q_TABLE_EVAL", WORD);
+            field("TABLE_TAG_EVAL", WORD);
+            field("TABLE_EVALS", lookup.width * WORD);
+            field("M_EVAL", WORD);
+            field("PHI_EVAL", WORD);
+            field("PHI_NEXT_EVAL", WORD);
+        }
+        field("OPENING", POINT);
+        field("NEXT_OPENING", POINT);
+        let proof_length = address - proof_start;
+
+        let num_zeta_openings = num_wires
+            + vk.selector_comms.len()
+            + num_custom
+            + num_wires
+            + 1
+            + chunks
+            + vk.lookup.as_ref().map_or(0, |lookup| lookup.width + 5);
+
+        constants.push(("PUBLIC_INPUTS", address));
+        address += vk.public_input_rows.len() * WORD;
+        // Room for absorbing everything at once: a labelled point or scalar
+        // takes at most twice its size in the proof, plus a word of slack
+        // for the last mstore
+        constants.push(("TRANSCRIPT", address));
+        address +=
+            prefix_len + vk.public_input_rows.len() * (2 + WORD) + 2 * proof_length + 2 * WORD;
+        constants.push(("MSM", address));
+        address += (num_zeta_openings + 2) * (POINT + WORD);
+        // opening, τ·G2, combined point, -G2
+        constants.push(("PAIRING", address));
+
+        Layout {
+            constants,
+            proof_length,
+            num_zeta_openings,
+        }
+    }
+}
+
+/// Yul for the constraints of one custom gate.
+struct GateCode {
+    /// Helper functions the constraints call
+    functions: &'static str,
+    /// One expression per constraint, `{q}` standing for the selector
+    constraints: Vec<String>,
+}
+
+impl GateCode {
+    fn new(gate: &dyn CustomGate) -> Result<Self, String> {
+        let param = |key: &str| {
+            let (_, value) = gate
+                .params()
+                .into_iter()
+                .find(|(k, _)| *k == key)
+                .expect("builtin gates report their parameters");
+            fr_hex(&value)
+        };
+        Ok(match gate.name() {
+            "boolean" => GateCode {
+                functions: BOOLEAN_GATE,
+                constraints: vec!["booleanGate({q})".to_string()],
+            },
+            "pow5" => GateCode {
+                functions: POW5_GATE,
+                constraints: vec!["pow5Gate({q})".to_string()],
+            },
+            "edwards_add" => GateCode {
+                functions: EDWARDS_ADD_GATE,
+                constraints: vec![
+                    format!("edwardsAddX({{q}}, {})", param("d")),
+                    format!("edwardsAddY({{q}}, {}, {})", param("a"), param("d")),
+                ],
+            },
+            other => {
+                return Err(format!(
+                    "Custom gate {other} has no Solidity implementation"
+                ));
+            }
+        })
+    }
+}
+
+const BOOLEAN_GATE: &str = "
+            // q·(a² - a)
+            function booleanGate(q) -> c {
+                let a := wire(0)
+                c := fmul(q, fsub(fmul(a, a), a))
+            }";
+
+const POW5_GATE: &str = "
+            // q·(a⁵ - c)
+            function pow5Gate(q) -> c {
+                let a := wire(0)
+                let a2 := fmul(a, a)
+                c := fmul(q, fsub(fmul(fmul(a2, a2), a), wire(2)))
+            }";
+
+const EDWARDS_ADD_GATE: &str = "
+            // q·(x3·(1 + t) - (x1·y2 + y1·x2)) with t = d·x1·x2·y1·y2
+            function edwardsAddX(q, d) -> c {
+                let x1 := wire(0)
+                let y1 := wire(1)
+                let x2 := wire(2)
+                let y2 := nextWire(0)
+                let t := fmul(d, fmul(fmul(x1, x2), fmul(y1, y2)))
+                c := fmul(q, fsub(fmul(nextWire(1), fadd(1, t)), fadd(fmul(x1, y2), fmul(y1, x2))))
+            }
+
+            // q·(y3·(1 - t) - (y1·y2 - a·x1·x2))
+            function edwardsAddY(q, a, d) -> c {
+                let x1 := wire(0)
+                let y1 := wire(1)
+                let x2 := wire(2)
+                let y2 := nextWire(0)
+                let t := fmul(d, fmul(fmul(x1, x2), fmul(y1, y2)))
+                c := fmul(q, fsub(fmul(nextWire(2), fsub(1, t)), fsub(fmul(y1, y2), fmul(a, fmul(x1, x2)))))
+            }";
+
+fn hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{digits}")
+}
+
+fn fr_hex(value: &Fr) -> String {
+    hex(&scalar_bytes(value))
+}
+
+/// The four words of a G1 point, for an `mstore` each.
+fn g1_words(point: &G1Affine) -> Vec<String> {
+    eip2537_g1(point).chunks(WORD).map(hex).collect()
+}
+
+fn contract<T: SolidityTranscript>(
+    out: &mut String,
+    setup: &TrustedSetup,
+    vk: &VerifyingKey,
+    prefix: &[u8],
+    layout: &Layout,
+    gates: &[GateCode],
+) -> std::fmt::Result {
+    let domain = vk.domain();
+    let half_p = (-Fq::from(1u64)) * Fq::from(2u64).inverse().expect("p is odd");
+    let half_p = eip2537_fp(&half_p);
+
+    writeln!(out, "// SPDX-License-Identifier: MIT")?;
+    writeln!(out, "pragma solidity ^0.8.20;")?;
+    writeln!(out)?;
+    writeln!(out, "/// @title PLONK verifier")?;
+    writeln!(
+        out,
+        "/// @notice Generated from the verifying key of a circuit with {} rows.",
+        vk.domain_size
+    )?;
+    writeln!(
+        out,
+        "/// @dev Needs the EIP-2537 BLS12-381 precompiles. Proofs are G1 points in"
+    )?;
+    writeln!(
+        out,
+        "/// the 128-byte EIP-2537 encoding and 32-byte big-endian scalars, in the"
+    )?;
+    writeln!(out, "/// field order of the prover's proof structure.")?;
+    writeln!(out, "contract PlonkVerifier {{")?;
+    writeln!(out, "    // Scalar field modulus")?;
+    writeln!(
+        out,
+        "    uint256 internal constant R = {};",
+        hex(&Fr::MODULUS.to_bytes_be())
+    )?;
+    writeln!(
+        out,
+        "    // (p - 1) / 2 for the base field, as its top 16 and low 32 bytes"
+    )?;
+    writeln!(
+        out,
+        "    uint256 internal constant HALF_P_HI = {};",
+        hex(&half_p[..WORD])
+    )?;
+    writeln!(
+        out,
+        "    uint256 internal constant HALF_P_LO = {};",
+        hex(&half_p[WORD..])
+    )?;
+    writeln!(out)?;
+    for (name, address) in PRECOMPILES {
+        writeln!(
+            out,
+            "    uint256 internal constant {name} = {address:#04x};"
+        )?;
+    }
+    writeln!(out)?;
+    writeln!(out, "    uint256 internal constant N = {};", vk.domain_size)?;
+    writeln!(
+        out,
+        "    uint256 internal constant LOG_N = {};",
+        vk.domain_size.trailing_zeros()
+    )?;
+    writeln!(
+        out,
+        "    uint256 internal constant OMEGA = {};",
+        fr_hex(&domain.group_gen())
+    )?;
+    writeln!(
+        out,
+        "    uint256 internal constant NUM_WIRES = {};",
+        vk.num_wires
+    )?;
+    writeln!(
+        out,
+        "    uint256 internal constant NUM_CHUNKS = {};",
+        vk.num_quotient_chunks()
+    )?;
+    writeln!(
+        out,
+        "    uint256 internal constant NUM_PUBLIC_INPUTS = {};",
+        vk.public_input_rows.len()
+    )?;
+    if let Some(lookup) = &vk.lookup {
+        writeln!(
+            out,
+            "    uint256 internal constant LOOKUP_WIDTH = {};",
+            lookup.width
+        )?;
+    }
+    writeln!(
+        out,
+        "    uint256 internal constant PROOF_LENGTH = {};",
+        layout.proof_length
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    // Memory: challenges and running values, then the proof, the public"
+    )?;
+    writeln!(
+        out,
+        "    // inputs and buffers for the transcript and precompile calls"
+    )?;
+    for (name, address) in &layout.constants {
+        writeln!(out, "    uint256 internal constant {name} = {address:#x};")?;
+    }
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    function verifyProof(bytes calldata proof, uint256[] calldata publicInputs)"
+    )?;
+    writeln!(out, "        external")?;
+    writeln!(out, "        view")?;
+    writeln!(out, "        returns (bool valid)")?;
+    writeln!(out, "    {{")?;
+    writeln!(out, "        assembly {{")?;
+    out.push_str(HELPERS);
+    out.push_str(T::ABSORB);
+    for function in gates
+        .iter()
+        .map(|gate| gate.functions)
+        .collect::<std::collections::BTreeSet<_>>()
+    {
+        out.push_str(function);
+        out.push('\n');
+    }
+    absorb_verifying_key(out, prefix)?;
+    public_input_eval(out, vk, &domain)?;
+    constraint_eval(out, vk, gates)?;
+    if vk.lookup.is_some() {
+        out.push_str(LOOKUP_EVAL);
+    }
+    check_opening(out, setup)?;
+    zeta_openings(out, vk, layout)?;
+    main_body(out, vk)?;
+    writeln!(out, "            valid := 1")?;
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+const HELPERS: &str = "            function fail() {
+                mstore(0x00, 0)
+                return(0x00, 0x20)
+            }
+
+            function fadd(a, b) -> c {
+                c := addmod(a, b, R)
+            }
+
+            function fsub(a, b) -> c {
+                c := addmod(a, sub(R, b), R)
+            }
+
+            function fmul(a, b) -> c {
+                c := mulmod(a, b, R)
+            }
+
+            // x^(R - 2) with the modexp precompile
+            function inverse(x) -> y {
+                mstore(SCRATCH, 0x20)
+                mstore(add(SCRATCH, 0x20), 0x20)
+                mstore(add(SCRATCH, 0x40), 0x20)
+                mstore(add(SCRATCH, 0x60), x)
+                mstore(add(SCRATCH, 0x80), sub(R, 2))
+                mstore(add(SCRATCH, 0xa0), R)
+                if iszero(staticcall(gas(), MODEXP_PRECOMPILE, SCRATCH, 0xc0, SCRATCH, 0x20)) { fail() }
+                y := mload(SCRATCH)
+            }
+
+            function wire(j) -> w {
+                w := mload(add(WIRE_EVALS, mul(j, 0x20)))
+            }
+
+            function nextWire(j) -> w {
+                w := mload(add(WIRE_NEXT_EVALS, mul(j, 0x20)))
+            }
+
+            function checkScalars(p, count) {
+                for { let i := 0 } lt(i, count) { i := add(i, 1) } {
+                    if iszero(lt(mload(add(p, mul(i, 0x20))), R)) { fail() }
+                }
+            }
+
+            // Transcript: labels and values are appended at t, squeezing hashes
+            // everything since TRANSCRIPT and restarts from the digest
+            function absorbLabel(t, label, len) -> next {
+                mstore(t, label)
+                next := add(t, len)
+            }
+
+            // ωⁱ·(ζⁿ - 1) / (n·(ζ - ωⁱ))
+            function lagrange(omegaI) -> l {
+                let denominator := fmul(N, fsub(mload(ZETA), omegaI))
+                l := fmul(fmul(omegaI, mload(VANISHING)), inverse(denominator))
+            }
+
+            // t(ζ) = Σ ζⁱⁿ·tᵢ(ζ)
+            function quotientEval() -> t {
+                let power := 1
+                for { let i := 0 } lt(i, NUM_CHUNKS) { i := add(i, 1) } {
+                    t := fadd(t, fmul(power, mload(add(T_EVALS, mul(i, 0x20)))))
+                    power := fmul(power, mload(ZETA_N))
+                }
+            }
+
+            function copyPoint(dst, src) {
+                mstore(dst, mload(src))
+                mstore(add(dst, 0x20), mload(add(src, 0x20)))
+                mstore(add(dst, 0x40), mload(add(src, 0x40)))
+                mstore(add(dst, 0x60), mload(add(src, 0x60)))
+            }
+
+            // Appends POWER as the scalar of the point at p, folds eval into ACC
+            function addOpening(p, eval) -> next {
+                let power := mload(POWER)
+                mstore(add(p, 0x80), power)
+                mstore(ACC, fadd(mload(ACC), fmul(power, eval)))
+                mstore(POWER, fmul(power, mload(V)))
+                next := add(p, 0xa0)
+            }
+
+            function addProofOpenings(p, points, evals, count) -> next {
+                next := p
+                for { let i := 0 } lt(i, count) { i := add(i, 1) } {
+                    copyPoint(next, add(points, mul(i, 0x80)))
+                    next := addOpening(next, mload(add(evals, mul(i, 0x20))))
+                }
+            }
+
+            function addKeyOpening(p, x0, x1, y0, y1, eval) -> next {
+                mstore(p, x0)
+                mstore(add(p, 0x20), x1)
+                mstore(add(p, 0x40), y0)
+                mstore(add(p, 0x60), y1)
+                next := addOpening(p, eval)
+            }
+";
+
+const SHA256_ABSORB: &str = "
+            // Scalars are absorbed little-endian
+            function absorbScalar(t, label, len, s) -> next {
+                let start := absorbLabel(t, label, len)
+                s := or(shr(8, and(s, 0xff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00)), shl(8, and(s, 0x00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff)))
+                s := or(shr(16, and(s, 0xffff0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff0000)), shl(16, and(s, 0x0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff)))
+                s := or(shr(32, and(s, 0xffffffff00000000ffffffff00000000ffffffff00000000ffffffff00000000)), shl(32, and(s, 0x00000000ffffffff00000000ffffffff00000000ffffffff00000000ffffffff)))
+                s := or(shr(64, and(s, 0xffffffffffffffff0000000000000000ffffffffffffffff0000000000000000)), shl(64, and(s, 0x0000000000000000ffffffffffffffff0000000000000000ffffffffffffffff)))
+                mstore(start, or(shr(128, s), shl(128, s)))
+                next := add(start, 0x20)
+            }
+
+            // Points are absorbed compressed: x big-endian with the compression,
+            // infinity and sign flags in its top bits
+            function absorbPoint(t, label, len, p) -> next {
+                let xHi := mload(p)
+                let xLo := mload(add(p, 0x20))
+                let yHi := mload(add(p, 0x40))
+                let yLo := mload(add(p, 0x60))
+                let flags := 0xc0
+                if or(or(xHi, xLo), or(yHi, yLo)) {
+                    flags := 0x80
+                    if or(gt(yHi, HALF_P_HI), and(eq(yHi, HALF_P_HI), gt(yLo, HALF_P_LO))) { flags := 0xa0 }
+                }
+                let start := absorbLabel(t, label, len)
+                mstore(start, shl(128, or(xHi, shl(120, flags))))
+                mstore(add(start, 0x10), xLo)
+                next := add(start, 0x30)
+            }
+
+            function squeeze(t, label, len, slot) -> next {
+                let end := absorbLabel(t, label, len)
+                if iszero(staticcall(gas(), SHA256_PRECOMPILE, TRANSCRIPT, sub(end, TRANSCRIPT), TRANSCRIPT, 0x20)) { fail() }
+                mstore(slot, mod(mload(TRANSCRIPT), R))
+                next := add(TRANSCRIPT, 0x20)
+            }
+";
+
+const KECCAK256_ABSORB: &str = "
+            // Scalars are absorbed big-endian, as the proof holds them
+            function absorbScalar(t, label, len, s) -> next {
+                let start := absorbLabel(t, label, len)
+                mstore(start, s)
+                next := add(start, 0x20)
+            }
+
+            // Points are absorbed in their EIP-2537 encoding
+            function absorbPoint(t, label, len, p) -> next {
+                let start := absorbLabel(t, label, len)
+                copyPoint(start, p)
+                next := add(start, 0x80)
+            }
+
+            function squeeze(t, label, len, slot) -> next {
+                let end := absorbLabel(t, label, len)
+                let digest := keccak256(TRANSCRIPT, sub(end, TRANSCRIPT))
+                mstore(TRANSCRIPT, digest)
+                mstore(slot, mod(digest, R))
+                next := add(TRANSCRIPT, 0x20)
+            }
+";
+
+fn absorb_verifying_key(out: &mut String, prefix: &[u8]) -> std::fmt::Result {
+    writeln!(out)?;
+    writeln!(out, "            // The circuit's part of the transcript")?;
+    writeln!(out, "            function absorbVerifyingKey(t) -> next {{")?;
+    for (i, chunk) in prefix.chunks(WORD).enumerate() {
+        let mut word = chunk.to_vec();
+        word.resize(WORD, 0);
+        writeln!(
+            out,
+            "                mstore(add(t, {:#x}), {})",
+            i * WORD,
+            hex(&word)
+        )?;
+    }
+    writeln!(out, "                next := add(t, {:#x})", prefix.len())?;
+    writeln!(out, "            }}")
+}
+
+fn public_input_eval(
+    out: &mut String,
+    vk: &VerifyingKey,
+    domain: &impl EvaluationDomain<Fr>,
+) -> std::fmt::Result {
+    writeln!(out)?;
+    writeln!(out, "            // PI(ζ) = Σ -xₖ·L_rowₖ(ζ)")?;
+    writeln!(out, "            function publicInputEval() -> pi {{")?;
+    for (k, &row) in vk.public_input_rows.iter().enumerate() {
+        writeln!(
+            out,
+            "                pi := fsub(pi, fmul(mload(add(PUBLIC_INPUTS, {:#x})), lagrange({})))",
+            k * WORD,
+            fr_hex(&domain.element(row))
+        )?;
+    }
+    writeln!(out, "            }}")
+}
+
+fn constraint_eval(out: &mut String, vk: &VerifyingKey, gates: &[GateCode]) -> std::fmt::Result {
+    let selector = |i: usize| format!("mload(add(SELECTOR_EVALS, {:#x}))", i * WORD);
+    writeln!(out)?;
+    writeln!(
+        out,
+        "            // The combined identity at ζ, as in the prover's quotient"
+    )?;
+    writeln!(out, "            function constraintEval() -> result {{")?;
+    writeln!(
+        out,
+        "                // qL·a + qR·b + qO·c + qM·a·b + qC + Σ qⱼ·wⱼ + PI"
+    )?;
+    writeln!(
+        out,
+        "                result := fmul({}, wire(0))",
+        selector(0)
+    )?;
+    writeln!(
+        out,
+        "                result := fadd(result, fmul({}, wire(1)))",
+        selector(1)
+    )?;
+    writeln!(
+        out,
+        "                result := fadd(result, fmul({}, wire(2)))",
+        selector(2)
+    )?;
+    writeln!(
+        out,
+        "                result := fadd(result, fmul({}, fmul(wire(0), wire(1))))",
+        selector(3)
+    )?;
+    writeln!(
+        out,
+        "                result := fadd(result, {})",
+        selector(4)
+    )?;
+    for j in 3..vk.num_wires {
+        writeln!(
+            out,
+            "                result := fadd(result, fmul({}, wire({j})))",
+            selector(j + 2)
+        )?;
+    }
+    writeln!(
+        out,
+        "                result := fadd(result, mload(PI_EVAL))"
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "                // α·(z·Π(wⱼ + β·kⱼ·ζ + γ) - z(ωζ)·Π(wⱼ + β·σⱼ + γ))"
+    )?;
+    writeln!(out, "                let alpha := mload(ALPHA)")?;
+    writeln!(out, "                let beta := mload(BETA)")?;
+    writeln!(out, "                let gamma := mload(GAMMA)")?;
+    writeln!(out, "                let identity := mload(Z_EVAL)")?;
+    writeln!(out, "                let sigma := mload(Z_NEXT_EVAL)")?;
+    for (j, shift) in wire_shifts(vk.num_wires).iter().enumerate() {
+        writeln!(
+            out,
+            "                identity := fmul(identity, fadd(fadd(wire({j}), fmul(beta, fmul({}, mload(ZETA)))), gamma))",
+            fr_hex(shift)
+        )?;
+        writeln!(
+            out,
+            "                sigma := fmul(sigma, fadd(fadd(wire({j}), fmul(beta, mload(add(SIGMA_EVALS, {:#x})))), gamma))",
+            j * WORD
+        )?;
+    }
+    writeln!(
+        out,
+        "                result := fadd(result, fmul(alpha, fsub(identity, sigma)))"
+    )?;
+    writeln!(out)?;
+    writeln!(out, "                // α²·L1·(z - 1)")?;
+    writeln!(out, "                let alphaPower := fmul(alpha, alpha)")?;
+    writeln!(
+        out,
+        "                result := fadd(result, fmul(alphaPower, fmul(mload(L1), fsub(mload(Z_EVAL), 1))))"
+    )?;
+    writeln!(out, "                alphaPower := fmul(alphaPower, alpha)")?;
+
+    let offsets = custom_selector_offsets(&vk.custom_gates);
+    for ((gate, code), offset) in vk.custom_gates.iter().zip(gates).zip(offsets) {
+        writeln!(out)?;
+        writeln!(out, "                // {}", gate.name())?;
+        let q = format!("mload(add(CUSTOM_SELECTOR_EVALS, {:#x}))", offset * WORD);
+        for constraint in &code.constraints {
+            writeln!(
+                out,
+                "                result := fadd(result, fmul(alphaPower, {}))",
+                constraint.replace("{q}", &q)
+            )?;
+            writeln!(out, "                alphaPower := fmul(alphaPower, alpha)")?;
+        }
+    }
+    if vk.lookup.is_some() {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "                result := fadd(result, fmul(alphaPower, lookupEval()))"
+        )?;
+    }
+    writeln!(out, "            }}")
+}
+
+const LOOKUP_EVAL: &str = "
+            // (φ(ωζ) - φ(ζ))·(δ + f)·(δ + t) - q_lookup·(δ + t) + m·(δ + f) with
+            // f = q_table + η·w₀ + η²·w₁ + … and t = tag + η·t₀ + η²·t₁ + …
+            function lookupEval() -> c {
+                let eta := mload(ETA)
+                let f := mload(Q_TABLE_EVAL)
+                let t := mload(TABLE_TAG_EVAL)
+                let power := eta
+                for { let i := 0 } lt(i, LOOKUP_WIDTH) { i := add(i, 1) } {
+                    f := fadd(f, fmul(power, wire(i)))
+                    t := fadd(t, fmul(power, mload(add(TABLE_EVALS, mul(i, 0x20)))))
+                    power := fmul(power, eta)
+                }
+                f := fadd(mload(DELTA), f)
+                t := fadd(mload(DELTA), t)
+                c := fmul(fmul(fsub(mload(PHI_NEXT_EVAL), mload(PHI_EVAL)), f), t)
+                c := fadd(fsub(c, fmul(mload(Q_LOOKUP_EVAL), t)), fmul(mload(M_EVAL), f))
+            }
+";
+
+fn check_opening(out: &mut String, setup: &TrustedSetup) -> std::fmt::Result {
+    let generator = g1_words(&setup.g1_powers[0]);
+    let g2_tau: Vec<String> = g2_bytes(&setup.g2_tau).chunks(WORD).map(hex).collect();
+    let neg_g2: Vec<String> = g2_bytes(&-setup.g2_gen).chunks(WORD).map(hex).collect();
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "            // Checks the batched opening whose terms run from MSM to p:"
+    )?;
+    writeln!(
+        out,
+        "            // e(π, τ·G2) = e(C - y·G1 + z·π, G2) for C and y folded in ACC"
+    )?;
+    writeln!(out, "            function checkOpening(p, z, opening) {{")?;
+    writeln!(out, "                // -y·G1")?;
+    for (i, word) in generator.iter().enumerate() {
+        writeln!(
+            out,
+            "                mstore(add(p, {:#x}), {word})",
+            i * WORD
+        )?;
+    }
+    writeln!(
+        out,
+        "                mstore(add(p, 0x80), sub(R, mload(ACC)))"
+    )?;
+    writeln!(out, "                // z·π")?;
+    writeln!(out, "                copyPoint(add(p, 0xa0), opening)")?;
+    writeln!(out, "                mstore(add(p, 0x120), z)")?;
+    writeln!(
+        out,
+        "                if iszero(staticcall(gas(), G1_MSM_PRECOMPILE, MSM, sub(add(p, 0x140), MSM), add(PAIRING, 0x180), 0x80)) {{ fail() }}"
+    )?;
+    writeln!(out, "                copyPoint(PAIRING, opening)")?;
+    writeln!(out, "                // τ·G2")?;
+    for (i, word) in g2_tau.iter().enumerate() {
+        writeln!(
+            out,
+            "                mstore(add(PAIRING, {:#x}), {word})",
+            0x80 + i * WORD
+        )?;
+    }
+    writeln!(out, "                // -G2")?;
+    for (i, word) in neg_g2.iter().enumerate() {
+        writeln!(
+            out,
+            "                mstore(add(PAIRING, {:#x}), {word})",
+            0x200 + i * WORD
+        )?;
+    }
+    writeln!(
+        out,
+        "                if iszero(staticcall(gas(), PAIRING_PRECOMPILE, PAIRING, 0x300, 0x00, 0x20)) {{ fail() }}"
+    )?;
+    writeln!(out, "                if iszero(mload(0x00)) {{ fail() }}")?;
+    writeln!(out, "            }}")
+}
+
+fn zeta_openings(out: &mut String, vk: &VerifyingKey, layout: &Layout) -> std::fmt::Result {
+    let key_opening = |out: &mut String, name: String, point: &G1Affine, eval: String| {
+        let words = g1_words(point);
+        writeln!(out, "                // {name}")?;
+        writeln!(
+            out,
+            "                p := addKeyOpening(p, {}, {}, {}, {}, {eval})",
+            words[0], words[1], words[2], words[3]
+        )
+    };
+    let at = |field: &str, i: usize| format!("mload(add({field}, {:#x}))", i * WORD);
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "            // The {} polynomials opened at ζ, in the prover's order",
+        layout.num_zeta_openings
+    )?;
+    writeln!(out, "            function zetaOpenings() -> p {{")?;
+    writeln!(
+        out,
+        "                p := addProofOpenings(MSM, WIRES, WIRE_EVALS, NUM_WIRES)"
+    )?;
+    for (i, comm) in vk.selector_comms.iter().enumerate() {
+        key_opening(
+            out,
+            format!("selector {i}"),
+            &comm.0,
+            at("SELECTOR_EVALS", i),
+        )?;
+    }
+    for (i, comm) in vk.custom_selector_comms.iter().enumerate() {
+        key_opening(
+            out,
+            format!("custom selector {i}"),
+            &comm.0,
+            at("CUSTOM_SELECTOR_EVALS", i),
+        )?;
+    }
+    for (i, comm) in vk.sigma_comms.iter().enumerate() {
+        key_opening(out, format!("sigma {i}"), &comm.0, at("SIGMA_EVALS", i))?;
+    }
+    writeln!(
+        out,
+        "                p := addProofOpenings(p, Z, Z_EVAL, 1)"
+    )?;
+    writeln!(
+        out,
+        "                p := addProofOpenings(p, T, T_EVALS, NUM_CHUNKS)"
+    )?;
+    if let Some(lookup) = &vk.lookup {
+        key_opening(
+            out,
+            "q_lookup".to_string(),
+            &lookup.q_lookup_comm.0,
+            "mload(Q_LOOKUP_EVAL)".to_string(),
+        )?;
+        key_opening(
+            out,
+            "q_table".to_string(),
+            &lookup.q_table_comm.0,
+            "mload(Q_TABLE_EVAL)".to_string(),
+        )?;
+        key_opening(
+            out,
+            "table tag".to_string(),
+            &lookup.table_tag_comm.0,
+            "mload(TABLE_TAG_EVAL)".to_string(),
+        )?;
+        for (i, comm) in lookup.table_comms.iter().enumerate() {
+            key_opening(out, format!("table {i}"), &comm.0, at("TABLE_EVALS", i))?;
+        }
+        writeln!(
+            out,
+            "                p := addProofOpenings(p, M, M_EVAL, 1)"
+        )?;
+        writeln!(
+            out,
+            "                p := addProofOpenings(p, PHI, PHI_EVAL, 1)"
+        )?;
+    }
+    writeln!(out, "            }}")
+}
+
+fn main_body(out: &mut String, vk: &VerifyingKey) -> std::fmt::Result {
+    let lookup = vk.lookup.is_some();
+    writeln!(out)?;
+    writeln!(
+        out,
+        "            if iszero(eq(proof.length, PROOF_LENGTH)) {{ fail() }}"
+    )?;
+    writeln!(
+        out,
+        "            if iszero(eq(publicInputs.length, NUM_PUBLIC_INPUTS)) {{ fail() }}"
+    )?;
+    writeln!(
+        out,
+        "            calldatacopy(WIRES, proof.offset, PROOF_LENGTH)"
+    )?;
+    writeln!(
+        out,
+        "            calldatacopy(PUBLIC_INPUTS, publicInputs.offset, mul(NUM_PUBLIC_INPUTS, 0x20))"
+    )?;
+    writeln!(
+        out,
+        "            checkScalars(PUBLIC_INPUTS, NUM_PUBLIC_INPUTS)"
+    )?;
+    writeln!(
+        out,
+        "            checkScalars(WIRE_EVALS, div(sub({}, WIRE_EVALS), 0x20))",
+        if lookup { "M" } else { "OPENING" }
+    )?;
+    if lookup {
+        writeln!(
+            out,
+            "            checkScalars(Q_LOOKUP_EVAL, div(sub(OPENING, Q_LOOKUP_EVAL), 0x20))"
+        )?;
+    }
+    writeln!(out)?;
+    writeln!(out, "            // Fiat-Shamir challenges")?;
+    writeln!(out, "            let t := absorbVerifyingKey(TRANSCRIPT)")?;
+    writeln!(
+        out,
+        "            for {{ let i := 0 }} lt(i, NUM_PUBLIC_INPUTS) {{ i := add(i, 1) }} {{"
+    )?;
+    writeln!(
+        out,
+        "                t := absorbScalar(t, \"pi\", 2, mload(add(PUBLIC_INPUTS, mul(i, 0x20))))"
+    )?;
+    writeln!(out, "            }}")?;
+    writeln!(
+        out,
+        "            for {{ let i := 0 }} lt(i, NUM_WIRES) {{ i := add(i, 1) }} {{"
+    )?;
+    writeln!(
+        out,
+        "                t := absorbPoint(t, \"wire\", 4, add(WIRES, mul(i, 0x80)))"
+    )?;
+    writeln!(out, "            }}")?;
+    if lookup {
+        writeln!(out, "            t := absorbPoint(t, \"m\", 1, M)")?;
+    }
+    writeln!(out, "            t := squeeze(t, \"beta\", 4, BETA)")?;
+    writeln!(out, "            t := squeeze(t, \"gamma\", 5, GAMMA)")?;
+    writeln!(out, "            t := squeeze(t, \"eta\", 3, ETA)")?;
+    writeln!(out, "            t := squeeze(t, \"delta\", 5, DELTA)")?;
+    writeln!(out, "            t := absorbPoint(t, \"z\", 1, Z)")?;
+    if lookup {
+        writeln!(out, "            t := absorbPoint(t, \"phi\", 3, PHI)")?;
+    }
+    writeln!(out, "            t := squeeze(t, \"alpha\", 5, ALPHA)")?;
+    writeln!(
+        out,
+        "            for {{ let i := 0 }} lt(i, NUM_CHUNKS) {{ i := add(i, 1) }} {{"
+    )?;
+    writeln!(
+        out,
+        "                t := absorbPoint(t, \"t\", 1, add(T, mul(i, 0x80)))"
+    )?;
+    writeln!(out, "            }}")?;
+    writeln!(out, "            t := squeeze(t, \"zeta\", 4, ZETA)")?;
+    writeln!(
+        out,
+        "            for {{ let e := WIRE_EVALS }} lt(e, {}) {{ e := add(e, 0x20) }} {{",
+        if lookup { "M" } else { "OPENING" }
+    )?;
+    writeln!(
+        out,
+        "                t := absorbScalar(t, \"eval\", 4, mload(e))"
+    )?;
+    writeln!(out, "            }}")?;
+    if lookup {
+        writeln!(
+            out,
+            "            for {{ let e := Q_LOOKUP_EVAL }} lt(e, OPENING) {{ e := add(e, 0x20) }} {{"
+        )?;
+        writeln!(
+            out,
+            "                t := absorbScalar(t, \"eval\", 4, mload(e))"
+        )?;
+        writeln!(out, "            }}")?;
+    }
+    writeln!(out, "            t := squeeze(t, \"v\", 1, V)")?;
+    writeln!(out)?;
+    writeln!(out, "            // The identity must equal t(ζ)·Z_H(ζ)")?;
+    writeln!(out, "            let zetaN := mload(ZETA)")?;
+    writeln!(
+        out,
+        "            for {{ let i := 0 }} lt(i, LOG_N) {{ i := add(i, 1) }} {{ zetaN := fmul(zetaN, zetaN) }}"
+    )?;
+    writeln!(out, "            mstore(ZETA_N, zetaN)")?;
+    writeln!(out, "            mstore(VANISHING, fsub(zetaN, 1))")?;
+    writeln!(out, "            if iszero(mload(VANISHING)) {{ fail() }}")?;
+    writeln!(out, "            mstore(L1, lagrange(1))")?;
+    writeln!(out, "            mstore(PI_EVAL, publicInputEval())")?;
+    writeln!(
+        out,
+        "            if iszero(eq(constraintEval(), fmul(quotientEval(), mload(VANISHING)))) {{ fail() }}"
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "            // Batched openings at ζ and ωζ, folded with powers of v"
+    )?;
+    writeln!(out, "            mstore(POWER, 1)")?;
+    writeln!(out, "            mstore(ACC, 0)")?;
+    writeln!(
+        out,
+        "            checkOpening(zetaOpenings(), mload(ZETA), OPENING)"
+    )?;
+    writeln!(out, "            mstore(POWER, 1)")?;
+    writeln!(out, "            mstore(ACC, 0)")?;
+    writeln!(
+        out,
+        "            let p := addProofOpenings(MSM, WIRES, WIRE_NEXT_EVALS, NUM_WIRES)"
+    )?;
+    writeln!(
+        out,
+        "            p := addProofOpenings(p, Z, Z_NEXT_EVAL, 1)"
+    )?;
+    if lookup {
+        writeln!(
+            out,
+            "            p := addProofOpenings(p, PHI, PHI_NEXT_EVAL, 1)"
+        )?;
+    }
+    writeln!(
+        out,
+        "            checkOpening(p, fmul(mload(ZETA), OMEGA), NEXT_OPENING)"
+    )?;
+    Ok(())
+}
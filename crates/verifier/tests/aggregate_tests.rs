@@ -0,0 +1,152 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, PrimeField};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::custom_gates::Pow5Gate;
+use circuit::lookup::LookupTable;
+use proof::transcript::{Keccak256Transcript, Sha256Transcript};
+use proof::{Proof, ProvingKey, preprocess, prove, prove_with};
+use setup::TrustedSetup;
+use verifier::aggregate::{accumulate, decide};
+use verifier::verify;
+
+/// x⁵ = y with y public and x range checked, over any scalar field.
+fn pow5_circuit<F: PrimeField>(x: u64) -> ConstraintSystem<F> {
+    let mut cs = ConstraintSystem::new();
+    let pow5 = cs.register_gate(Pow5Gate);
+    let range = cs.register_table(LookupTable::range(4));
+    let x_var = cs.alloc(F::from(x));
+    let y = cs.public_input(F::from(x).pow([5]));
+    cs.custom_gate(pow5, vec![F::one()], &[x_var, cs.zero(), y]);
+    cs.lookup(range, &[x_var]);
+    cs
+}
+
+/// Public inputs and proofs of x = 1, 2, …, count.
+fn prove_many<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    pk: &ProvingKey<E>,
+    count: u64,
+) -> Vec<(Vec<E::ScalarField>, Proof<E>)> {
+    (1..=count)
+        .map(|x| {
+            let cs = pow5_circuit(x);
+            (cs.public_inputs(), prove(setup, pk, &cs))
+        })
+        .collect()
+}
+
+fn check_aggregate<E: Pairing>() {
+    let setup = TrustedSetup::<E>::generate(64);
+    let pk = preprocess(&setup, &pow5_circuit(0));
+    let proofs = prove_many(&setup, &pk, 4);
+    let instances: Vec<_> = proofs
+        .iter()
+        .map(|(inputs, proof)| (inputs.as_slice(), proof))
+        .collect();
+
+    let acc = accumulate::<Sha256Transcript<E>>(&setup, &pk.vk, &instances).unwrap();
+    assert!(decide(&setup, &acc));
+    assert!(accumulate::<Keccak256Transcript<E>>(&setup, &pk.vk, &instances).is_none());
+}
+
+#[test]
+fn test_aggregate_decides() {
+    check_aggregate::<Bls12_381>();
+}
+
+#[test]
+fn test_bn254_aggregate_decides() {
+    check_aggregate::<Bn254>();
+}
+
+#[test]
+fn test_corrupting_any_proof_breaks_aggregate() {
+    let setup = TrustedSetup::generate(64);
+    let pk = preprocess(&setup, &pow5_circuit(0));
+    let proofs = prove_many(&setup, &pk, 3);
+    let corruptions: [fn(&mut Proof); 5] = [
+        |proof| proof.wire_evals[0] += Fr::one(),
+        |proof| proof.t_evals[1] += Fr::one(),
+        |proof| proof.z_comm = proof.wire_comms[0].clone(),
+        |proof| proof.opening.0 = proof.next_opening.0,
+        |proof| proof.next_opening.0 = proof.opening.0,
+    ];
+
+    for i in 0..proofs.len() {
+        for corrupt in corruptions {
+            let mut corrupted = proofs.clone();
+            corrupt(&mut corrupted[i].1);
+            assert!(!verify(&setup, &pk.vk, &corrupted[i].0, &corrupted[i].1));
+
+            let instances: Vec<_> = corrupted
+                .iter()
+                .map(|(inputs, proof)| (inputs.as_slice(), proof))
+                .collect();
+            let acc = accumulate::<Sha256Transcript>(&setup, &pk.vk, &instances);
+            assert!(acc.is_none_or(|acc| !decide(&setup, &acc)));
+        }
+
+        let mut wrong_inputs = proofs.clone();
+        wrong_inputs[i].0[0] += Fr::one();
+        let instances: Vec<_> = wrong_inputs
+            .iter()
+            .map(|(inputs, proof)| (inputs.as_slice(), proof))
+            .collect();
+        let acc = accumulate::<Sha256Transcript>(&setup, &pk.vk, &instances);
+        assert!(acc.is_none_or(|acc| !decide(&setup, &acc)));
+    }
+}
+
+#[test]
+fn test_opening_only_corruption_reaches_decide() {
+    let setup = TrustedSetup::generate(64);
+    let pk = preprocess(&setup, &pow5_circuit(0));
+    let mut proofs = prove_many(&setup, &pk, 2);
+    proofs[1].1.opening.0 = proofs[0].1.opening.0;
+    let instances: Vec<_> = proofs
+        .iter()
+        .map(|(inputs, proof)| (inputs.as_slice(), proof))
+        .collect();
+
+    let acc = accumulate::<Sha256Transcript>(&setup, &pk.vk, &instances).unwrap();
+    assert!(!decide(&setup, &acc));
+}
+
+#[test]
+fn test_tampered_accumulator_fails() {
+    let setup = TrustedSetup::generate(64);
+    let pk = preprocess(&setup, &pow5_circuit(0));
+    let proofs = prove_many(&setup, &pk, 2);
+    let instances: Vec<_> = proofs
+        .iter()
+        .map(|(inputs, proof)| (inputs.as_slice(), proof))
+        .collect();
+    let acc = accumulate::<Sha256Transcript>(&setup, &pk.vk, &instances).unwrap();
+
+    let mut swapped = acc.clone();
+    std::mem::swap(&mut swapped.lhs, &mut swapped.rhs);
+    assert!(!decide(&setup, &swapped));
+
+    let other = accumulate::<Sha256Transcript>(&setup, &pk.vk, &instances[..1]).unwrap();
+    let mixed = kzg::Accumulator {
+        lhs: acc.lhs,
+        rhs: other.rhs,
+    };
+    assert!(!decide(&setup, &mixed));
+}
+
+#[test]
+fn test_keccak_proofs_aggregate() {
+    let setup = TrustedSetup::<Bls12_381>::generate(64);
+    let pk = preprocess(&setup, &pow5_circuit(0));
+    let cs = pow5_circuit(3);
+    let proof = prove_with::<Keccak256Transcript>(&setup, &pk, &cs);
+    let inputs = cs.public_inputs();
+
+    let instances = [(inputs.as_slice(), &proof), (inputs.as_slice(), &proof)];
+    let acc = accumulate::<Keccak256Transcript>(&setup, &pk.vk, &instances).unwrap();
+    assert!(decide(&setup, &acc));
+    assert!(accumulate::<Sha256Transcript>(&setup, &pk.vk, &instances).is_none());
+}
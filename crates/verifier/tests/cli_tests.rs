@@ -0,0 +1,203 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../circuit/tests/fixtures");
+
+/// Fresh scratch directory for one test.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("plonk-cli-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the binary with whitespace-separated arguments.
+fn plonk(args: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_plonk"))
+        .args(args.split_whitespace())
+        .output()
+        .unwrap()
+}
+
+fn path(dir: &Path, file: &str) -> String {
+    dir.join(file).to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_prove_and_verify_r1cs_circuit() {
+    let dir = scratch("r1cs");
+    let (srs, vk, proof, public) = (
+        path(&dir, "srs.bin"),
+        path(&dir, "circuit.vk"),
+        path(&dir, "proof.bin"),
+        path(&dir, "public.json"),
+    );
+    let circuit = format!("{FIXTURES}/multiplier.r1cs");
+    let witness = format!("{FIXTURES}/multiplier.wtns");
+
+    assert!(
+        plonk(&format!("setup --degree 32 --out {srs}"))
+            .status
+            .success()
+    );
+    let output = plonk(&format!(
+        "preprocess --srs {srs} --circuit {circuit} --out {vk}"
+    ));
+    assert!(output.status.success(), "{output:?}");
+    let output = plonk(&format!(
+        "prove --srs {srs} --circuit {circuit} --witness {witness} --out {proof} --public {public}"
+    ));
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(fs::read_to_string(&public).unwrap(), r#"["21","3"]"#);
+
+    let verify = |public: &str| {
+        plonk(&format!(
+            "verify --srs {srs} --vk {vk} --proof {proof} --public {public}"
+        ))
+    };
+    let output = verify(&public);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Proof valid: true\n"
+    );
+
+    let tampered = path(&dir, "tampered.json");
+    fs::write(&tampered, "[22, 3]").unwrap();
+    let output = verify(&tampered);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Proof valid: false\n"
+    );
+
+    fs::write(&tampered, "[21]").unwrap();
+    assert_eq!(verify(&tampered).status.code(), Some(1));
+    assert_eq!(verify(&path(&dir, "missing.json")).status.code(), Some(2));
+}
+
+#[test]
+fn test_inspect_text_circuit() {
+    let dir = scratch("inspect");
+    let circuit = path(&dir, "square.circuit");
+    fs::write(
+        &circuit,
+        "variables 3\nrow public : 1\nrow q_m=1 q_o=-1 : 2 2 1\n",
+    )
+    .unwrap();
+    let (good, bad, dot) = (
+        path(&dir, "good.json"),
+        path(&dir, "bad.json"),
+        path(&dir, "circuit.dot"),
+    );
+    fs::write(&good, r#"{"1": 25, "2": "-5"}"#).unwrap();
+    fs::write(&bad, r#"{"1": 25, "2": 6}"#).unwrap();
+
+    let output = plonk(&format!(
+        "inspect --circuit {circuit} --witness {good} --dot {dot}"
+    ));
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("rows           3"), "{stdout}");
+    assert!(stdout.contains("Witness satisfies the circuit"), "{stdout}");
+    assert!(
+        fs::read_to_string(&dot)
+            .unwrap()
+            .starts_with("graph circuit {")
+    );
+
+    let output = plonk(&format!("inspect --circuit {circuit} --witness {bad}"));
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("row 2: gate evaluates to 11"), "{stderr}");
+
+    // Proving refuses the bad witness before any cryptography
+    let srs = path(&dir, "srs.bin");
+    assert!(
+        plonk(&format!("setup --degree 8 --out {srs}"))
+            .status
+            .success()
+    );
+    let proof = path(&dir, "proof.bin");
+    let output = plonk(&format!(
+        "prove --srs {srs} --circuit {circuit} --witness {bad} --out {proof}"
+    ));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!dir.join("proof.bin").exists());
+}
+
+#[test]
+fn test_errors_exit_with_two() {
+    let dir = scratch("errors");
+    let srs = path(&dir, "srs.bin");
+    let circuit = format!("{FIXTURES}/multiplier.r1cs");
+    assert!(
+        plonk(&format!("setup --degree 2 --out {srs}"))
+            .status
+            .success()
+    );
+
+    let output = plonk(&format!(
+        "preprocess --srs {srs} --circuit {circuit} --out x"
+    ));
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Setup supports degree 2"));
+
+    let garbage = path(&dir, "garbage.circuit");
+    fs::write(&garbage, "wires 2").unwrap();
+    let output = plonk(&format!("inspect --circuit {garbage}"));
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 1"));
+
+    assert_eq!(plonk("prove").status.code(), Some(2));
+}
+
+#[test]
+fn test_solidity_and_calldata_export() {
+    let dir = scratch("solidity");
+    let (srs, vk, proof, public) = (
+        path(&dir, "srs.bin"),
+        path(&dir, "circuit.vk"),
+        path(&dir, "proof.bin"),
+        path(&dir, "public.json"),
+    );
+    let (contract, calldata) = (path(&dir, "Verifier.sol"), path(&dir, "calldata.hex"));
+    let circuit = format!("{FIXTURES}/multiplier.r1cs");
+    let witness = format!("{FIXTURES}/multiplier.wtns");
+
+    assert!(
+        plonk(&format!("setup --degree 32 --out {srs}"))
+            .status
+            .success()
+    );
+    assert!(
+        plonk(&format!(
+            "preprocess --srs {srs} --circuit {circuit} --out {vk}"
+        ))
+        .status
+        .success()
+    );
+    assert!(
+        plonk(&format!(
+            "prove --srs {srs} --circuit {circuit} --witness {witness} --out {proof} --public {public}"
+        ))
+        .status
+        .success()
+    );
+
+    let output = plonk(&format!("solidity --srs {srs} --vk {vk} --out {contract}"));
+    assert!(output.status.success(), "{output:?}");
+    let source = fs::read_to_string(&contract).unwrap();
+    assert!(source.contains("contract PlonkVerifier"));
+    assert!(source.contains("uint256 internal constant NUM_PUBLIC_INPUTS = 2;"));
+
+    let output = plonk(&format!(
+        "calldata --proof {proof} --public {public} --out {calldata}"
+    ));
+    assert!(output.status.success(), "{output:?}");
+    // A 4-byte selector followed by 32-byte ABI words
+    let hex = fs::read_to_string(&calldata).unwrap();
+    assert!(hex.starts_with("0x"));
+    assert_eq!(hex.len() % 64, 10);
+}
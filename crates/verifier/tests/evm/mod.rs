@@ -0,0 +1,629 @@
+//! Runs generated verifier contracts in an in-process EVM.
+//!
+//! [`Verifier::from_runtime`] deploys runtime bytecode as solc emits it;
+//! the fixtures under `tests/fixtures/solidity` hold solc's output for one
+//! fixed verifying key. [`Verifier::deploy`] instead compiles any
+//! generated contract with this module's own compiler, which covers the
+//! part of the contract that does the work: its `uint256 internal
+//! constant` declarations and the body of the `assembly` block. The Yul
+//! subset is what the generator emits: `let`, assignment, `if`, `for`,
+//! non-recursive functions with at most one return value and the usual
+//! builtins. Variables live at fixed memory addresses, one per
+//! declaration.
+
+use std::collections::HashMap;
+
+use revm::context::TxEnv;
+use revm::context::result::{ExecutionResult, Output};
+use revm::database::{CacheDB, EmptyDB};
+use revm::primitives::{Address, Bytes, TxKind, keccak256};
+use revm::{Context, ExecuteCommitEvm, MainBuilder, MainContext};
+
+/// Memory address of the first compiled variable, above the contract's
+/// own buffers.
+const VARIABLES: usize = 0x10000;
+
+/// A deployed contract with a `verifyProof(bytes, uint256[])` entry point.
+pub struct Verifier {
+    evm: revm::MainnetEvm<revm::handler::MainnetContext<CacheDB<EmptyDB>>>,
+    address: Address,
+}
+
+impl Verifier {
+    /// Compiles a generated contract with [`compile`] and deploys it.
+    pub fn deploy(source: &str) -> Self {
+        Self::from_runtime(&compile(source))
+    }
+
+    /// Deploys runtime bytecode behind a constructor that returns it.
+    pub fn from_runtime(runtime: &[u8]) -> Self {
+        let mut code = vec![0x61];
+        code.extend((runtime.len() as u16).to_be_bytes());
+        code.extend([0x80, 0x61, 0x00, 0x0b, 0x5f, 0x39, 0x5f, 0xf3]);
+        code.extend(runtime);
+
+        let mut evm = Context::mainnet()
+            .modify_cfg_chained(|cfg| cfg.disable_nonce_check = true)
+            .with_db(CacheDB::<EmptyDB>::default())
+            .build_mainnet();
+        let result = evm
+            .transact_commit(
+                TxEnv::builder()
+                    .kind(TxKind::Create)
+                    .data(code.into())
+                    .gas_limit(16_000_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let ExecutionResult::Success {
+            output: Output::Create(_, Some(address)),
+            ..
+        } = result
+        else {
+            panic!("deployment failed: {result:?}");
+        };
+        Verifier { evm, address }
+    }
+
+    /// Calls the contract and returns its boolean result and the gas used.
+    pub fn call(&mut self, calldata: Vec<u8>) -> (bool, u64) {
+        let result = self
+            .evm
+            .transact_commit(
+                TxEnv::builder()
+                    .kind(TxKind::Call(self.address))
+                    .data(Bytes::from(calldata))
+                    .gas_limit(16_000_000)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let gas = result.tx_gas_used();
+        match result {
+            ExecutionResult::Success {
+                output: Output::Call(output),
+                ..
+            } => {
+                assert_eq!(output.len(), 32);
+                (output[31] == 1, gas)
+            }
+            other => panic!("call failed: {other:?}"),
+        }
+    }
+}
+
+/// Compiles a generated contract to runtime bytecode.
+pub fn compile(source: &str) -> Vec<u8> {
+    let mut compiler = Compiler::new(source);
+    let start = source
+        .find("assembly {")
+        .expect("contract has an assembly block")
+        + 9;
+    let mut tokens = Tokens::new(&source[start..]);
+    let body = tokens.block();
+
+    let signature = source
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("function verifyProof("))
+        .expect("contract has verifyProof");
+    let params: Vec<&str> = signature
+        .split(')')
+        .next()
+        .unwrap()
+        .split(',')
+        .map(|param| param.split_whitespace().last().unwrap())
+        .collect();
+    compiler.prologue(&params);
+    compiler.block(&body);
+    compiler.epilogue();
+    compiler.finish()
+}
+
+#[derive(Debug)]
+enum Expr {
+    Literal([u8; 32]),
+    Ident(String),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug)]
+enum Stmt {
+    Let(String, Option<Expr>),
+    Assign(String, Expr),
+    If(Expr, Vec<Stmt>),
+    For(Vec<Stmt>, Expr, Vec<Stmt>, Vec<Stmt>),
+    Function(Function),
+    Expr(Expr),
+}
+
+#[derive(Debug)]
+struct Function {
+    name: String,
+    params: Vec<String>,
+    ret: Option<String>,
+    body: Vec<Stmt>,
+}
+
+struct Tokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Tokens { rest: text }
+    }
+
+    fn skip_space(&mut self) {
+        loop {
+            self.rest = self.rest.trim_start();
+            match self.rest.strip_prefix("//") {
+                Some(comment) => self.rest = comment.split_once('\n').map_or("", |(_, rest)| rest),
+                None => return,
+            }
+        }
+    }
+
+    fn peek(&mut self) -> &'a str {
+        self.skip_space();
+        let rest = self.rest;
+        for symbol in [":=", "->", "{", "}", "(", ")", ","] {
+            if rest.starts_with(symbol) {
+                return &rest[..symbol.len()];
+            }
+        }
+        if let Some(string) = rest.strip_prefix('"') {
+            return &rest[..string.find('"').unwrap() + 2];
+        }
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len());
+        assert!(end > 0, "unexpected input: {}", &rest[..rest.len().min(40)]);
+        &rest[..end]
+    }
+
+    fn next(&mut self) -> &'a str {
+        let token = self.peek();
+        self.rest = &self.rest[token.len()..];
+        token
+    }
+
+    fn expect(&mut self, expected: &str) {
+        let token = self.next();
+        assert_eq!(token, expected);
+    }
+
+    fn block(&mut self) -> Vec<Stmt> {
+        self.expect("{");
+        let mut statements = Vec::new();
+        while self.peek() != "}" {
+            statements.push(self.statement());
+        }
+        self.next();
+        statements
+    }
+
+    fn statement(&mut self) -> Stmt {
+        match self.peek() {
+            "let" => {
+                self.next();
+                let name = self.next().to_string();
+                let value = (self.peek() == ":=").then(|| {
+                    self.next();
+                    self.expr()
+                });
+                Stmt::Let(name, value)
+            }
+            "if" => {
+                self.next();
+                let condition = self.expr();
+                Stmt::If(condition, self.block())
+            }
+            "for" => {
+                self.next();
+                let init = self.block();
+                let condition = self.expr();
+                let post = self.block();
+                Stmt::For(init, condition, post, self.block())
+            }
+            "function" => {
+                self.next();
+                let name = self.next().to_string();
+                self.expect("(");
+                let mut params = Vec::new();
+                while self.peek() != ")" {
+                    params.push(self.next().to_string());
+                    if self.peek() == "," {
+                        self.next();
+                    }
+                }
+                self.next();
+                let ret = (self.peek() == "->").then(|| {
+                    self.next();
+                    self.next().to_string()
+                });
+                let body = self.block();
+                Stmt::Function(Function {
+                    name,
+                    params,
+                    ret,
+                    body,
+                })
+            }
+            _ => {
+                let expr = self.expr();
+                match expr {
+                    Expr::Ident(name) => {
+                        self.expect(":=");
+                        Stmt::Assign(name, self.expr())
+                    }
+                    expr => Stmt::Expr(expr),
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Expr {
+        let token = self.next();
+        if let Some(string) = token.strip_prefix('"') {
+            let mut word = [0; 32];
+            let bytes = &string.as_bytes()[..string.len() - 1];
+            word[..bytes.len()].copy_from_slice(bytes);
+            return Expr::Literal(word);
+        }
+        if token.starts_with(|c: char| c.is_ascii_digit()) {
+            return Expr::Literal(literal(token));
+        }
+        if self.peek() != "(" {
+            return Expr::Ident(token.to_string());
+        }
+        self.next();
+        let mut args = Vec::new();
+        while self.peek() != ")" {
+            args.push(self.expr());
+            if self.peek() == "," {
+                self.next();
+            }
+        }
+        self.next();
+        Expr::Call(token.to_string(), args)
+    }
+}
+
+fn literal(token: &str) -> [u8; 32] {
+    let mut word = [0; 32];
+    match token.strip_prefix("0x") {
+        Some(hex) => {
+            let hex = format!("{hex:0>64}");
+            for (i, byte) in word.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+            }
+        }
+        None => word[16..].copy_from_slice(&token.parse::<u128>().unwrap().to_be_bytes()),
+    }
+    word
+}
+
+/// Builtins: opcode and whether they return a value.
+fn builtin(name: &str) -> Option<(u8, bool)> {
+    Some(match name {
+        "add" => (0x01, true),
+        "mul" => (0x02, true),
+        "sub" => (0x03, true),
+        "div" => (0x04, true),
+        "mod" => (0x06, true),
+        "addmod" => (0x08, true),
+        "mulmod" => (0x09, true),
+        "lt" => (0x10, true),
+        "gt" => (0x11, true),
+        "eq" => (0x14, true),
+        "iszero" => (0x15, true),
+        "and" => (0x16, true),
+        "or" => (0x17, true),
+        "xor" => (0x18, true),
+        "not" => (0x19, true),
+        "shl" => (0x1b, true),
+        "shr" => (0x1c, true),
+        "keccak256" => (0x20, true),
+        "calldataload" => (0x35, true),
+        "calldatacopy" => (0x37, false),
+        "pop" => (0x50, false),
+        "mload" => (0x51, true),
+        "mstore" => (0x52, false),
+        "gas" => (0x5a, true),
+        "return" => (0xf3, false),
+        "staticcall" => (0xfa, true),
+        "revert" => (0xfd, false),
+        _ => return None,
+    })
+}
+
+const JUMP: u8 = 0x56;
+const JUMPI: u8 = 0x57;
+const JUMPDEST: u8 = 0x5b;
+const PUSH2: u8 = 0x61;
+const MLOAD: u8 = 0x51;
+const MSTORE: u8 = 0x52;
+
+struct Compiler<'a> {
+    constants: HashMap<String, [u8; 32]>,
+    code: Vec<u8>,
+    labels: Vec<Option<usize>>,
+    fixups: Vec<(usize, usize)>,
+    /// Visible variables, innermost last
+    scopes: Vec<Vec<(String, usize)>>,
+    next_variable: usize,
+    functions: HashMap<String, (usize, &'a Function)>,
+    pending: Vec<&'a Function>,
+}
+
+impl<'a> Compiler<'a> {
+    fn new(source: &str) -> Self {
+        let constants = source
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("uint256 internal constant "))
+            .map(|line| {
+                let (name, value) = line.trim_end_matches(';').split_once(" = ").unwrap();
+                (name.to_string(), literal(value))
+            })
+            .collect();
+        Compiler {
+            constants,
+            code: Vec::new(),
+            labels: Vec::new(),
+            fixups: Vec::new(),
+            scopes: vec![Vec::new()],
+            next_variable: VARIABLES,
+            functions: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = Some(self.code.len());
+        self.code.push(JUMPDEST);
+    }
+
+    fn push_label(&mut self, label: usize) {
+        self.code.push(PUSH2);
+        self.fixups.push((self.code.len(), label));
+        self.code.extend([0, 0]);
+    }
+
+    fn push(&mut self, word: [u8; 32]) {
+        let start = word.iter().position(|&byte| byte != 0).unwrap_or(32);
+        self.code.push(0x5f + (32 - start) as u8);
+        self.code.extend(&word[start..]);
+    }
+
+    fn push_usize(&mut self, value: usize) {
+        let mut word = [0; 32];
+        word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+        self.push(word);
+    }
+
+    fn declare(&mut self, name: &str) -> usize {
+        let address = self.next_variable;
+        self.next_variable += 32;
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .push((name.to_string(), address));
+        address
+    }
+
+    fn variable(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(declared, _)| declared == name)
+            .map(|&(_, address)| address)
+    }
+
+    fn store(&mut self, address: usize) {
+        self.push_usize(address);
+        self.code.push(MSTORE);
+    }
+
+    /// Reads the dynamic calldata arguments into `name.offset` and
+    /// `name.length`, and checks the function selector.
+    fn prologue(&mut self, params: &[&str]) {
+        let signature = format!(
+            "verifyProof({})",
+            ["bytes", "uint256[]"][..params.len()].join(",")
+        );
+        let selector = &keccak256(signature.as_bytes())[..4];
+        let mut word = [0; 32];
+        word[28..].copy_from_slice(selector);
+        let ok = self.label();
+        self.push(word);
+        self.push_usize(0);
+        self.code.extend([0x35, 0x60, 0xe0, 0x1c, 0x14]);
+        self.push_label(ok);
+        self.code.push(JUMPI);
+        self.code.extend([0x5f, 0x5f, 0xfd]);
+        self.place(ok);
+
+        for (i, param) in params.iter().enumerate() {
+            // offset = 4 + head, length at offset, data after it
+            let length = self.declare(&format!("{param}.length"));
+            let offset = self.declare(&format!("{param}.offset"));
+            self.push_usize(4 + 32 * i);
+            self.code.push(0x35);
+            self.push_usize(4);
+            self.code.push(0x01);
+            self.code.push(0x80);
+            self.code.push(0x35);
+            self.store(length);
+            self.push_usize(32);
+            self.code.push(0x01);
+            self.store(offset);
+        }
+        let valid = self.declare("valid");
+        self.push_usize(0);
+        self.store(valid);
+    }
+
+    fn epilogue(&mut self) {
+        let valid = self.variable("valid").unwrap();
+        self.push_usize(valid);
+        self.code.push(MLOAD);
+        self.push_usize(0);
+        self.code.push(MSTORE);
+        self.push_usize(32);
+        self.push_usize(0);
+        self.code.push(0xf3);
+
+        while let Some(function) = self.pending.pop() {
+            self.function(function);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        for (position, label) in &self.fixups {
+            let target = self.labels[*label].expect("label placed") as u16;
+            self.code[*position..*position + 2].copy_from_slice(&target.to_be_bytes());
+        }
+        self.code
+    }
+
+    fn block(&mut self, statements: &'a [Stmt]) {
+        for statement in statements {
+            if let Stmt::Function(function) = statement {
+                let label = self.label();
+                self.functions
+                    .insert(function.name.clone(), (label, function));
+                self.pending.push(function);
+            }
+        }
+        self.scopes.push(Vec::new());
+        for statement in statements {
+            self.statement(statement);
+        }
+        self.scopes.pop();
+    }
+
+    fn statement(&mut self, statement: &'a Stmt) {
+        match statement {
+            Stmt::Let(name, value) => {
+                match value {
+                    Some(value) => self.expr(value),
+                    None => self.push_usize(0),
+                }
+                let address = self.declare(name);
+                self.store(address);
+            }
+            Stmt::Assign(name, value) => {
+                self.expr(value);
+                let address = self
+                    .variable(name)
+                    .unwrap_or_else(|| panic!("unknown {name}"));
+                self.store(address);
+            }
+            Stmt::If(condition, body) => {
+                let end = self.label();
+                self.expr(condition);
+                self.code.push(0x15);
+                self.push_label(end);
+                self.code.push(JUMPI);
+                self.block(body);
+                self.place(end);
+            }
+            Stmt::For(init, condition, post, body) => {
+                let (start, end) = (self.label(), self.label());
+                self.scopes.push(Vec::new());
+                for statement in init {
+                    self.statement(statement);
+                }
+                self.place(start);
+                self.expr(condition);
+                self.code.push(0x15);
+                self.push_label(end);
+                self.code.push(JUMPI);
+                self.block(body);
+                self.block(post);
+                self.push_label(start);
+                self.code.push(JUMP);
+                self.place(end);
+                self.scopes.pop();
+            }
+            Stmt::Function(_) => {}
+            Stmt::Expr(expr) => self.expr(expr),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(word) => self.push(*word),
+            Expr::Ident(name) => match self.variable(name) {
+                Some(address) => {
+                    self.push_usize(address);
+                    self.code.push(MLOAD);
+                }
+                None => {
+                    let value = *self
+                        .constants
+                        .get(name)
+                        .unwrap_or_else(|| panic!("unknown identifier {name}"));
+                    self.push(value);
+                }
+            },
+            Expr::Call(name, args) => {
+                if let Some((opcode, _)) = builtin(name) {
+                    for arg in args.iter().rev() {
+                        self.expr(arg);
+                    }
+                    self.code.push(opcode);
+                    return;
+                }
+                let (label, _) = *self
+                    .functions
+                    .get(name)
+                    .unwrap_or_else(|| panic!("unknown function {name}"));
+                let ret = self.label();
+                self.push_label(ret);
+                for arg in args {
+                    self.expr(arg);
+                }
+                self.push_label(label);
+                self.code.push(JUMP);
+                self.place(ret);
+            }
+        }
+    }
+
+    /// Emits a function: parameters are stored from the stack, the return
+    /// variable is zeroed, and the epilogue jumps back to the caller's label.
+    fn function(&mut self, function: &'a Function) {
+        let (label, _) = self.functions[&function.name];
+        self.place(label);
+        let outer = std::mem::replace(&mut self.scopes, vec![Vec::new()]);
+        let params: Vec<usize> = function
+            .params
+            .iter()
+            .map(|param| self.declare(param))
+            .collect();
+        for &address in params.iter().rev() {
+            self.store(address);
+        }
+        let ret = function.ret.as_ref().map(|ret| self.declare(ret));
+        if let Some(address) = ret {
+            self.push_usize(0);
+            self.store(address);
+        }
+        self.block(&function.body);
+        if let Some(address) = ret {
+            self.push_usize(address);
+            self.code.push(MLOAD);
+            self.code.push(0x90);
+        }
+        self.code.push(JUMP);
+        self.scopes = outer;
+    }
+}
@@ -0,0 +1,30 @@
+#!/bin/sh
+# Regenerates the Solidity verifier fixtures for the multiplier circuit in
+# crates/circuit/tests/fixtures, then compiles the contracts with solc.
+# With --sources-only, stops before solc and leaves the bytecode alone.
+set -eu
+cd "$(dirname "$0")"
+
+if [ "${1:-}" != "--sources-only" ] && ! command -v solc >/dev/null; then
+    echo "solc not found; install solc 0.8.20 or later" >&2
+    exit 1
+fi
+
+circuit=../../../../circuit/tests/fixtures/multiplier
+plonk() { cargo run --quiet --bin plonk -- "$@"; }
+
+plonk setup --degree 32 --out srs.bin
+plonk preprocess --srs srs.bin --circuit "$circuit.r1cs" --out multiplier.vk
+for transcript in sha256 keccak256; do
+    plonk prove --srs srs.bin --circuit "$circuit.r1cs" --witness "$circuit.wtns" \
+        --transcript "$transcript" --out "$transcript.proof" --public public.json
+    plonk solidity --srs srs.bin --vk multiplier.vk --transcript "$transcript" \
+        --out "$transcript.sol"
+done
+
+if [ "${1:-}" = "--sources-only" ]; then
+    exit 0
+fi
+for transcript in sha256 keccak256; do
+    solc --optimize --bin-runtime "$transcript.sol" | tail -n 1 >"$transcript.bin-runtime"
+done
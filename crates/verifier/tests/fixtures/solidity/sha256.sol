@@ -0,0 +1,368 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/// @title PLONK verifier
+/// @notice Generated from the verifying key of a circuit with 16 rows.
+/// @dev Needs the EIP-2537 BLS12-381 precompiles. Proofs are G1 points in
+/// the 128-byte EIP-2537 encoding and 32-byte big-endian scalars, in the
+/// field order of the prover's proof structure.
+contract PlonkVerifier {
+    // Scalar field modulus
+    uint256 internal constant R = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001;
+    // (p - 1) / 2 for the base field, as its top 16 and low 32 bytes
+    uint256 internal constant HALF_P_HI = 0x000000000000000000000000000000000d0088f51cbff34d258dd3db21a5d66b;
+    uint256 internal constant HALF_P_LO = 0xb23ba5c279c2895fb39869507b587b120f55ffff58a9ffffdcff7fffffffd555;
+
+    uint256 internal constant SHA256_PRECOMPILE = 0x02;
+    uint256 internal constant MODEXP_PRECOMPILE = 0x05;
+    uint256 internal constant G1_MSM_PRECOMPILE = 0x0c;
+    uint256 internal constant PAIRING_PRECOMPILE = 0x0f;
+
+    uint256 internal constant N = 16;
+    uint256 internal constant LOG_N = 4;
+    uint256 internal constant OMEGA = 0x20b1ce9140267af9dd1c0af834cec32c17beb312f20b6f7653ea61d87742bcce;
+    uint256 internal constant NUM_WIRES = 3;
+    uint256 internal constant NUM_CHUNKS = 3;
+    uint256 internal constant NUM_PUBLIC_INPUTS = 2;
+    uint256 internal constant PROOF_LENGTH = 1760;
+
+    // Memory: challenges and running values, then the proof, the public
+    // inputs and buffers for the transcript and precompile calls
+    uint256 internal constant BETA = 0x80;
+    uint256 internal constant GAMMA = 0xa0;
+    uint256 internal constant ETA = 0xc0;
+    uint256 internal constant DELTA = 0xe0;
+    uint256 internal constant ALPHA = 0x100;
+    uint256 internal constant ZETA = 0x120;
+    uint256 internal constant V = 0x140;
+    uint256 internal constant ZETA_N = 0x160;
+    uint256 internal constant VANISHING = 0x180;
+    uint256 internal constant L1 = 0x1a0;
+    uint256 internal constant PI_EVAL = 0x1c0;
+    uint256 internal constant POWER = 0x1e0;
+    uint256 internal constant ACC = 0x200;
+    uint256 internal constant SCRATCH = 0x220;
+    uint256 internal constant WIRES = 0x2e0;
+    uint256 internal constant Z = 0x460;
+    uint256 internal constant T = 0x4e0;
+    uint256 internal constant WIRE_EVALS = 0x660;
+    uint256 internal constant WIRE_NEXT_EVALS = 0x6c0;
+    uint256 internal constant SELECTOR_EVALS = 0x720;
+    uint256 internal constant CUSTOM_SELECTOR_EVALS = 0x7c0;
+    uint256 internal constant SIGMA_EVALS = 0x7c0;
+    uint256 internal constant Z_EVAL = 0x820;
+    uint256 internal constant Z_NEXT_EVAL = 0x840;
+    uint256 internal constant T_EVALS = 0x860;
+    uint256 internal constant OPENING = 0x8c0;
+    uint256 internal constant NEXT_OPENING = 0x940;
+    uint256 internal constant PUBLIC_INPUTS = 0x9c0;
+    uint256 internal constant TRANSCRIPT = 0xa00;
+    uint256 internal constant MSM = 0x19fa;
+    uint256 internal constant PAIRING = 0x249a;
+
+    function verifyProof(bytes calldata proof, uint256[] calldata publicInputs)
+        external
+        view
+        returns (bool valid)
+    {
+        assembly {
+            function fail() {
+                mstore(0x00, 0)
+                return(0x00, 0x20)
+            }
+
+            function fadd(a, b) -> c {
+                c := addmod(a, b, R)
+            }
+
+            function fsub(a, b) -> c {
+                c := addmod(a, sub(R, b), R)
+            }
+
+            function fmul(a, b) -> c {
+                c := mulmod(a, b, R)
+            }
+
+            // x^(R - 2) with the modexp precompile
+            function inverse(x) -> y {
+                mstore(SCRATCH, 0x20)
+                mstore(add(SCRATCH, 0x20), 0x20)
+                mstore(add(SCRATCH, 0x40), 0x20)
+                mstore(add(SCRATCH, 0x60), x)
+                mstore(add(SCRATCH, 0x80), sub(R, 2))
+                mstore(add(SCRATCH, 0xa0), R)
+                if iszero(staticcall(gas(), MODEXP_PRECOMPILE, SCRATCH, 0xc0, SCRATCH, 0x20)) { fail() }
+                y := mload(SCRATCH)
+            }
+
+            function wire(j) -> w {
+                w := mload(add(WIRE_EVALS, mul(j, 0x20)))
+            }
+
+            function nextWire(j) -> w {
+                w := mload(add(WIRE_NEXT_EVALS, mul(j, 0x20)))
+            }
+
+            function checkScalars(p, count) {
+                for { let i := 0 } lt(i, count) { i := add(i, 1) } {
+                    if iszero(lt(mload(add(p, mul(i, 0x20))), R)) { fail() }
+                }
+            }
+
+            // Transcript: labels and values are appended at t, squeezing hashes
+            // everything since TRANSCRIPT and restarts from the digest
+            function absorbLabel(t, label, len) -> next {
+                mstore(t, label)
+                next := add(t, len)
+            }
+
+            // ωⁱ·(ζⁿ - 1) / (n·(ζ - ωⁱ))
+            function lagrange(omegaI) -> l {
+                let denominator := fmul(N, fsub(mload(ZETA), omegaI))
+                l := fmul(fmul(omegaI, mload(VANISHING)), inverse(denominator))
+            }
+
+            // t(ζ) = Σ ζⁱⁿ·tᵢ(ζ)
+            function quotientEval() -> t {
+                let power := 1
+                for { let i := 0 } lt(i, NUM_CHUNKS) { i := add(i, 1) } {
+                    t := fadd(t, fmul(power, mload(add(T_EVALS, mul(i, 0x20)))))
+                    power := fmul(power, mload(ZETA_N))
+                }
+            }
+
+            function copyPoint(dst, src) {
+                mstore(dst, mload(src))
+                mstore(add(dst, 0x20), mload(add(src, 0x20)))
+                mstore(add(dst, 0x40), mload(add(src, 0x40)))
+                mstore(add(dst, 0x60), mload(add(src, 0x60)))
+            }
+
+            // Appends POWER as the scalar of the point at p, folds eval into ACC
+            function addOpening(p, eval) -> next {
+                let power := mload(POWER)
+                mstore(add(p, 0x80), power)
+                mstore(ACC, fadd(mload(ACC), fmul(power, eval)))
+                mstore(POWER, fmul(power, mload(V)))
+                next := add(p, 0xa0)
+            }
+
+            function addProofOpenings(p, points, evals, count) -> next {
+                next := p
+                for { let i := 0 } lt(i, count) { i := add(i, 1) } {
+                    copyPoint(next, add(points, mul(i, 0x80)))
+                    next := addOpening(next, mload(add(evals, mul(i, 0x20))))
+                }
+            }
+
+            function addKeyOpening(p, x0, x1, y0, y1, eval) -> next {
+                mstore(p, x0)
+                mstore(add(p, 0x20), x1)
+                mstore(add(p, 0x40), y0)
+                mstore(add(p, 0x60), y1)
+                next := addOpening(p, eval)
+            }
+
+            // Scalars are absorbed little-endian
+            function absorbScalar(t, label, len, s) -> next {
+                let start := absorbLabel(t, label, len)
+                s := or(shr(8, and(s, 0xff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00)), shl(8, and(s, 0x00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff)))
+                s := or(shr(16, and(s, 0xffff0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff0000)), shl(16, and(s, 0x0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff0000ffff)))
+                s := or(shr(32, and(s, 0xffffffff00000000ffffffff00000000ffffffff00000000ffffffff00000000)), shl(32, and(s, 0x00000000ffffffff00000000ffffffff00000000ffffffff00000000ffffffff)))
+                s := or(shr(64, and(s, 0xffffffffffffffff0000000000000000ffffffffffffffff0000000000000000)), shl(64, and(s, 0x0000000000000000ffffffffffffffff0000000000000000ffffffffffffffff)))
+                mstore(start, or(shr(128, s), shl(128, s)))
+                next := add(start, 0x20)
+            }
+
+            // Points are absorbed compressed: x big-endian with the compression,
+            // infinity and sign flags in its top bits
+            function absorbPoint(t, label, len, p) -> next {
+                let xHi := mload(p)
+                let xLo := mload(add(p, 0x20))
+                let yHi := mload(add(p, 0x40))
+                let yLo := mload(add(p, 0x60))
+                let flags := 0xc0
+                if or(or(xHi, xLo), or(yHi, yLo)) {
+                    flags := 0x80
+                    if or(gt(yHi, HALF_P_HI), and(eq(yHi, HALF_P_HI), gt(yLo, HALF_P_LO))) { flags := 0xa0 }
+                }
+                let start := absorbLabel(t, label, len)
+                mstore(start, shl(128, or(xHi, shl(120, flags))))
+                mstore(add(start, 0x10), xLo)
+                next := add(start, 0x30)
+            }
+
+            function squeeze(t, label, len, slot) -> next {
+                let end := absorbLabel(t, label, len)
+                if iszero(staticcall(gas(), SHA256_PRECOMPILE, TRANSCRIPT, sub(end, TRANSCRIPT), TRANSCRIPT, 0x20)) { fail() }
+                mstore(slot, mod(mload(TRANSCRIPT), R))
+                next := add(TRANSCRIPT, 0x20)
+            }
+
+            // The circuit's part of the transcript
+            function absorbVerifyingKey(t) -> next {
+                mstore(add(t, 0x0), 0x706c6f6e6b6e1000000000000000000000000000000000000000000000000000)
+                mstore(add(t, 0x20), 0x000000000000766ba8e4a948c3ccf4816776948f583e21bbb324051f9bd3f5a7)
+                mstore(add(t, 0x40), 0x236e41b001e94495bfac9242eb0d5d3575118554024198ed766baf16efc789ea)
+                mstore(add(t, 0x60), 0x48a211ba36e6254cce49229bf8c154fe37187b433695140ed4ea14b214d278fd)
+                mstore(add(t, 0x80), 0x1074949303714153c44c766bb5c1fe79d54251db129a17bc7dc86459bf229458)
+                mstore(add(t, 0xa0), 0xe238f317eef0d0e335e66dbcdaf25729cbccbc32db99b8647ff13462766b95ef)
+                mstore(add(t, 0xc0), 0x8777f696e1deb0d1ab1f3ff48245d9836f033d3982649df180195ba021fade91)
+                mstore(add(t, 0xe0), 0x548446192b4ea2b71579405988fd766ba7f85fab17dd538f91a2df3a8f88d70a)
+                mstore(add(t, 0x100), 0x7f02309fed52cebd44d58c8a4d0a0bf42ad6e07e2b4818e3f627bacd8876511a)
+                mstore(add(t, 0x120), 0x766bb8600ceee20bb41997c3fc30389bfed84e7b879ed551bec9e5d37435d0c8)
+                mstore(add(t, 0x140), 0x97ae1bb4d0ba9d77aa41098bc99571c2426b766b84b7a7bd6a529f5529ed817a)
+                mstore(add(t, 0x160), 0x4eaffc017ce8b4352e2f6e027d02701d0bdc08101d98dc7c49f66968cc8fa503)
+                mstore(add(t, 0x180), 0x5714d46f766ba3ea4ba8abdccd09b561b3d441862ca6d188d81d4c0d5ff39893)
+                mstore(add(t, 0x1a0), 0xb3196cc04650c69f5032ef021fa4c85a458f4205c4d900000000000000000000)
+                next := add(t, 0x1b6)
+            }
+
+            // PI(ζ) = Σ -xₖ·L_rowₖ(ζ)
+            function publicInputEval() -> pi {
+                pi := fsub(pi, fmul(mload(add(PUBLIC_INPUTS, 0x0)), lagrange(0x345766f603fa66e78c0625cd70d77ce2b38b21c28713b7007228fd3397743f7a)))
+                pi := fsub(pi, fmul(mload(add(PUBLIC_INPUTS, 0x20)), lagrange(0x1edc919ec91f38ac5ccd4631f16edba4967a6b6cfb0faca4807b811a823f728d)))
+            }
+
+            // The combined identity at ζ, as in the prover's quotient
+            function constraintEval() -> result {
+                // qL·a + qR·b + qO·c + qM·a·b + qC + Σ qⱼ·wⱼ + PI
+                result := fmul(mload(add(SELECTOR_EVALS, 0x0)), wire(0))
+                result := fadd(result, fmul(mload(add(SELECTOR_EVALS, 0x20)), wire(1)))
+                result := fadd(result, fmul(mload(add(SELECTOR_EVALS, 0x40)), wire(2)))
+                result := fadd(result, fmul(mload(add(SELECTOR_EVALS, 0x60)), fmul(wire(0), wire(1))))
+                result := fadd(result, mload(add(SELECTOR_EVALS, 0x80)))
+                result := fadd(result, mload(PI_EVAL))
+
+                // α·(z·Π(wⱼ + β·kⱼ·ζ + γ) - z(ωζ)·Π(wⱼ + β·σⱼ + γ))
+                let alpha := mload(ALPHA)
+                let beta := mload(BETA)
+                let gamma := mload(GAMMA)
+                let identity := mload(Z_EVAL)
+                let sigma := mload(Z_NEXT_EVAL)
+                identity := fmul(identity, fadd(fadd(wire(0), fmul(beta, fmul(0x0000000000000000000000000000000000000000000000000000000000000001, mload(ZETA)))), gamma))
+                sigma := fmul(sigma, fadd(fadd(wire(0), fmul(beta, mload(add(SIGMA_EVALS, 0x0)))), gamma))
+                identity := fmul(identity, fadd(fadd(wire(1), fmul(beta, fmul(0x0000000000000000000000000000000000000000000000000000000000000007, mload(ZETA)))), gamma))
+                sigma := fmul(sigma, fadd(fadd(wire(1), fmul(beta, mload(add(SIGMA_EVALS, 0x20)))), gamma))
+                identity := fmul(identity, fadd(fadd(wire(2), fmul(beta, fmul(0x0000000000000000000000000000000000000000000000000000000000000031, mload(ZETA)))), gamma))
+                sigma := fmul(sigma, fadd(fadd(wire(2), fmul(beta, mload(add(SIGMA_EVALS, 0x40)))), gamma))
+                result := fadd(result, fmul(alpha, fsub(identity, sigma)))
+
+                // α²·L1·(z - 1)
+                let alphaPower := fmul(alpha, alpha)
+                result := fadd(result, fmul(alphaPower, fmul(mload(L1), fsub(mload(Z_EVAL), 1))))
+                alphaPower := fmul(alphaPower, alpha)
+            }
+
+            // Checks the batched opening whose terms run from MSM to p:
+            // e(π, τ·G2) = e(C - y·G1 + z·π, G2) for C and y folded in ACC
+            function checkOpening(p, z, opening) {
+                // -y·G1
+                mstore(add(p, 0x0), 0x0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0f)
+                mstore(add(p, 0x20), 0xc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb)
+                mstore(add(p, 0x40), 0x0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4)
+                mstore(add(p, 0x60), 0xfcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1)
+                mstore(add(p, 0x80), sub(R, mload(ACC)))
+                // z·π
+                copyPoint(add(p, 0xa0), opening)
+                mstore(add(p, 0x120), z)
+                if iszero(staticcall(gas(), G1_MSM_PRECOMPILE, MSM, sub(add(p, 0x140), MSM), add(PAIRING, 0x180), 0x80)) { fail() }
+                copyPoint(PAIRING, opening)
+                // τ·G2
+                mstore(add(PAIRING, 0x80), 0x00000000000000000000000000000000166209f14ada9e3506de82a58fbc3209)
+                mstore(add(PAIRING, 0xa0), 0x51f7204fae3223bc6d905ce1634db829365e630f4838ab588ca9876ad685218c)
+                mstore(add(PAIRING, 0xc0), 0x00000000000000000000000000000000007758d248930574661bd111e8cf88d7)
+                mstore(add(PAIRING, 0xe0), 0x11509a16cb17bf7d75d52899d03989829995474bc6bae73abe38ffc23d726f63)
+                mstore(add(PAIRING, 0x100), 0x0000000000000000000000000000000001f603a39f1332d42e043787c902a049)
+                mstore(add(PAIRING, 0x120), 0x4756a6d6dca5fb54225b816b14fdce195c6c8fb0df74f0e158878dcb101c6acb)
+                mstore(add(PAIRING, 0x140), 0x0000000000000000000000000000000019b338bfe62b8b6118d8ae4aa9365241)
+                mstore(add(PAIRING, 0x160), 0x4fad89d2a4efe11368e209b655ffb48b7da1614e8d86ed82e43654d533ae84d4)
+                // -G2
+                mstore(add(PAIRING, 0x200), 0x00000000000000000000000000000000024aa2b2f08f0a91260805272dc51051)
+                mstore(add(PAIRING, 0x220), 0xc6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8)
+                mstore(add(PAIRING, 0x240), 0x0000000000000000000000000000000013e02b6052719f607dacd3a088274f65)
+                mstore(add(PAIRING, 0x260), 0x596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e)
+                mstore(add(PAIRING, 0x280), 0x000000000000000000000000000000000d1b3cc2c7027888be51d9ef691d77bc)
+                mstore(add(PAIRING, 0x2a0), 0xb679afda66c73f17f9ee3837a55024f78c71363275a75d75d86bab79f74782aa)
+                mstore(add(PAIRING, 0x2c0), 0x0000000000000000000000000000000013fa4d4a0ad8b1ce186ed5061789213d)
+                mstore(add(PAIRING, 0x2e0), 0x993923066dddaf1040bc3ff59f825c78df74f2d75467e25e0f55f8a00fa030ed)
+                if iszero(staticcall(gas(), PAIRING_PRECOMPILE, PAIRING, 0x300, 0x00, 0x20)) { fail() }
+                if iszero(mload(0x00)) { fail() }
+            }
+
+            // The 15 polynomials opened at ζ, in the prover's order
+            function zetaOpenings() -> p {
+                p := addProofOpenings(MSM, WIRES, WIRE_EVALS, NUM_WIRES)
+                // selector 0
+                p := addKeyOpening(p, 0x0000000000000000000000000000000008e4a948c3ccf4816776948f583e21bb, 0xb324051f9bd3f5a7236e41b001e94495bfac9242eb0d5d3575118554024198ed, 0x000000000000000000000000000000000d4c788e750f1cc52ca33e885d0c2618, 0xdcd63bed682701b1e7e46bc36728196357f0808a65f4329e6ae5bb27e0a04615, mload(add(SELECTOR_EVALS, 0x0)))
+                // selector 1
+                p := addKeyOpening(p, 0x000000000000000000000000000000000f16efc789ea48a211ba36e6254cce49, 0x229bf8c154fe37187b433695140ed4ea14b214d278fd1074949303714153c44c, 0x0000000000000000000000000000000013f8dfac99697a9e1a5a2d5b0381d444, 0x65a7712eb1eb01eb3a599a0a18c2fbf3122273e750b511cf1310b66392179445, mload(add(SELECTOR_EVALS, 0x20)))
+                // selector 2
+                p := addKeyOpening(p, 0x0000000000000000000000000000000015c1fe79d54251db129a17bc7dc86459, 0xbf229458e238f317eef0d0e335e66dbcdaf25729cbccbc32db99b8647ff13462, 0x000000000000000000000000000000000f7f044eb1f3d2d59278ef05b5cdab39, 0xe8945a4d2708e9a7a873ed8cf0186911552e5dd5a118679b5ffcc6641de92459, mload(add(SELECTOR_EVALS, 0x40)))
+                // selector 3
+                p := addKeyOpening(p, 0x0000000000000000000000000000000015ef8777f696e1deb0d1ab1f3ff48245, 0xd9836f033d3982649df180195ba021fade91548446192b4ea2b71579405988fd, 0x00000000000000000000000000000000017f8762dc6804b4000f3be4c6fdb56a, 0x280e292c74f02669f08d673c0f0deec9af5ea1a0ee3d76418b99f768a0d0ed19, mload(add(SELECTOR_EVALS, 0x60)))
+                // selector 4
+                p := addKeyOpening(p, 0x0000000000000000000000000000000007f85fab17dd538f91a2df3a8f88d70a, 0x7f02309fed52cebd44d58c8a4d0a0bf42ad6e07e2b4818e3f627bacd8876511a, 0x0000000000000000000000000000000015a3d86a3747a54aeef292462a751b9a, 0xf77c9fb97a900af6fed9b5023f2630e9100b8e9c7aba38f56768e6bb8db200b1, mload(add(SELECTOR_EVALS, 0x80)))
+                // sigma 0
+                p := addKeyOpening(p, 0x0000000000000000000000000000000018600ceee20bb41997c3fc30389bfed8, 0x4e7b879ed551bec9e5d37435d0c897ae1bb4d0ba9d77aa41098bc99571c2426b, 0x00000000000000000000000000000000198a2b0a40bb6746bef9a3c6f2773412, 0x16357726b0ac13a70ed14653424fd55c471d06ea531b5e92752512e890ac9cd5, mload(add(SIGMA_EVALS, 0x0)))
+                // sigma 1
+                p := addKeyOpening(p, 0x0000000000000000000000000000000004b7a7bd6a529f5529ed817a4eaffc01, 0x7ce8b4352e2f6e027d02701d0bdc08101d98dc7c49f66968cc8fa5035714d46f, 0x0000000000000000000000000000000000b3f7a6f0727a6498fad74e13a195f1, 0x7ca40653d485f2cd5e41fe6e909ee08ec6a21bb887a81f09a51685996b18f7c2, mload(add(SIGMA_EVALS, 0x20)))
+                // sigma 2
+                p := addKeyOpening(p, 0x0000000000000000000000000000000003ea4ba8abdccd09b561b3d441862ca6, 0xd188d81d4c0d5ff39893b3196cc04650c69f5032ef021fa4c85a458f4205c4d9, 0x0000000000000000000000000000000011f4aa1f8a42e12df478dfb529dd1c7d, 0xab8083f0a8b316b39e1c7febc8ffce806d646b3e52725188965a8457ba7b6894, mload(add(SIGMA_EVALS, 0x40)))
+                p := addProofOpenings(p, Z, Z_EVAL, 1)
+                p := addProofOpenings(p, T, T_EVALS, NUM_CHUNKS)
+            }
+
+            if iszero(eq(proof.length, PROOF_LENGTH)) { fail() }
+            if iszero(eq(publicInputs.length, NUM_PUBLIC_INPUTS)) { fail() }
+            calldatacopy(WIRES, proof.offset, PROOF_LENGTH)
+            calldatacopy(PUBLIC_INPUTS, publicInputs.offset, mul(NUM_PUBLIC_INPUTS, 0x20))
+            checkScalars(PUBLIC_INPUTS, NUM_PUBLIC_INPUTS)
+            checkScalars(WIRE_EVALS, div(sub(OPENING, WIRE_EVALS), 0x20))
+
+            // Fiat-Shamir challenges
+            let t := absorbVerifyingKey(TRANSCRIPT)
+            for { let i := 0 } lt(i, NUM_PUBLIC_INPUTS) { i := add(i, 1) } {
+                t := absorbScalar(t, "pi", 2, mload(add(PUBLIC_INPUTS, mul(i, 0x20))))
+            }
+            for { let i := 0 } lt(i, NUM_WIRES) { i := add(i, 1) } {
+                t := absorbPoint(t, "wire", 4, add(WIRES, mul(i, 0x80)))
+            }
+            t := squeeze(t, "beta", 4, BETA)
+            t := squeeze(t, "gamma", 5, GAMMA)
+            t := squeeze(t, "eta", 3, ETA)
+            t := squeeze(t, "delta", 5, DELTA)
+            t := absorbPoint(t, "z", 1, Z)
+            t := squeeze(t, "alpha", 5, ALPHA)
+            for { let i := 0 } lt(i, NUM_CHUNKS) { i := add(i, 1) } {
+                t := absorbPoint(t, "t", 1, add(T, mul(i, 0x80)))
+            }
+            t := squeeze(t, "zeta", 4, ZETA)
+            for { let e := WIRE_EVALS } lt(e, OPENING) { e := add(e, 0x20) } {
+                t := absorbScalar(t, "eval", 4, mload(e))
+            }
+            t := squeeze(t, "v", 1, V)
+
+            // The identity must equal t(ζ)·Z_H(ζ)
+            let zetaN := mload(ZETA)
+            for { let i := 0 } lt(i, LOG_N) { i := add(i, 1) } { zetaN := fmul(zetaN, zetaN) }
+            mstore(ZETA_N, zetaN)
+            mstore(VANISHING, fsub(zetaN, 1))
+            if iszero(mload(VANISHING)) { fail() }
+            mstore(L1, lagrange(1))
+            mstore(PI_EVAL, publicInputEval())
+            if iszero(eq(constraintEval(), fmul(quotientEval(), mload(VANISHING)))) { fail() }
+
+            // Batched openings at ζ and ωζ, folded with powers of v
+            mstore(POWER, 1)
+            mstore(ACC, 0)
+            checkOpening(zetaOpenings(), mload(ZETA), OPENING)
+            mstore(POWER, 1)
+            mstore(ACC, 0)
+            let p := addProofOpenings(MSM, WIRES, WIRE_NEXT_EVALS, NUM_WIRES)
+            p := addProofOpenings(p, Z, Z_NEXT_EVAL, 1)
+            checkOpening(p, fmul(mload(ZETA), OMEGA), NEXT_OPENING)
+            valid := 1
+        }
+    }
+}
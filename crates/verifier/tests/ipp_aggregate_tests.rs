@@ -0,0 +1,182 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_bn254::Bn254;
+use ark_ec::AffineRepr;
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, PrimeField};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::custom_gates::Pow5Gate;
+use circuit::lookup::LookupTable;
+use proof::transcript::{Keccak256Transcript, Sha256Transcript};
+use proof::{Proof, ProvingKey, preprocess, prove};
+use setup::{IppSetup, TrustedSetup};
+use verifier::ipp_aggregate::{aggregate, verify_aggregate};
+
+/// x⁵ = y with y public and x range checked, over any scalar field.
+fn pow5_circuit<F: PrimeField>(x: u64) -> ConstraintSystem<F> {
+    let mut cs = ConstraintSystem::new();
+    let pow5 = cs.register_gate(Pow5Gate);
+    let range = cs.register_table(LookupTable::range(4));
+    let x_var = cs.alloc(F::from(x));
+    let y = cs.public_input(F::from(x).pow([5]));
+    cs.custom_gate(pow5, vec![F::one()], &[x_var, cs.zero(), y]);
+    cs.lookup(range, &[x_var]);
+    cs
+}
+
+/// Public inputs and proofs of x = 1, 2, …, count.
+fn prove_many<E: Pairing>(
+    setup: &TrustedSetup<E>,
+    pk: &ProvingKey<E>,
+    count: u64,
+) -> Vec<(Vec<E::ScalarField>, Proof<E>)> {
+    (1..=count)
+        .map(|x| {
+            let cs = pow5_circuit(x);
+            (cs.public_inputs(), prove(setup, pk, &cs))
+        })
+        .collect()
+}
+
+fn check_ipp_aggregate<E: Pairing>(count: u64) {
+    let setup = TrustedSetup::<E>::generate(64);
+    let ipp = IppSetup::<E>::generate(16);
+    let pk = preprocess(&setup, &pow5_circuit(0));
+    let proofs = prove_many(&setup, &pk, count);
+    let instances: Vec<_> = proofs
+        .iter()
+        .map(|(inputs, proof)| (inputs.as_slice(), proof))
+        .collect();
+
+    let agg = aggregate::<Sha256Transcript<E>>(&ipp, &pk.vk, &instances).unwrap();
+    assert_eq!(
+        agg.mipp.rounds.len(),
+        (2 * count as usize).next_power_of_two().trailing_zeros() as usize
+    );
+    assert!(verify_aggregate::<Sha256Transcript<E>>(
+        &setup, &ipp, &pk.vk, &instances, &agg
+    ));
+    assert!(!verify_aggregate::<Keccak256Transcript<E>>(
+        &setup, &ipp, &pk.vk, &instances, &agg
+    ));
+
+    // The verifier has no use for the opening proofs themselves
+    let stripped: Vec<_> = proofs
+        .iter()
+        .map(|(inputs, proof)| {
+            let mut proof = proof.clone();
+            proof.opening.0 = E::G1Affine::zero();
+            proof.next_opening.0 = E::G1Affine::zero();
+            (inputs.clone(), proof)
+        })
+        .collect();
+    let instances: Vec<_> = stripped
+        .iter()
+        .map(|(inputs, proof)| (inputs.as_slice(), proof))
+        .collect();
+    assert!(verify_aggregate::<Sha256Transcript<E>>(
+        &setup, &ipp, &pk.vk, &instances, &agg
+    ));
+}
+
+#[test]
+fn test_ipp_aggregate_verifies() {
+    for count in [1, 3, 4] {
+        check_ipp_aggregate::<Bls12_381>(count);
+    }
+}
+
+#[test]
+fn test_bn254_ipp_aggregate_verifies() {
+    check_ipp_aggregate::<Bn254>(3);
+}
+
+#[test]
+fn test_corrupted_proofs_break_ipp_aggregate() {
+    let setup = TrustedSetup::generate(64);
+    let ipp = IppSetup::generate(8);
+    let pk = preprocess(&setup, &pow5_circuit(0));
+    let proofs = prove_many(&setup, &pk, 3);
+    let corruptions: [fn(&mut Proof); 4] = [
+        |proof| proof.wire_evals[0] += Fr::one(),
+        |proof| proof.z_comm = proof.wire_comms[0].clone(),
+        |proof| proof.opening.0 = proof.next_opening.0,
+        |proof| proof.next_opening.0 = proof.opening.0,
+    ];
+
+    for i in 0..proofs.len() {
+        for corrupt in corruptions {
+            let mut corrupted = proofs.clone();
+            corrupt(&mut corrupted[i].1);
+            let instances: Vec<_> = corrupted
+                .iter()
+                .map(|(inputs, proof)| (inputs.as_slice(), proof))
+                .collect();
+            let agg = aggregate::<Sha256Transcript>(&ipp, &pk.vk, &instances);
+            assert!(agg.is_none_or(|agg| !verify_aggregate::<Sha256Transcript>(
+                &setup, &ipp, &pk.vk, &instances, &agg
+            )));
+        }
+
+        // An honest aggregate does not carry over to other public inputs
+        let instances: Vec<_> = proofs
+            .iter()
+            .map(|(inputs, proof)| (inputs.as_slice(), proof))
+            .collect();
+        let agg = aggregate::<Sha256Transcript>(&ipp, &pk.vk, &instances).unwrap();
+        let mut wrong_inputs = proofs.clone();
+        wrong_inputs[i].0[0] += Fr::one();
+        let instances: Vec<_> = wrong_inputs
+            .iter()
+            .map(|(inputs, proof)| (inputs.as_slice(), proof))
+            .collect();
+        assert!(!verify_aggregate::<Sha256Transcript>(
+            &setup, &ipp, &pk.vk, &instances, &agg
+        ));
+    }
+}
+
+#[test]
+fn test_tampered_ipp_aggregate_fails() {
+    let setup = TrustedSetup::generate(64);
+    let ipp = IppSetup::generate(8);
+    let pk = preprocess(&setup, &pow5_circuit(0));
+    let proofs = prove_many(&setup, &pk, 3);
+    let instances: Vec<_> = proofs
+        .iter()
+        .map(|(inputs, proof)| (inputs.as_slice(), proof))
+        .collect();
+    let agg = aggregate::<Sha256Transcript>(&ipp, &pk.vk, &instances).unwrap();
+    let verify = |instances: &[_], agg: &_| {
+        verify_aggregate::<Sha256Transcript>(&setup, &ipp, &pk.vk, instances, agg)
+    };
+    assert!(verify(&instances, &agg));
+
+    assert!(!verify(&instances[..2], &agg));
+    let other = aggregate::<Sha256Transcript>(&ipp, &pk.vk, &instances[..2]).unwrap();
+    assert!(verify(&instances[..2], &other));
+
+    let mut tampered = agg.clone();
+    tampered.products.swap(0, 1);
+    assert!(!verify(&instances, &tampered));
+
+    let mut tampered = agg.clone();
+    tampered.opening_comm = other.opening_comm.clone();
+    assert!(!verify(&instances, &tampered));
+
+    let mut tampered = agg.clone();
+    tampered.mipp.point = other.mipp.point;
+    assert!(!verify(&instances, &tampered));
+
+    let mut tampered = agg.clone();
+    tampered.products.pop();
+    assert!(!verify(&instances, &tampered));
+
+    // The keys are part of what the aggregate is checked against
+    assert!(!verify_aggregate::<Sha256Transcript>(
+        &setup,
+        &IppSetup::generate(8),
+        &pk.vk,
+        &instances,
+        &agg
+    ));
+}
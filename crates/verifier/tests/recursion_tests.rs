@@ -0,0 +1,119 @@
+use ark_bls12_381::Fr;
+use ark_ff::One;
+use circuit::constraint_system::ConstraintSystem;
+use circuit::custom_gates::{BooleanGate, EdwardsAddGate, Pow5Gate};
+use circuit::lookup::LookupTable;
+use circuit::mock_prover::MockProver;
+use circuit::square_circuit::SquareCircuit;
+use proof::transcript::PoseidonTranscript;
+use proof::{Proof, ProvingKey, VerifyingKey, preprocess, prove, prove_with};
+use setup::TrustedSetup;
+use verifier::recursion::{prove_recursive, verifier_circuit, verify_recursive};
+
+fn prove_inner(setup: &TrustedSetup, cs: &ConstraintSystem) -> (VerifyingKey, Proof) {
+    let pk = preprocess(setup, cs);
+    let proof = prove_with::<PoseidonTranscript>(setup, &pk, cs);
+    (pk.vk, proof)
+}
+
+/// Boolean and pow5 gates with one public input, x⁵ = 243.
+fn gate_circuit(x: u64) -> ConstraintSystem {
+    let mut cs = ConstraintSystem::new();
+    let boolean = cs.register_gate(BooleanGate);
+    let pow5 = cs.register_gate(Pow5Gate);
+    let bit = cs.alloc(Fr::one());
+    cs.custom_gate(boolean, vec![Fr::one()], &[bit, cs.zero(), cs.zero()]);
+    let x = cs.alloc(Fr::from(x));
+    let x5 = cs.public_input(Fr::from(243u64));
+    cs.custom_gate(pow5, vec![Fr::one()], &[x, cs.zero(), x5]);
+    cs
+}
+
+#[test]
+fn test_verifier_circuit_is_satisfied() {
+    let setup = TrustedSetup::generate(64);
+    let (vk, proof) = prove_inner(&setup, &gate_circuit(3));
+
+    let (cs, deferred) = verifier_circuit(&vk, &[Fr::from(243u64)], &proof).unwrap();
+    assert_eq!(MockProver::new(&cs).verify(), Ok(()));
+    assert_eq!(cs.public_inputs().last(), Some(&deferred.zeta_omega_eval));
+
+    let (cs, _) = verifier_circuit(&vk, &[Fr::from(244u64)], &proof).unwrap();
+    assert!(MockProver::new(&cs).verify().is_err());
+}
+
+#[test]
+fn test_unsupported_circuits_are_rejected() {
+    let setup = TrustedSetup::generate(64);
+
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(2));
+    let x = cs.alloc(Fr::from(3u64));
+    cs.lookup(range, &[x]);
+    let (vk, proof) = prove_inner(&setup, &cs);
+    let err = verifier_circuit(&vk, &[], &proof).unwrap_err();
+    assert!(err.contains("lookup argument"), "{err}");
+
+    let mut cs = ConstraintSystem::new();
+    cs.register_gate(EdwardsAddGate::jubjub());
+    let (vk, proof) = prove_inner(&setup, &cs);
+    let err = verifier_circuit(&vk, &[], &proof).unwrap_err();
+    assert!(err.contains("edwards_add"), "{err}");
+}
+
+#[test]
+fn test_recursive_proof_verifies() {
+    let setup = TrustedSetup::generate(1 << 16);
+    let circuit = SquareCircuit::new();
+    let public_inputs = circuit.public_inputs();
+    let inner_cs = circuit.synthesize(&circuit.witness(5u64));
+    let (inner_vk, key_proof) = prove_inner(&setup, &inner_cs);
+
+    // The outer key only depends on the inner key, so a proof of another
+    // witness reuses it
+    let (outer_cs, _) = verifier_circuit(&inner_vk, &public_inputs, &key_proof).unwrap();
+    let outer_pk: ProvingKey = preprocess(&setup, &outer_cs);
+    let inner_cs = circuit.synthesize(&circuit.witness(-5i64));
+    let inner_proof =
+        prove_with::<PoseidonTranscript>(&setup, &preprocess(&setup, &inner_cs), &inner_cs);
+
+    let recursive =
+        prove_recursive(&setup, &outer_pk, &inner_vk, &public_inputs, &inner_proof).unwrap();
+    assert!(verify_recursive(
+        &setup,
+        &outer_pk.vk,
+        &inner_vk,
+        &public_inputs,
+        &recursive
+    ));
+    assert!(!verify_recursive(
+        &setup,
+        &outer_pk.vk,
+        &inner_vk,
+        &[Fr::from(26u64)],
+        &recursive
+    ));
+
+    let mut tampered = recursive.clone();
+    tampered.deferred.zeta_eval += Fr::one();
+    assert!(!verify_recursive(
+        &setup,
+        &outer_pk.vk,
+        &inner_vk,
+        &public_inputs,
+        &tampered
+    ));
+
+    let mut tampered = recursive.clone();
+    tampered.opening = tampered.next_opening.clone();
+    assert!(!verify_recursive(
+        &setup,
+        &outer_pk.vk,
+        &inner_vk,
+        &public_inputs,
+        &tampered
+    ));
+
+    let sha_proof = prove(&setup, &preprocess(&setup, &inner_cs), &inner_cs);
+    assert!(prove_recursive(&setup, &outer_pk, &inner_vk, &public_inputs, &sha_proof).is_err());
+}
@@ -0,0 +1,284 @@
+mod evm;
+
+use ark_bls12_381::Fr;
+use ark_ff::{Field, One};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::custom_gates::{BooleanGate, EdwardsAddGate, Pow5Gate};
+use circuit::gate::{CustomGate, Gate};
+use circuit::lookup::LookupTable;
+use circuit::square_circuit::SquareCircuit;
+use evm::Verifier;
+use proof::transcript::{Keccak256Transcript, Sha256Transcript};
+use proof::{Proof, VerifyingKey, preprocess, prove, prove_with};
+use setup::TrustedSetup;
+use verifier::solidity::{SolidityTranscript, encode_calldata, encode_proof, generate_verifier};
+use verifier::verify;
+
+fn prove_cs(setup: &TrustedSetup, cs: &ConstraintSystem) -> (VerifyingKey, Proof) {
+    let pk = preprocess(setup, cs);
+    let proof = prove(setup, &pk, cs);
+    (pk.vk, proof)
+}
+
+/// A fixed multiplier circuit's setup, key and proofs, the contracts
+/// generated for them and solc's runtime bytecode for those contracts.
+/// `regenerate.sh` there rebuilds them.
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/solidity");
+
+fn deploy(setup: &TrustedSetup, vk: &VerifyingKey) -> Verifier {
+    deploy_with::<Sha256Transcript>(setup, vk)
+}
+
+fn deploy_with<T: SolidityTranscript>(setup: &TrustedSetup, vk: &VerifyingKey) -> Verifier {
+    Verifier::deploy(&generate_verifier::<T>(setup, vk).unwrap())
+}
+
+#[test]
+fn test_square_circuit_verifies_on_chain() {
+    let setup = TrustedSetup::generate(10);
+    let circuit = SquareCircuit::new();
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+    let (vk, proof) = prove_cs(&setup, &cs);
+    let public = circuit.public_inputs();
+
+    let mut contract = deploy(&setup, &vk);
+    let (valid, gas) = contract.call(encode_calldata(&proof, &public));
+    assert!(valid);
+    assert!(gas < 1_000_000, "{gas}");
+    assert!(!contract.call(encode_calldata(&proof, &[Fr::from(26u64)])).0);
+    assert!(!contract.call(encode_calldata(&proof, &[])).0);
+
+    let mut tampered = proof.clone();
+    tampered.z_eval += Fr::one();
+    assert!(!verify(&setup, &vk, &public, &tampered));
+    assert!(!contract.call(encode_calldata(&tampered, &public)).0);
+
+    let mut tampered = proof.clone();
+    tampered.t_comms.swap(0, 1);
+    assert!(!contract.call(encode_calldata(&tampered, &public)).0);
+
+    let mut tampered = proof;
+    tampered.opening = tampered.next_opening.clone();
+    assert!(!contract.call(encode_calldata(&tampered, &public)).0);
+}
+
+#[test]
+fn test_custom_gates_verify_on_chain() {
+    let setup = TrustedSetup::generate(16);
+    let d = -(Fr::from(10240u64) * Fr::from(10241u64).inverse().unwrap());
+    let curve = EdwardsAddGate::new(-Fr::one(), d);
+    let y = Fr::from(3u64);
+    let x = ((y.square() - Fr::one()) * (d * y.square() + Fr::one()).inverse().unwrap())
+        .sqrt()
+        .unwrap();
+    let (x3, y3) = curve.add((x, y), (x, y));
+
+    let mut cs = ConstraintSystem::new();
+    let boolean = cs.register_gate(BooleanGate);
+    let pow5 = cs.register_gate(Pow5Gate);
+    let ecc = cs.register_gate(curve);
+    let bit = cs.alloc(Fr::one());
+    cs.custom_gate(boolean, vec![Fr::one()], &[bit, cs.zero(), cs.zero()]);
+    let a = cs.alloc(Fr::from(3u64));
+    let a5 = cs.public_input(Fr::from(243u64));
+    cs.custom_gate(pow5, vec![Fr::one()], &[a, cs.zero(), a5]);
+    let px = cs.alloc(x);
+    let py = cs.alloc(y);
+    let qx = cs.public_input(x3);
+    let qy = cs.public_input(y3);
+    cs.custom_gate(ecc, vec![Fr::one()], &[px, py, px]);
+    cs.add_gate(Gate::disabled(), &[py, qx, qy]);
+    let (vk, proof) = prove_cs(&setup, &cs);
+
+    let mut contract = deploy(&setup, &vk);
+    let public = [Fr::from(243u64), x3, y3];
+    assert!(contract.call(encode_calldata(&proof, &public)).0);
+    assert!(
+        !contract
+            .call(encode_calldata(&proof, &[Fr::from(243u64), y3, x3]))
+            .0
+    );
+}
+
+#[test]
+fn test_lookup_circuit_verifies_on_chain() {
+    let setup = TrustedSetup::generate(32);
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(2));
+    let xor = cs.register_table(LookupTable::xor(2));
+    let a = cs.public_input(Fr::from(2u64));
+    let b = cs.alloc(Fr::from(3u64));
+    let c = cs.alloc(Fr::from(1u64));
+    cs.lookup(xor, &[a, b, c]);
+    cs.lookup(range, &[c]);
+    let (vk, proof) = prove_cs(&setup, &cs);
+
+    let mut contract = deploy(&setup, &vk);
+    assert!(contract.call(encode_calldata(&proof, &[Fr::from(2u64)])).0);
+    assert!(!contract.call(encode_calldata(&proof, &[Fr::from(1u64)])).0);
+
+    let mut tampered = proof.clone();
+    tampered.lookup.as_mut().unwrap().phi_next_eval += Fr::one();
+    assert!(
+        !contract
+            .call(encode_calldata(&tampered, &[Fr::from(2u64)]))
+            .0
+    );
+}
+
+#[test]
+fn test_keccak_transcript_verifies_on_chain() {
+    let setup = TrustedSetup::generate(32);
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(4));
+    let pow5 = cs.register_gate(Pow5Gate);
+    let x = cs.alloc(Fr::from(3u64));
+    let x5 = cs.public_input(Fr::from(243u64));
+    cs.custom_gate(pow5, vec![Fr::one()], &[x, cs.zero(), x5]);
+    cs.lookup(range, &[x]);
+    let pk = preprocess(&setup, &cs);
+    let keccak_proof = prove_with::<Keccak256Transcript>(&setup, &pk, &cs);
+    let sha_proof = prove(&setup, &pk, &cs);
+    let public = [Fr::from(243u64)];
+
+    let mut keccak = deploy_with::<Keccak256Transcript>(&setup, &pk.vk);
+    let mut sha = deploy(&setup, &pk.vk);
+    let (valid, keccak_gas) = keccak.call(encode_calldata(&keccak_proof, &public));
+    assert!(valid);
+    let (valid, sha_gas) = sha.call(encode_calldata(&sha_proof, &public));
+    assert!(valid);
+    assert!(keccak_gas < sha_gas, "{keccak_gas} >= {sha_gas}");
+
+    // Each contract only accepts its own transcript
+    assert!(!keccak.call(encode_calldata(&sha_proof, &public)).0);
+    assert!(!sha.call(encode_calldata(&keccak_proof, &public)).0);
+    assert!(
+        !keccak
+            .call(encode_calldata(&keccak_proof, &[Fr::from(244u64)]))
+            .0
+    );
+}
+
+#[test]
+fn test_calldata_rejects_malformed_proofs() {
+    let setup = TrustedSetup::generate(10);
+    let circuit = SquareCircuit::new();
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+    let (vk, proof) = prove_cs(&setup, &cs);
+    let public = circuit.public_inputs();
+    let mut contract = deploy(&setup, &vk);
+
+    // Offsets of the two dynamic arguments, then the proof length
+    let calldata = encode_calldata(&proof, &public);
+    assert_eq!(
+        u64::from_be_bytes(calldata[4 + 88..4 + 96].try_into().unwrap()) as usize,
+        encode_proof(&proof).len()
+    );
+
+    // A scalar above the field modulus
+    let mut bad = calldata.clone();
+    let first_eval = 4 + 96 + proof.wire_comms.len() * 128 + (1 + proof.t_comms.len()) * 128;
+    bad[first_eval..first_eval + 32].fill(0xff);
+    assert!(!contract.call(bad).0);
+
+    // A point off the curve
+    let mut bad = calldata.clone();
+    bad[4 + 96 + 127] ^= 1;
+    assert!(!contract.call(bad).0);
+
+    // Truncated proof
+    let mut short = proof.clone();
+    short.t_evals.pop();
+    assert!(!contract.call(encode_calldata(&short, &public)).0);
+}
+
+#[test]
+fn test_unknown_gate_is_rejected() {
+    #[derive(Debug)]
+    struct Unknown;
+
+    impl CustomGate for Unknown {
+        fn name(&self) -> &'static str {
+            "unknown"
+        }
+
+        fn selectors(&self) -> &'static [&'static str] {
+            &["q_unknown"]
+        }
+
+        fn degree(&self) -> usize {
+            2
+        }
+
+        fn evaluate(&self, selectors: &[Fr], wires: &[Fr], _next: &[Fr]) -> Vec<Fr> {
+            vec![selectors[0] * wires[0]]
+        }
+    }
+
+    let setup = TrustedSetup::generate(10);
+    let mut cs = ConstraintSystem::new();
+    let gate = cs.register_gate(Unknown);
+    let zero = cs.zero();
+    cs.custom_gate(gate, vec![Fr::one()], &[zero, zero, zero]);
+    let pk = preprocess(&setup, &cs);
+
+    let error = generate_verifier::<Sha256Transcript>(&setup, &pk.vk).unwrap_err();
+    assert!(error.contains("unknown"), "{error}");
+}
+
+/// Setup, key, public inputs and the proof for one transcript from the
+/// Solidity fixtures.
+fn solidity_fixture(transcript: &str) -> (TrustedSetup, VerifyingKey, Vec<Fr>, Proof) {
+    let setup = TrustedSetup::load(format!("{FIXTURES}/srs.bin")).unwrap();
+    let vk = VerifyingKey::load(format!("{FIXTURES}/multiplier.vk")).unwrap();
+    let proof = Proof::load(format!("{FIXTURES}/{transcript}.proof")).unwrap();
+    // out, then a, as in public.json
+    (setup, vk, vec![Fr::from(21u64), Fr::from(3u64)], proof)
+}
+
+/// Checks that the committed contract is what the generator emits today
+/// and that it accepts the committed proof through [`Verifier::deploy`].
+fn check_fixture_sources<T: SolidityTranscript>(transcript: &str) {
+    let (setup, vk, public, proof) = solidity_fixture(transcript);
+    let source = std::fs::read_to_string(format!("{FIXTURES}/{transcript}.sol")).unwrap();
+    assert_eq!(generate_verifier::<T>(&setup, &vk).unwrap(), source);
+    assert!(verifier::verify_with::<T>(&setup, &vk, &public, &proof));
+
+    let mut contract = Verifier::deploy(&source);
+    assert!(contract.call(encode_calldata(&proof, &public)).0);
+}
+
+/// Runs solc's runtime bytecode for the committed contract on the
+/// committed proof.
+fn check_solc_bytecode(transcript: &str) {
+    let (_, _, public, proof) = solidity_fixture(transcript);
+    let hex = std::fs::read_to_string(format!("{FIXTURES}/{transcript}.bin-runtime")).unwrap();
+    let hex = hex.trim().trim_start_matches("0x");
+    let runtime: Vec<u8> = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect();
+
+    let mut contract = Verifier::from_runtime(&runtime);
+    let (valid, gas) = contract.call(encode_calldata(&proof, &public));
+    assert!(valid, "{transcript}");
+    assert!(gas < 1_000_000, "{transcript}: {gas}");
+    let wrong = [Fr::from(22u64), Fr::from(3u64)];
+    assert!(!contract.call(encode_calldata(&proof, &wrong)).0);
+    let mut tampered = proof;
+    tampered.z_eval += Fr::one();
+    assert!(!contract.call(encode_calldata(&tampered, &public)).0);
+}
+
+#[test]
+fn test_solidity_fixtures_match_generator() {
+    check_fixture_sources::<Sha256Transcript>("sha256");
+    check_fixture_sources::<Keccak256Transcript>("keccak256");
+}
+
+#[test]
+#[ignore = "needs solc's runtime bytecode in tests/fixtures/solidity; run regenerate.sh there"]
+fn test_solc_bytecode_verifies() {
+    check_solc_bytecode("sha256");
+    check_solc_bytecode("keccak256");
+}
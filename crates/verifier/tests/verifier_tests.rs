@@ -1,64 +1,650 @@
-use ark_bls12_381::Fr;
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One, PrimeField};
+use circuit::constraint_system::ConstraintSystem;
+use circuit::custom_gates::{BooleanGate, EdwardsAddGate, Pow5Gate};
+use circuit::gadgets::arithmetic::assert_equal;
+use circuit::gadgets::comparison::assert_less_than;
+use circuit::gadgets::merkle::{MerklePathVars, assert_membership};
+use circuit::gadgets::poseidon;
+use circuit::gadgets::range::range_check_lookup;
+use circuit::gate::Gate;
+use circuit::lookup::LookupTable;
+use circuit::merkle::MerkleTree;
+use circuit::optimizer::optimize;
+use circuit::poseidon::hash_two;
+use circuit::r1cs::{R1cs, load_wtns};
 use circuit::square_circuit::SquareCircuit;
 use circuit::witness::Witness;
-use proof::prove;
+use proof::transcript::{Keccak256Transcript, PoseidonTranscript, Sha256Transcript};
+use proof::{Proof, VerifyingKey, preprocess, prove, prove_with};
 use setup::TrustedSetup;
-use verifier::verify;
+use verifier::{verify, verify_with};
+
+fn prove_square(setup: &TrustedSetup, witness: &Witness) -> (VerifyingKey, Proof) {
+    let circuit = SquareCircuit::new();
+    let cs = circuit.synthesize(witness);
+    let pk = preprocess(setup, &cs);
+    let proof = prove(setup, &pk, &cs);
+    (pk.vk, proof)
+}
+
+fn prove_cs(setup: &TrustedSetup, cs: &ConstraintSystem) -> (VerifyingKey, Proof) {
+    let pk = preprocess(setup, cs);
+    let proof = prove(setup, &pk, cs);
+    (pk.vk, proof)
+}
 
 #[test]
 fn test_valid_proof_verifies() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(5);
+    let witness = circuit.witness(5u64);
 
-    let proof = prove(&setup, &circuit, &witness);
-    assert!(verify(&setup, &circuit, &proof));
+    let (vk, proof) = prove_square(&setup, &witness);
+    assert!(verify(&setup, &vk, &circuit.public_inputs(), &proof));
 }
 
 #[test]
 fn test_valid_proof_negative_root() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let circuit = SquareCircuit::new();
+    let witness = circuit.witness(-5i64);
+
+    let (vk, proof) = prove_square(&setup, &witness);
+    assert!(verify(&setup, &vk, &circuit.public_inputs(), &proof));
+}
+
+#[test]
+fn test_keccak_transcript_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
     let circuit = SquareCircuit::new();
-    let witness = Witness::new_signed(-5);
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+    let pk = preprocess(&setup, &cs);
+    let public_inputs = circuit.public_inputs();
 
-    let proof = prove(&setup, &circuit, &witness);
-    assert!(verify(&setup, &circuit, &proof));
+    let proof = prove_with::<Keccak256Transcript>(&setup, &pk, &cs);
+    assert!(verify_with::<Keccak256Transcript>(
+        &setup,
+        &pk.vk,
+        &public_inputs,
+        &proof
+    ));
+    assert!(!verify_with::<Sha256Transcript>(
+        &setup,
+        &pk.vk,
+        &public_inputs,
+        &proof
+    ));
+    assert!(!verify_with::<Keccak256Transcript>(
+        &setup,
+        &pk.vk,
+        &[Fr::from(26u64)],
+        &proof
+    ));
+}
+
+#[test]
+fn test_poseidon_transcript_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let circuit = SquareCircuit::new();
+    let cs = circuit.synthesize(&circuit.witness(5u64));
+    let pk = preprocess(&setup, &cs);
+    let public_inputs = circuit.public_inputs();
+
+    let proof = prove_with::<PoseidonTranscript>(&setup, &pk, &cs);
+    assert!(verify_with::<PoseidonTranscript>(
+        &setup,
+        &pk.vk,
+        &public_inputs,
+        &proof
+    ));
+    assert!(!verify(&setup, &pk.vk, &public_inputs, &proof));
+    assert!(!verify_with::<PoseidonTranscript>(
+        &setup,
+        &pk.vk,
+        &[Fr::from(26u64)],
+        &proof
+    ));
 }
 
 #[test]
 fn test_invalid_witness_fails_verification() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(6);
+    let witness = circuit.witness(6u64);
 
-    let proof = prove(&setup, &circuit, &witness);
-    assert!(!verify(&setup, &circuit, &proof));
+    let (vk, proof) = prove_square(&setup, &witness);
+    assert!(!verify(&setup, &vk, &circuit.public_inputs(), &proof));
 }
 
 #[test]
 fn test_tampered_evaluation_fails() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+    let circuit = SquareCircuit::new();
+    let witness = circuit.witness(5u64);
+
+    let (vk, mut proof) = prove_square(&setup, &witness);
+    proof.wire_evals[0] = Fr::from(999u64);
+
+    assert!(!verify(&setup, &vk, &circuit.public_inputs(), &proof));
+}
+
+#[test]
+fn test_tampered_quotient_fails() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(5);
+    let witness = circuit.witness(5u64);
 
-    let mut proof = prove(&setup, &circuit, &witness);
-    proof.a_eval = Fr::from(999u64);
+    let (vk, mut proof) = prove_square(&setup, &witness);
+    proof.t_comms.swap(0, 1);
 
-    assert!(!verify(&setup, &circuit, &proof));
+    assert!(!verify(&setup, &vk, &circuit.public_inputs(), &proof));
 }
 
 #[test]
 fn test_tampered_public_output_fails() {
-    let setup = TrustedSetup::generate(10);
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
     let circuit = SquareCircuit::new();
-    let witness = Witness::new(5);
+    let witness = circuit.witness(5u64);
 
-    let proof = prove(&setup, &circuit, &witness);
+    let (vk, proof) = prove_square(&setup, &witness);
 
     let wrong_circuit = SquareCircuit {
         gate: circuit.gate.clone(),
         public_output: Fr::from(100u64),
     };
 
-    assert!(!verify(&setup, &wrong_circuit, &proof));
+    assert!(!verify(&setup, &vk, &wrong_circuit.public_inputs(), &proof));
+}
+
+#[test]
+fn test_broken_copy_constraint_fails() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    // x + 1 = y and y·y = 16, with y appearing on both gates
+    let square_of_sum = |y_on_square: Option<Fr>| {
+        let mut cs = ConstraintSystem::new();
+        let one = cs.constant(Fr::one());
+        let x = cs.alloc(Fr::from(3u64));
+        let y = cs.add(x, one);
+        let out = cs.public_input(Fr::from(16u64));
+        let y = y_on_square.map_or(y, |value| cs.alloc(value));
+        cs.add_gate(Gate::multiplication(), &[y, y, out]);
+        cs
+    };
+
+    let honest = square_of_sum(None);
+    let pk = preprocess(&setup, &honest);
+    let proof = prove(&setup, &pk, &honest);
+    assert!(verify(&setup, &pk.vk, &[Fr::from(16u64)], &proof));
+
+    // Every gate holds with y = 4 on the add and y = -4 on the square,
+    // but the copy constraint between them does not
+    let cheating = square_of_sum(Some(-Fr::from(4u64)));
+    assert!(cheating.is_satisfied());
+    let proof = prove(&setup, &pk, &cheating);
+    assert!(!verify(&setup, &pk.vk, &[Fr::from(16u64)], &proof));
+}
+
+#[test]
+fn test_boolean_and_pow5_gates_verify() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let mut cs = ConstraintSystem::new();
+    let boolean = cs.register_gate(BooleanGate);
+    let pow5 = cs.register_gate(Pow5Gate);
+
+    let bit = cs.alloc(Fr::one());
+    cs.custom_gate(boolean, vec![Fr::one()], &[bit, cs.zero(), cs.zero()]);
+
+    let x = cs.alloc(Fr::from(3u64));
+    let x5 = cs.public_input(Fr::from(243u64));
+    cs.custom_gate(pow5, vec![Fr::one()], &[x, cs.zero(), x5]);
+
+    let (vk, proof) = prove_cs(&setup, &cs);
+    assert_eq!(proof.custom_selector_evals.len(), 2);
+    assert!(verify(&setup, &vk, &[Fr::from(243u64)], &proof));
+    assert!(!verify(&setup, &vk, &[Fr::from(244u64)], &proof));
+}
+
+#[test]
+fn test_unsatisfied_custom_gate_fails() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let mut cs = ConstraintSystem::new();
+    let boolean = cs.register_gate(BooleanGate);
+    let not_bit = cs.alloc(Fr::from(2u64));
+    cs.custom_gate(boolean, vec![Fr::one()], &[not_bit, cs.zero(), cs.zero()]);
+
+    let (vk, proof) = prove_cs(&setup, &cs);
+    assert!(!verify(&setup, &vk, &[], &proof));
+}
+
+#[test]
+fn test_edwards_add_gate_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let d = -(Fr::from(10240u64) * Fr::from(10241u64).inverse().unwrap());
+    let curve = EdwardsAddGate::new(-Fr::one(), d);
+
+    // Doubling the point (x, y) with y = 3
+    let y = Fr::from(3u64);
+    let x = ((y.square() - Fr::one()) * (d * y.square() + Fr::one()).inverse().unwrap())
+        .sqrt()
+        .unwrap();
+    let (x3, y3) = curve.add((x, y), (x, y));
+
+    let mut cs = ConstraintSystem::new();
+    let ecc = cs.register_gate(curve);
+    let px = cs.alloc(x);
+    let py = cs.alloc(y);
+    let qx = cs.public_input(x3);
+    let qy = cs.public_input(y3);
+    cs.custom_gate(ecc, vec![Fr::one()], &[px, py, px]);
+    cs.add_gate(Gate::disabled(), &[py, qx, qy]);
+
+    let (vk, proof) = prove_cs(&setup, &cs);
+    assert!(verify(&setup, &vk, &[x3, y3], &proof));
+    assert!(!verify(&setup, &vk, &[y3, x3], &proof));
+}
+
+/// Sums `values` in one wide row, then squares the sum.
+fn wide_sum_then_square(
+    num_wires: usize,
+    values: &[u64],
+    square_input: Option<Fr>,
+) -> ConstraintSystem {
+    let mut cs = ConstraintSystem::with_wires(num_wires);
+    let inputs: Vec<_> = values.iter().map(|&v| cs.alloc(Fr::from(v))).collect();
+    let total: u64 = values.iter().sum();
+    let sum = cs.alloc(Fr::from(total));
+
+    let mut coeffs = vec![Fr::one(); values.len()];
+    coeffs.push(-Fr::one());
+    let mut wires = inputs.clone();
+    wires.push(sum);
+    cs.add_gate(Gate::linear(&coeffs, Fr::from(0u64)), &wires);
+
+    let out = cs.public_input(Fr::from(total * total));
+    let sum = square_input.map_or(sum, |value| cs.alloc(value));
+    cs.add_gate(Gate::multiplication(), &[sum, sum, out]);
+    cs
+}
+
+#[test]
+fn test_four_wire_circuit_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let cs = wide_sum_then_square(4, &[1, 2, 3], None);
+    assert!(cs.is_satisfied());
+
+    let (vk, proof) = prove_cs(&setup, &cs);
+    assert_eq!(proof.wire_comms.len(), 4);
+    assert!(verify(&setup, &vk, &[Fr::from(36u64)], &proof));
+    assert!(!verify(&setup, &vk, &[Fr::from(37u64)], &proof));
+}
+
+#[test]
+fn test_five_wire_circuit_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    let cs = wide_sum_then_square(5, &[1, 2, 3, 4], None);
+    let (vk, proof) = prove_cs(&setup, &cs);
+    assert!(verify(&setup, &vk, &[Fr::from(100u64)], &proof));
+}
+
+#[test]
+fn test_five_wire_broken_copy_fails() {
+    let setup = TrustedSetup::<Bls12_381>::generate(10);
+
+    // The sum on wire e is copied into the square; use -10 there instead
+    let honest = wide_sum_then_square(5, &[1, 2, 3, 4], None);
+    let pk = preprocess(&setup, &honest);
+
+    let cheating = wide_sum_then_square(5, &[1, 2, 3, 4], Some(-Fr::from(10u64)));
+    assert!(cheating.is_satisfied());
+    let proof = prove(&setup, &pk, &cheating);
+    assert!(!verify(&setup, &pk.vk, &[Fr::from(100u64)], &proof));
+}
+
+fn range_checked(setup: &TrustedSetup, values: &[u64]) -> (VerifyingKey, Proof) {
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(4));
+    for &value in values {
+        let var = cs.alloc(Fr::from(value));
+        cs.lookup(range, &[var]);
+    }
+    prove_cs(setup, &cs)
+}
+
+#[test]
+fn test_range_lookup_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(32);
+    let (vk, proof) = range_checked(&setup, &[0, 3, 3, 15]);
+
+    assert!(verify(&setup, &vk, &[], &proof));
+}
+
+#[test]
+fn test_out_of_range_lookup_fails() {
+    let setup = TrustedSetup::<Bls12_381>::generate(32);
+    let (vk, proof) = range_checked(&setup, &[3, 16]);
+
+    assert!(!verify(&setup, &vk, &[], &proof));
+}
+
+#[test]
+fn test_multi_table_lookup_with_gates_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(32);
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(2));
+    let xor = cs.register_table(LookupTable::xor(2));
+
+    let a = cs.public_input(Fr::from(2u64));
+    let b = cs.alloc(Fr::from(3u64));
+    let c = cs.alloc(Fr::from(1u64));
+    cs.lookup(xor, &[a, b, c]);
+    cs.lookup(range, &[c]);
+    let sum = cs.add(a, c);
+    cs.lookup(range, &[sum]);
+
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+    assert!(verify(&setup, &pk.vk, &[Fr::from(2u64)], &proof));
+    assert!(!verify(&setup, &pk.vk, &[Fr::from(1u64)], &proof));
+}
+
+#[test]
+fn test_tampered_lookup_fails() {
+    let setup = TrustedSetup::<Bls12_381>::generate(32);
+    let (vk, proof) = range_checked(&setup, &[1, 2]);
+
+    let mut tampered = proof.clone();
+    tampered.lookup.as_mut().unwrap().m_eval += Fr::one();
+    assert!(!verify(&setup, &vk, &[], &tampered));
+
+    let mut tampered = proof.clone();
+    tampered.lookup.as_mut().unwrap().phi_next_eval += Fr::one();
+    assert!(!verify(&setup, &vk, &[], &tampered));
+
+    let mut stripped = proof;
+    stripped.lookup = None;
+    assert!(!verify(&setup, &vk, &[], &stripped));
+}
+
+#[test]
+fn test_gadget_circuit_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(64);
+    let mut cs = ConstraintSystem::new();
+    let table = cs.register_table(LookupTable::range(4));
+
+    // Public bound, private value below it, both range checked
+    let bound = cs.public_input(Fr::from(200u64));
+    let x = cs.alloc(Fr::from(123u64));
+    range_check_lookup(&mut cs, table, 4, bound, 2);
+    range_check_lookup(&mut cs, table, 4, x, 2);
+    assert_less_than(&mut cs, x, bound, 8);
+
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+    assert!(verify(&setup, &pk.vk, &[Fr::from(200u64)], &proof));
+    assert!(!verify(&setup, &pk.vk, &[Fr::from(100u64)], &proof));
+}
+
+#[test]
+fn test_r1cs_import_verifies() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/../circuit/tests/fixtures");
+    let r1cs = R1cs::load(format!("{fixtures}/multiplier.r1cs")).unwrap();
+    let witness = load_wtns(format!("{fixtures}/multiplier.wtns")).unwrap();
+
+    let setup = TrustedSetup::<Bls12_381>::generate(64);
+    let cs = r1cs.compile(3).synthesize(&witness).unwrap();
+    let (vk, proof) = prove_cs(&setup, &cs);
+
+    // Public signals: out, then a
+    assert!(verify(&setup, &vk, &[Fr::from(21u64), Fr::from(3u64)], &proof));
+    assert!(!verify(&setup, &vk, &[Fr::from(21u64), Fr::from(4u64)], &proof));
+}
+
+#[test]
+fn test_circom_bn254_import_verifies() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/../circuit/tests/fixtures");
+    let r1cs = R1cs::<ark_bn254::Fr>::load(format!("{fixtures}/circuit2.r1cs")).unwrap();
+    let witness = load_wtns(format!("{fixtures}/circuit2.wtns")).unwrap();
+
+    let cs = r1cs.compile(3).synthesize(&witness).unwrap();
+    let setup = TrustedSetup::<Bn254>::generate(1024);
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+
+    // The only public signal is the output c = a·b
+    assert!(verify(&setup, &pk.vk, &[ark_bn254::Fr::from(33u64)], &proof));
+    assert!(!verify(&setup, &pk.vk, &[ark_bn254::Fr::from(34u64)], &proof));
+}
+
+#[test]
+fn test_optimized_circuit_verifies() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/../circuit/tests/fixtures");
+    let r1cs = R1cs::load(format!("{fixtures}/multiplier.r1cs")).unwrap();
+    let witness = load_wtns(format!("{fixtures}/multiplier.wtns")).unwrap();
+
+    let setup = TrustedSetup::<Bls12_381>::generate(64);
+    let mut cs = r1cs.compile(3).synthesize(&witness).unwrap();
+    let rows = cs.rows().len();
+    optimize(&mut cs);
+    assert!(cs.rows().len() < rows);
+    let (vk, proof) = prove_cs(&setup, &cs);
+
+    assert!(verify(&setup, &vk, &[Fr::from(21u64), Fr::from(3u64)], &proof));
+    assert!(!verify(&setup, &vk, &[Fr::from(22u64), Fr::from(3u64)], &proof));
+}
+
+#[test]
+fn test_poseidon_preimage_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(512);
+    let (left, right) = (Fr::from(3u64), Fr::from(4u64));
+    let digest = hash_two(left, right);
+
+    let mut cs = ConstraintSystem::with_wires(4);
+    let expected = cs.public_input(digest);
+    let a = cs.alloc(left);
+    let b = cs.alloc(right);
+    let out = poseidon::hash_two(&mut cs, a, b);
+    assert_equal(&mut cs, out, expected);
+
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+    assert!(verify(&setup, &pk.vk, &[digest], &proof));
+    assert!(!verify(&setup, &pk.vk, &[digest + Fr::one()], &proof));
+}
+
+#[test]
+fn test_merkle_membership_verifies() {
+    let setup = TrustedSetup::<Bls12_381>::generate(1024);
+    let leaves: Vec<Fr> = (1..=4u64).map(Fr::from).collect();
+    let tree = MerkleTree::new(2, &leaves);
+
+    let mut cs = ConstraintSystem::with_wires(4);
+    let root = cs.public_input(tree.root());
+    let leaf = cs.alloc(tree.leaf(2));
+    let path = MerklePathVars::alloc(&mut cs, &tree.path(2));
+    assert_membership(&mut cs, root, leaf, &path);
+
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+    assert!(verify(&setup, &pk.vk, &[tree.root()], &proof));
+
+    let other_root = MerkleTree::new(2, &leaves[..3]).root();
+    assert!(!verify(&setup, &pk.vk, &[other_root], &proof));
+}
+
+#[test]
+fn test_serialized_proof_and_key_verify() {
+    let setup = TrustedSetup::<Bls12_381>::generate(64);
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(4));
+    let curve = cs.register_gate(EdwardsAddGate::new(Fr::from(5u64), Fr::from(7u64)));
+    let pow5 = cs.register_gate(Pow5Gate);
+    let x = cs.alloc(Fr::from(3u64));
+    let x5 = cs.public_input(Fr::from(243u64));
+    cs.custom_gate(pow5, vec![Fr::one()], &[x, cs.zero(), x5]);
+    cs.lookup(range, &[x]);
+    let (vk, proof) = prove_cs(&setup, &cs);
+
+    let mut vk_bytes = Vec::new();
+    vk.write(&mut vk_bytes).unwrap();
+    let mut proof_bytes = Vec::new();
+    proof.write(&mut proof_bytes).unwrap();
+    let vk = VerifyingKey::read(vk_bytes.as_slice()).unwrap();
+    let proof = Proof::read(proof_bytes.as_slice()).unwrap();
+
+    let params = vk.custom_gates[curve.index()].params();
+    assert_eq!(params, [("a", Fr::from(5u64)), ("d", Fr::from(7u64))]);
+    assert!(verify(&setup, &vk, &[Fr::from(243u64)], &proof));
+    assert!(!verify(&setup, &vk, &[Fr::from(244u64)], &proof));
+
+    assert!(Proof::<Bls12_381>::read(&proof_bytes[..proof_bytes.len() - 1]).is_err());
+    assert!(VerifyingKey::<Bls12_381>::read(&vk_bytes[..vk_bytes.len() - 1]).is_err());
+    assert!(VerifyingKey::<Bls12_381>::read(proof_bytes.as_slice()).is_err());
+}
+
+#[test]
+fn test_key_with_lookup_wider_than_rows_is_rejected() {
+    let setup = TrustedSetup::<Bls12_381>::generate(64);
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(4));
+    let x = cs.alloc(Fr::from(3u64));
+    cs.lookup(range, &[x]);
+    let (vk, _) = prove_cs(&setup, &cs);
+
+    for width in [0, vk.num_wires + 1] {
+        let mut wide = vk.clone();
+        let lookup = wide.lookup.as_mut().unwrap();
+        lookup.width = width;
+        lookup.table_comms = vec![lookup.table_comms[0].clone(); width];
+        let mut bytes = Vec::new();
+        wide.write(&mut bytes).unwrap();
+        let error = VerifyingKey::<Bls12_381>::read(bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "width {width}");
+    }
+}
+
+#[test]
+fn test_json_proof_and_key_verify() {
+    let setup = TrustedSetup::<Bls12_381>::generate(64);
+    let mut cs = ConstraintSystem::new();
+    let range = cs.register_table(LookupTable::range(4));
+    cs.register_gate(EdwardsAddGate::new(Fr::from(5u64), Fr::from(7u64)));
+    let x = cs.alloc(Fr::from(3u64));
+    let x2 = cs.public_input(Fr::from(9u64));
+    cs.add_gate(Gate::multiplication(), &[x, x, x2]);
+    cs.lookup(range, &[x]);
+    let (vk, proof) = prove_cs(&setup, &cs);
+
+    let vk = VerifyingKey::from_json(&vk.to_json()).unwrap();
+    let proof = Proof::from_json(&proof.to_json()).unwrap();
+    assert!(verify(&setup, &vk, &[Fr::from(9u64)], &proof));
+    assert!(!verify(&setup, &vk, &[Fr::from(10u64)], &proof));
+
+    let dir = std::env::temp_dir().join(format!("plonk-json-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    vk.save(dir.join("circuit.json")).unwrap();
+    proof.save(dir.join("proof.json")).unwrap();
+    let text = std::fs::read_to_string(dir.join("circuit.json")).unwrap();
+    assert!(text.contains("\"edwards_add\""));
+    let vk = VerifyingKey::load(dir.join("circuit.json")).unwrap();
+    let proof = Proof::load(dir.join("proof.json")).unwrap();
+    assert!(verify(&setup, &vk, &[Fr::from(9u64)], &proof));
+
+    assert!(VerifyingKey::<Bls12_381>::from_json(&proof.to_json()).is_err());
+    let wide = text.replace("\"num_wires\": 3", "\"num_wires\": 4");
+    assert!(VerifyingKey::<Bls12_381>::from_json(&wide).is_err());
+}
+
+/// Custom gates, a range lookup and a public input, over any curve's scalar field.
+fn gates_and_lookup<F: PrimeField>() -> ConstraintSystem<F> {
+    let mut cs = ConstraintSystem::new();
+    let boolean = cs.register_gate(BooleanGate);
+    let pow5 = cs.register_gate(Pow5Gate);
+    let range = cs.register_table(LookupTable::range(4));
+
+    let bit = cs.alloc(F::one());
+    cs.custom_gate(boolean, vec![F::one()], &[bit, cs.zero(), cs.zero()]);
+    let x = cs.alloc(F::from(3u64));
+    let x5 = cs.public_input(F::from(243u64));
+    cs.custom_gate(pow5, vec![F::one()], &[x, cs.zero(), x5]);
+    cs.lookup(range, &[x]);
+    cs
+}
+
+fn check_curve<E: Pairing>() {
+    let setup = TrustedSetup::<E>::generate(32);
+    let cs = gates_and_lookup::<E::ScalarField>();
+    let pk = preprocess(&setup, &cs);
+    let public_inputs = [E::ScalarField::from(243u64)];
+    let wrong_inputs = [E::ScalarField::from(244u64)];
+
+    let proof = prove(&setup, &pk, &cs);
+    assert!(verify(&setup, &pk.vk, &public_inputs, &proof));
+    assert!(!verify(&setup, &pk.vk, &wrong_inputs, &proof));
+
+    let proof = prove_with::<Keccak256Transcript<E>>(&setup, &pk, &cs);
+    assert!(verify_with::<Keccak256Transcript<E>>(&setup, &pk.vk, &public_inputs, &proof));
+    assert!(!verify_with::<Sha256Transcript<E>>(&setup, &pk.vk, &public_inputs, &proof));
+
+    let mut vk_bytes = Vec::new();
+    pk.vk.write(&mut vk_bytes).unwrap();
+    let mut proof_bytes = Vec::new();
+    proof.write(&mut proof_bytes).unwrap();
+    let vk = VerifyingKey::<E>::read(vk_bytes.as_slice()).unwrap();
+    let proof = Proof::<E>::read(proof_bytes.as_slice()).unwrap();
+    assert!(verify_with::<Keccak256Transcript<E>>(&setup, &vk, &public_inputs, &proof));
+}
+
+#[test]
+fn test_bls12_381_end_to_end() {
+    check_curve::<Bls12_381>();
+}
+
+#[test]
+fn test_bn254_end_to_end() {
+    check_curve::<Bn254>();
+}
+
+#[test]
+fn test_bn254_json_round_trip() {
+    let setup = TrustedSetup::<Bn254>::generate(32);
+    let cs = gates_and_lookup();
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+
+    let json = proof.to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["curve"], "bn254");
+    assert_eq!(value["z"][0].as_str().unwrap().len(), 2 + 64);
+    assert_eq!(value["z_eval"].as_str().unwrap().len(), 2 + 64);
+
+    let vk = VerifyingKey::<Bn254>::from_json(&pk.vk.to_json()).unwrap();
+    let proof = Proof::<Bn254>::from_json(&json).unwrap();
+    assert_eq!(proof.to_json(), json);
+    assert_eq!(vk.to_json(), pk.vk.to_json());
+    assert!(verify(&setup, &vk, &[ark_bn254::Fr::from(243u64)], &proof));
+
+    // Documents name their curve, so a BN254 proof is not read as BLS12-381
+    assert!(Proof::<Bls12_381>::from_json(&json).is_err());
+}
+
+#[test]
+fn test_bn254_proof_rejected_by_other_setup() {
+    let cs = gates_and_lookup();
+    let setup = TrustedSetup::<Bn254>::generate(32);
+    let other = TrustedSetup::<Bn254>::generate(32);
+    let pk = preprocess(&setup, &cs);
+    let proof = prove(&setup, &pk, &cs);
+
+    let public_inputs = [ark_bn254::Fr::from(243u64)];
+    assert!(verify(&setup, &pk.vk, &public_inputs, &proof));
+    assert!(!verify(&other, &pk.vk, &public_inputs, &proof));
 }